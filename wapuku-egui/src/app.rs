@@ -10,9 +10,9 @@ use egui::{Align, Align2, Color32, emath, epaint, Frame, Layout, Pos2, Rect, Str
 use log::{debug, error};
 use rfd;
 use wapuku_model::data_type::WapukuDataType;
-use wapuku_model::model::{ColumnsPlot, Corrs, DataLump, Filter, Histogram, SummaryColumn, SummaryColumnType, WaFrame, WaModelId};
+use wapuku_model::model::{Bandwidth, BoxPlot, ColumnsPlot, ConditionType, CorrMethod, Corrs, DataLump, DataQuality, Filter, OutlierMethod, Outliers, PairPlot, Pivot, PivotAgg, ValueCounts, ValueCountsSort, Histogram, Histogram2D, HistogramParams, Kde, Scatter, ScatterParams, SummaryColumn, SummaryColumnType, TimeSeries, TimeSeriesParams, WaFrame, WaModelId};
 
-use crate::edit_models::{FilterNewConditionCtx, SummaryActionsCtx};
+use crate::edit_models::{FilterNewConditionCtx, HistogramParamsCtx, KdeParamsCtx, SummaryActionsCtx};
 use crate::model_views::{LayoutRequest, View};
 
 pub enum UIAction {
@@ -24,31 +24,62 @@ pub enum UIAction {
 #[derive(Debug)]
 pub enum ActionRq {
     LoadFrame { name_ptr: u32, data_ptr: u32 },
-    Histogram { frame_id:u128, name_ptr: u32 },
+    Histogram { frame_id:u128, name_ptr: u32, params:HistogramParams, histogram_id:Option<u128>, compare:bool },
     Convert { frame_id:u128, name_ptr: u32, pattern_ptr: u32, to_type_ptr:u32 },
     DataLump { frame_id:u128, offset:usize, limit:usize},
     ApplyFilter { frame_id:u128, filter:Filter},
-    Corr { frame_id:u128, column_vec_ptr: u32 },
-    PlotColumns { frame_id:u128, column_vec_ptr: u32 },
+    Corr { frame_id:u128, column_vec_ptr: u32, method:CorrMethod },
+    PlotColumns { frame_id:u128, column_vec_ptr: u32, max_points:usize },
+    DataQuality { frame_id:u128 },
+    Outliers { frame_id:u128, method:OutlierMethod },
+    ValueCounts { frame_id:u128, name_ptr: u32, sort:ValueCountsSort, offset:usize, limit:usize },
+    Pivot { frame_id:u128, row_ptr: u32, column_ptr: u32, value_ptr: u32, agg:PivotAgg },
+    GroupBy { frame_id:u128, keys_ptr: u32, aggs_ptr: u32 },
+    Kde { frame_id:u128, name_ptr: u32, bandwidth:Bandwidth, split_ptr: u32, target:KdeTarget },
+    Histogram2D { frame_id:u128, column_x_ptr: u32, column_y_ptr: u32, bins_x:usize, bins_y:usize },
+    Scatter { frame_id:u128, params:ScatterParams, scatter_id:Option<u128> },
+    BoxPlot { frame_id:u128, column:String, split_by:Option<String>, box_plot_id:Option<u128> },
+    TimeSeries { frame_id:u128, params:TimeSeriesParams, time_series_id:Option<u128> },
+    PairPlot { frame_id:u128, columns:Vec<String> },
 }
 
 #[derive(Debug)]
 pub enum ActionRs {
     LoadFrame {frame: WaFrame},
-    Histogram {frame_id:u128, histogram:Histogram},
+    Histogram {frame_id:u128, histogram:Histogram, histogram_id:Option<u128>},
     Convert { frame_id:u128, name: String, new_type:SummaryColumn },
     DataLump { frame_id:u128, lump:DataLump},
     Corr {frame_id:u128, corrs: Corrs},
     ColumnsPlot {frame_id:u128, columns_plot: ColumnsPlot},
+    DataQuality {frame_id:u128, data_quality: DataQuality},
+    Outliers {frame_id:u128, outliers: Outliers},
+    ValueCounts {frame_id:u128, value_counts: ValueCounts},
+    Pivot {frame_id:u128, pivot: Pivot},
+    Kde {frame_id:u128, kde: Kde, target:KdeTarget},
+    Histogram2D {frame_id:u128, histogram: Histogram2D},
+    Scatter {frame_id:u128, scatter: Scatter, scatter_id:Option<u128>},
+    BoxPlot {frame_id:u128, box_plot: BoxPlot, box_plot_id:Option<u128>},
+    TimeSeries {frame_id:u128, time_series: TimeSeries, time_series_id:Option<u128>},
+    PairPlot {frame_id:u128, pair_plot: PairPlot},
     Err { msg:String},
 }
 
+#[derive(Debug, Clone, Copy)]
+pub enum KdeTarget {
+    New,
+    Replace{kde_id:u128},
+    Histogram{histogram_id:u128}, //overlay
+}
+
 
 pub struct ModelCtx {
     pending_actions: VecDeque<ActionRq>,
     uid_actions: Vec<UIAction>,
     filter_new_condition_ctx:FilterNewConditionCtx,
     summary_actions_ctx:SummaryActionsCtx,
+    histogram_params_ctx:HashMap<u128, HistogramParamsCtx>,
+    kde_params_ctx:HashMap<u128, KdeParamsCtx>,
+    rolling_window_ctx:HashMap<u128, usize>, //edited window of a time series, sent when the edit ends
     is_init: bool
 }
 
@@ -59,6 +90,9 @@ impl ModelCtx {
             uid_actions: vec![],
             filter_new_condition_ctx:FilterNewConditionCtx::new(),
             summary_actions_ctx: SummaryActionsCtx::new(),
+            histogram_params_ctx: HashMap::new(),
+            kde_params_ctx: HashMap::new(),
+            rolling_window_ctx: HashMap::new(),
             is_init: true
         }
     }
@@ -90,6 +124,18 @@ impl ModelCtx {
         &self.summary_actions_ctx
     }
 
+    pub fn histogram_params_ctx_mut(&mut self, histogram:&Histogram) -> &mut HistogramParamsCtx {
+        self.histogram_params_ctx.entry(*histogram.id()).or_insert_with(|| HistogramParamsCtx::new(histogram.params()))
+    }
+
+    pub fn kde_params_ctx_mut(&mut self, id:u128, bandwidth:Bandwidth) -> &mut KdeParamsCtx {
+        self.kde_params_ctx.entry(id).or_insert_with(|| KdeParamsCtx::new(bandwidth))
+    }
+
+    pub fn rolling_window_mut(&mut self, id:u128, window:usize) -> &mut usize {
+        self.rolling_window_ctx.entry(id).or_insert(window)
+    }
+
     pub fn is_init(&self) -> bool {
         self.is_init
     }
@@ -198,6 +244,14 @@ impl WapukuAppModel {
         }
     }
 
+    pub fn replace_histogram(&mut self, frame_id:u128, histogram_id:u128, historgam:Histogram) {
+        if let Some(frame) = self.frames.get_mut(&frame_id) {
+            frame.replace_histogram(histogram_id, historgam);
+        } else {
+            debug!("wapuku: no frame_id={}", frame_id); //TODO err msg
+        }
+    }
+
     pub fn add_corrs(&mut self, frame_id:u128, corrs:Corrs) {
         if let Some(frame) = self.frames.get_mut(&frame_id) {
             frame.add_corrs(corrs);
@@ -214,6 +268,103 @@ impl WapukuAppModel {
         }
     }
 
+    pub fn add_data_quality(&mut self, frame_id:u128, data_quality:DataQuality) {
+        if let Some(frame) = self.frames.get_mut(&frame_id) {
+            frame.add_data_quality(data_quality);
+        } else {
+            debug!("wapuku: no frame_id={}", frame_id); //TODO err msg
+        }
+    }
+
+    pub fn add_outliers(&mut self, frame_id:u128, outliers:Outliers) {
+        if let Some(frame) = self.frames.get_mut(&frame_id) {
+            frame.add_outliers(outliers);
+        } else {
+            debug!("wapuku: no frame_id={}", frame_id); //TODO err msg
+        }
+    }
+
+    pub fn add_value_counts(&mut self, frame_id:u128, value_counts:ValueCounts) {
+        if let Some(frame) = self.frames.get_mut(&frame_id) {
+            frame.add_value_counts(value_counts);
+        } else {
+            debug!("wapuku: no frame_id={}", frame_id); //TODO err msg
+        }
+    }
+
+    pub fn add_pivot(&mut self, frame_id:u128, pivot:Pivot) {
+        if let Some(frame) = self.frames.get_mut(&frame_id) {
+            frame.add_pivot(pivot);
+        } else {
+            debug!("wapuku: no frame_id={}", frame_id); //TODO err msg
+        }
+    }
+
+    pub fn parent_condition(&self, frame_id:u128) -> Option<(u128, ConditionType)> {
+        self.frames.get(&frame_id).and_then(|frame| frame.parent_condition())
+    }
+
+    pub fn add_histogram_2d(&mut self, frame_id:u128, histogram:Histogram2D) {
+        if let Some(frame) = self.frames.get_mut(&frame_id) {
+            frame.add_histogram_2d(histogram);
+        } else {
+            debug!("wapuku: no frame_id={}", frame_id); //TODO err msg
+        }
+    }
+
+    pub fn add_scatter(&mut self, frame_id:u128, scatter:Scatter, scatter_id:Option<u128>) {
+        if let Some(frame) = self.frames.get_mut(&frame_id) {
+            match scatter_id {
+                Some(scatter_id) => frame.replace_scatter(scatter_id, scatter),
+                None => frame.add_scatter(scatter),
+            }
+        } else {
+            debug!("wapuku: no frame_id={}", frame_id); //TODO err msg
+        }
+    }
+
+    pub fn add_box_plot(&mut self, frame_id:u128, box_plot:BoxPlot, box_plot_id:Option<u128>) {
+        if let Some(frame) = self.frames.get_mut(&frame_id) {
+            match box_plot_id {
+                Some(box_plot_id) => frame.replace_box_plot(box_plot_id, box_plot),
+                None => frame.add_box_plot(box_plot),
+            }
+        } else {
+            debug!("wapuku: no frame_id={}", frame_id); //TODO err msg
+        }
+    }
+
+    pub fn add_time_series(&mut self, frame_id:u128, time_series:TimeSeries, time_series_id:Option<u128>) {
+        if let Some(frame) = self.frames.get_mut(&frame_id) {
+            match time_series_id {
+                Some(time_series_id) => frame.replace_time_series(time_series_id, time_series),
+                None => frame.add_time_series(time_series),
+            }
+        } else {
+            debug!("wapuku: no frame_id={}", frame_id); //TODO err msg
+        }
+    }
+
+    pub fn add_pair_plot(&mut self, frame_id:u128, pair_plot:PairPlot) {
+        if let Some(frame) = self.frames.get_mut(&frame_id) {
+            frame.add_pair_plot(pair_plot);
+        } else {
+            debug!("wapuku: no frame_id={}", frame_id); //TODO err msg
+        }
+    }
+
+    pub fn add_kde(&mut self, frame_id:u128, kde:Kde, target:KdeTarget) {
+        if let Some(frame) = self.frames.get_mut(&frame_id) {
+            match target {
+                KdeTarget::New => frame.add_kde(kde),
+                KdeTarget::Replace { kde_id } => frame.replace_kde(kde_id, kde),
+                KdeTarget::Histogram { histogram_id } => frame.set_histogram_kde(histogram_id, kde),
+            }
+        } else {
+            debug!("wapuku: no frame_id={}", frame_id); //TODO err msg
+        }
+    }
+
     pub fn add_data_lump(&mut self, frame_id:u128, data_lump:DataLump) {
         if let Some(frame) = self.frames.get_mut(&frame_id) {
             frame.add_data_lump(data_lump);
@@ -258,6 +409,56 @@ impl WapukuAppModel {
                     frame.purge(id);
                 }
             }
+            WaModelId::DataQuality { frame_id, data_quality_id:_ } => {
+                if let Some(frame) = self.frames.get_mut(&frame_id) {
+                    frame.purge(id);
+                }
+            }
+            WaModelId::Outliers { frame_id, outliers_id:_ } => {
+                if let Some(frame) = self.frames.get_mut(&frame_id) {
+                    frame.purge(id);
+                }
+            }
+            WaModelId::ValueCounts { frame_id, value_counts_id:_ } => {
+                if let Some(frame) = self.frames.get_mut(&frame_id) {
+                    frame.purge(id);
+                }
+            }
+            WaModelId::Pivot { frame_id, pivot_id:_ } => {
+                if let Some(frame) = self.frames.get_mut(&frame_id) {
+                    frame.purge(id);
+                }
+            }
+            WaModelId::Kde { frame_id, kde_id:_ } => {
+                if let Some(frame) = self.frames.get_mut(&frame_id) {
+                    frame.purge(id);
+                }
+            }
+            WaModelId::Histogram2D { frame_id, histogram_id:_ } => {
+                if let Some(frame) = self.frames.get_mut(&frame_id) {
+                    frame.purge(id);
+                }
+            }
+            WaModelId::Scatter { frame_id, scatter_id:_ } => {
+                if let Some(frame) = self.frames.get_mut(&frame_id) {
+                    frame.purge(id);
+                }
+            }
+            WaModelId::BoxPlot { frame_id, box_plot_id:_ } => {
+                if let Some(frame) = self.frames.get_mut(&frame_id) {
+                    frame.purge(id);
+                }
+            }
+            WaModelId::TimeSeries { frame_id, time_series_id:_ } => {
+                if let Some(frame) = self.frames.get_mut(&frame_id) {
+                    frame.purge(id);
+                }
+            }
+            WaModelId::PairPlot { frame_id, pair_plot_id:_ } => {
+                if let Some(frame) = self.frames.get_mut(&frame_id) {
+                    frame.purge(id);
+                }
+            }
         }
         // mem::drop(self.frames.remove(frame_id));
     }
@@ -300,10 +501,50 @@ impl WapukuAppModel {
                 (f)(&mut self.ctx, columns_plot, &mut self.layout_queue);
             }
 
+            for value_counts in frame.value_counts() {
+                (f)(&mut self.ctx, value_counts, &mut self.layout_queue);
+            }
+
+            for pivot in frame.pivots() {
+                (f)(&mut self.ctx, pivot, &mut self.layout_queue);
+            }
+
+            for kde in frame.kdes() {
+                (f)(&mut self.ctx, kde, &mut self.layout_queue);
+            }
+
+            for histogram in frame.histograms_2d() {
+                (f)(&mut self.ctx, histogram, &mut self.layout_queue);
+            }
+
+            for scatter in frame.scatters() {
+                (f)(&mut self.ctx, scatter, &mut self.layout_queue);
+            }
+
+            for box_plot in frame.box_plots() {
+                (f)(&mut self.ctx, box_plot, &mut self.layout_queue);
+            }
+
+            for time_series in frame.time_series() {
+                (f)(&mut self.ctx, time_series, &mut self.layout_queue);
+            }
+
+            for pair_plot in frame.pair_plots() {
+                (f)(&mut self.ctx, pair_plot, &mut self.layout_queue);
+            }
+
             if let Some(lump) = frame.data_lump() {
                 (f)(&mut self.ctx, lump, &mut self.layout_queue);
             }
 
+            if let Some(data_quality) = frame.data_quality() {
+                (f)(&mut self.ctx, data_quality, &mut self.layout_queue);
+            }
+
+            if let Some(outliers) = frame.outliers() {
+                (f)(&mut self.ctx, outliers, &mut self.layout_queue);
+            }
+
         })
     }
 
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 use wapuku_model::data_type::WapukuDataType;
-use wapuku_model::model::{Condition, ConditionType, Corrs, SummaryColumn, SummaryColumnType};
+use wapuku_model::model::{Bandwidth, Binning, CalendarUnit, Condition, ConditionType, CorrMethod, Corrs, GroupByAgg, HistogramParams, PivotAgg, SummaryColumn, SummaryColumnType};
 use crate::model_views::Msg;
 
 
@@ -31,6 +31,24 @@ pub struct SummaryActionsCtx {
     pattern:String,
     to_type:WapukuDataType,
     corrs:HashMap<u128, HashMap<String, bool>>,
+    corr_method:CorrMethod,
+    pub is_pivot_dialog_open:Option<u128>,
+    pivot_row:String,
+    pivot_column:String,
+    pivot_value:String,
+    pivot_agg:PivotAgg,
+    pub is_group_by_dialog_open:Option<u128>,
+    group_by_keys:Vec<String>,
+    group_by_aggs:Vec<(String, GroupByAgg)>,
+    new_agg_column:String,
+    new_agg:GroupByAgg,
+    quantile:String,
+    group_by_msg:Msg,
+    pub is_histogram_2d_dialog_open:Option<u128>,
+    histogram_2d_x:String,
+    histogram_2d_y:String,
+    histogram_2d_bins_x:String,
+    histogram_2d_bins_y:String,
 }
 
 impl SummaryActionsCtx {
@@ -40,6 +58,24 @@ impl SummaryActionsCtx {
             pattern: "%m/%d/%Y %T".into(),
             to_type:WapukuDataType::Datetime,
             corrs:HashMap::new(),
+            corr_method:CorrMethod::Pearson,
+            is_pivot_dialog_open:None,
+            pivot_row:String::new(),
+            pivot_column:String::new(),
+            pivot_value:String::new(),
+            pivot_agg:PivotAgg::Count,
+            is_group_by_dialog_open:None,
+            group_by_keys:vec![],
+            group_by_aggs:vec![],
+            new_agg_column:String::new(),
+            new_agg:GroupByAgg::Count,
+            quantile:String::from("0.5"),
+            group_by_msg:Msg::empty(),
+            is_histogram_2d_dialog_open:None,
+            histogram_2d_x:String::new(),
+            histogram_2d_y:String::new(),
+            histogram_2d_bins_x:String::from("20"),
+            histogram_2d_bins_y:String::from("20"),
         }
     }
 
@@ -77,6 +113,302 @@ impl SummaryActionsCtx {
             .map(|corrs| corrs.iter().filter(|kv|*kv.1).map(|v|v.0.clone()).collect())
             .unwrap_or_default()
     }
+
+    pub fn corr_method(&self) -> CorrMethod {
+        self.corr_method
+    }
+
+    pub fn corr_method_mut(&mut self) -> &mut CorrMethod {
+        &mut self.corr_method
+    }
+
+    pub fn pivot_row_mut(&mut self) -> &mut String {
+        &mut self.pivot_row
+    }
+
+    pub fn pivot_column_mut(&mut self) -> &mut String {
+        &mut self.pivot_column
+    }
+
+    pub fn pivot_value_mut(&mut self) -> &mut String {
+        &mut self.pivot_value
+    }
+
+    pub fn pivot_agg_mut(&mut self) -> &mut PivotAgg {
+        &mut self.pivot_agg
+    }
+
+    pub fn is_group_by_key(&self, column:&String) -> bool {
+        self.group_by_keys.contains(column)
+    }
+
+    pub fn toggle_group_by_key(&mut self, column:&String) {
+        if let Some(i) = self.group_by_keys.iter().position(|k|k.eq(column)) {
+            self.group_by_keys.remove(i);
+        } else {
+            self.group_by_keys.push(column.clone());
+        }
+    }
+
+    pub fn group_by_keys(&self) -> &Vec<String> {
+        &self.group_by_keys
+    }
+
+    pub fn group_by_aggs(&self) -> &Vec<(String, GroupByAgg)> {
+        &self.group_by_aggs
+    }
+
+    pub fn remove_group_by_agg(&mut self, i:usize) {
+        self.group_by_aggs.remove(i);
+    }
+
+    pub fn new_agg_column_mut(&mut self) -> &mut String {
+        &mut self.new_agg_column
+    }
+
+    pub fn new_agg_mut(&mut self) -> &mut GroupByAgg {
+        &mut self.new_agg
+    }
+
+    pub fn quantile_mut(&mut self) -> &mut String {
+        &mut self.quantile
+    }
+
+    pub fn add_group_by_agg(&mut self) {
+        self.group_by_msg = if self.new_agg_column.is_empty() {
+            FilterValidationResult::EmptyPattern
+        } else {
+            match self.new_agg {
+                GroupByAgg::Quantile { .. } => {
+                    match self.quantile.parse::<f64>() {
+                        Ok(q) if (0.0..=1.0).contains(&q) => {
+                            self.group_by_aggs.push((self.new_agg_column.clone(), GroupByAgg::Quantile { q }));
+                            FilterValidationResult::Ok
+                        }
+                        _ => FilterValidationResult::WrongFormat
+                    }
+                }
+                agg => {
+                    self.group_by_aggs.push((self.new_agg_column.clone(), agg));
+                    FilterValidationResult::Ok
+                }
+            }
+        }.into();
+    }
+
+    pub fn group_by_msg(&self) -> &Msg {
+        &self.group_by_msg
+    }
+
+    pub fn reset_group_by(&mut self) {
+        self.is_group_by_dialog_open = None;
+        self.group_by_keys.clear();
+        self.group_by_aggs.clear();
+        self.new_agg_column.clear();
+        self.group_by_msg = Msg::empty();
+    }
+
+    pub fn histogram_2d_x_mut(&mut self) -> &mut String {
+        &mut self.histogram_2d_x
+    }
+
+    pub fn histogram_2d_y_mut(&mut self) -> &mut String {
+        &mut self.histogram_2d_y
+    }
+
+    pub fn histogram_2d_bins_x_mut(&mut self) -> &mut String {
+        &mut self.histogram_2d_bins_x
+    }
+
+    pub fn histogram_2d_bins_y_mut(&mut self) -> &mut String {
+        &mut self.histogram_2d_bins_y
+    }
+
+    pub fn histogram_2d_params(&self) -> Option<(String, String, usize, usize)> {
+        let bins = |v:&String| v.trim().parse::<usize>().ok().filter(|b| *b > 0);
+
+        if self.histogram_2d_x.is_empty() || self.histogram_2d_y.is_empty() {
+            None
+        } else {
+            Some((self.histogram_2d_x.clone(), self.histogram_2d_y.clone(), bins(&self.histogram_2d_bins_x)?, bins(&self.histogram_2d_bins_y)?))
+        }
+    }
+
+    pub fn pivot_params(&self) -> Option<(String, String, String, PivotAgg)> {
+        if self.pivot_row.is_empty() || self.pivot_column.is_empty() || self.pivot_value.is_empty() {
+            None
+        } else {
+            Some((self.pivot_row.clone(), self.pivot_column.clone(), self.pivot_value.clone(), self.pivot_agg))
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct HistogramParamsCtx {
+    binning:Binning,
+    value:String, //bins, width or comma separated edges depending on binning
+    min:String,
+    max:String,
+    include_nulls:bool,
+    top_n:String,
+    split:String,
+    msg:Msg,
+}
+
+impl HistogramParamsCtx {
+    pub fn new(params:&HistogramParams) -> Self {
+        let value = match params.binning() {
+            Binning::Count { bins } | Binning::Quantile { bins } => bins.to_string(),
+            Binning::Width { width } => width.to_string(),
+            Binning::Edges { edges } => edges.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(", "),
+            Binning::Sturges | Binning::Scott | Binning::FreedmanDiaconis => String::new(),
+            Binning::Calendar { .. } | Binning::DayOfWeek | Binning::HourOfDay => String::new(),
+        };
+        let (min, max) = params.range().map(|(min, max)| (min.to_string(), max.to_string())).unwrap_or_default();
+
+        Self {
+            binning: params.binning().clone(),
+            value,
+            min,
+            max,
+            include_nulls: params.include_nulls(),
+            top_n: params.top_n().to_string(),
+            split: params.split().cloned().unwrap_or_default(),
+            msg: Msg::empty(),
+        }
+    }
+
+    pub fn binning(&self) -> &Binning {
+        &self.binning
+    }
+
+    pub fn set_binning(&mut self, binning:Binning) {
+        if !self.binning.same_kind(&binning) {
+            self.value = Self::new(&HistogramParams::new(binning.clone(), None, false)).value;
+            self.binning = binning;
+        }
+    }
+
+    pub fn has_value(&self) -> bool {
+        !matches!(self.binning, Binning::Sturges | Binning::Scott | Binning::FreedmanDiaconis) && !self.binning.is_calendar()
+    }
+
+    pub fn calendar_unit_mut(&mut self) -> Option<&mut Option<CalendarUnit>> {
+        if let Binning::Calendar { unit } = &mut self.binning {
+            Some(unit)
+        } else {
+            None
+        }
+    }
+
+    pub fn value_mut(&mut self) -> &mut String {
+        &mut self.value
+    }
+
+    pub fn min_mut(&mut self) -> &mut String {
+        &mut self.min
+    }
+
+    pub fn max_mut(&mut self) -> &mut String {
+        &mut self.max
+    }
+
+    pub fn include_nulls_mut(&mut self) -> &mut bool {
+        &mut self.include_nulls
+    }
+
+    pub fn top_n_mut(&mut self) -> &mut String {
+        &mut self.top_n
+    }
+
+    pub fn split_mut(&mut self) -> &mut String {
+        &mut self.split
+    }
+
+    pub fn msg(&self) -> &Msg {
+        &self.msg
+    }
+
+    pub fn to_params(&mut self) -> Option<HistogramParams> {
+        let value = self.value.trim();
+
+        let binning = match self.binning {
+            Binning::Count { .. } => value.parse::<usize>().ok().filter(|b| *b > 0).map(|bins| Binning::Count { bins }),
+            Binning::Quantile { .. } => value.parse::<usize>().ok().filter(|b| *b > 0).map(|bins| Binning::Quantile { bins }),
+            Binning::Width { .. } => value.parse::<f64>().ok().filter(|w| *w > 0.0).map(|width| Binning::Width { width }),
+            Binning::Edges { .. } => value.split(',')
+                .map(|e| e.trim().parse::<f64>())
+                .collect::<Result<Vec<_>, _>>()
+                .ok()
+                .filter(|edges| edges.len() >= 2)
+                .map(|edges| Binning::Edges { edges }),
+            ref binning => Some(binning.clone()),
+        };
+
+        let range = match (self.min.trim(), self.max.trim()) {
+            ("", "") => Ok(None),
+            (min, max) => match (min.parse::<f64>(), max.parse::<f64>()) {
+                (Ok(min), Ok(max)) if min < max => Ok(Some((min, max))),
+                (Ok(_), Ok(_)) => Err(FilterValidationResult::MinLessThanMax),
+                _ => Err(FilterValidationResult::WrongFormat),
+            }
+        };
+
+        let top_n = self.top_n.trim().parse::<usize>().ok().filter(|n| *n > 0);
+
+        match (binning, range, top_n) {
+            (Some(binning), Ok(range), Some(top_n)) => {
+                self.msg = FilterValidationResult::Ok.into();
+                let split = Some(self.split.trim().to_string()).filter(|s| !s.is_empty());
+                Some(HistogramParams::new(binning, range, self.include_nulls).with_top_n(top_n).with_split(split))
+            }
+            (None, _, _) | (_, _, None) => {
+                self.msg = FilterValidationResult::WrongFormat.into();
+                None
+            }
+            (_, Err(e), _) => {
+                self.msg = e.into();
+                None
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct KdeParamsCtx {
+    bandwidth:Bandwidth,
+    h:String,
+    msg:Msg,
+}
+
+impl KdeParamsCtx {
+    pub fn new(bandwidth:Bandwidth) -> Self {
+        let h = if let Bandwidth::Manual { h } = bandwidth { h.to_string() } else { String::new() };
+        Self { bandwidth, h, msg: Msg::empty() }
+    }
+
+    pub fn bandwidth_mut(&mut self) -> &mut Bandwidth {
+        &mut self.bandwidth
+    }
+
+    pub fn h_mut(&mut self) -> &mut String {
+        &mut self.h
+    }
+
+    pub fn msg(&self) -> &Msg {
+        &self.msg
+    }
+
+    pub fn to_bandwidth(&mut self) -> Option<Bandwidth> {
+        let bandwidth = match self.bandwidth {
+            Bandwidth::Manual { .. } => self.h.trim().parse::<f64>().ok().filter(|h| *h > 0.0).map(|h| Bandwidth::Manual { h }),
+            bandwidth => Some(bandwidth),
+        };
+
+        self.msg = if bandwidth.is_some() { FilterValidationResult::Ok } else { FilterValidationResult::WrongFormat }.into();
+
+        bandwidth
+    }
 }
 
 #[derive(Debug)]
@@ -205,6 +537,17 @@ impl FilterNewConditionCtx {
                 Condition::Boolean { val } => {
                     self.boolean = val;
                 }
+                Condition::Equals { value } => {
+                    self.pattern = value.unwrap_or_default();
+                }
+                Condition::Range { min, max, .. } => {
+                    self.min = format!("{}", min);
+                    self.max = format!("{}", max);
+                }
+                Condition::Fences { lower, upper, .. } => {
+                    self.min = format!("{}", lower);
+                    self.max = format!("{}", upper);
+                }
             }
         }
     }
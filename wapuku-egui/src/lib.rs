@@ -27,7 +27,7 @@ use wapuku_common_web::workers::PoolWorker;
 #[cfg(target_arch = "wasm32")]
 use wapuku_model::data_type::WapukuDataType;
 #[cfg(target_arch = "wasm32")]
-use wapuku_model::model::{Data, wa_id, WaFrame};
+use wapuku_model::model::{Data, GroupByAgg, wa_id, WaFrame};
 #[cfg(target_arch = "wasm32")]
 use wapuku_model::polars_df::PolarsData;
 #[cfg(target_arch = "wasm32")]
@@ -194,17 +194,23 @@ pub async fn run() {
 
                         });
                     }
-                    ActionRq::Histogram { frame_id, name_ptr} => {
+                    ActionRq::Histogram { frame_id, name_ptr, params, histogram_id, compare} => {
+                        let (data_id, params) = match model_borrowed.parent_condition(frame_id).filter(|_| compare) { //parent's rows with this frame's rows as a series
+                            Some((parent_id, condition)) => (parent_id, params.with_compare(Some(condition)).with_split(None)), //one set of series at a time
+                            None => (frame_id, params.with_compare(None)),
+                        };
+
                        pool_worker.run_in_pool( move || {
                             let name = **unsafe { Box::from_raw(name_ptr as *mut Box<String>) };
                             debug!("wapuku: running in pool, ::ListUnique name={}", name);
 
-                            let result = data_map_rc_1.borrow().get(&frame_id).expect(format!("no data for frame_id={}", frame_id).as_str()).build_histogram(frame_id, name, None);
+                            let result = data_map_rc_1.borrow().get(&data_id).expect(format!("no data for frame_id={}", data_id).as_str()).build_histogram(frame_id, name, params.clone());
                             match result {
                                 Ok(histogram) => {
                                     to_main_rc_1_1.send(ActionRs::Histogram {
                                         frame_id,
                                         histogram,
+                                        histogram_id,
                                     }).expect("send");
                                 }
                                 Err(e) => {
@@ -266,17 +272,17 @@ pub async fn run() {
                                 Ok(wiltered_fame) => {
                                     debug!("wapuku: running in pool, sending data lump");
 
-                                    let frame_id = wa_id();
+                                    let filtered_frame_id = wa_id();
 
                                     to_main_rc_1_1.send(ActionRs::LoadFrame {
                                         frame: WaFrame::new(
-                                            frame_id,
+                                            filtered_frame_id,
                                             format!("{} filtered", wiltered_fame.data().name()),
-                                            wiltered_fame.data().build_summary(frame_id, None),
-                                        )
+                                            wiltered_fame.data().build_summary(filtered_frame_id, None),
+                                        ).with_parent(frame_id, filter.clone())
                                     }).expect("ActionRs::LoadFrame");
 
-                                    data_map_rc_1.borrow_mut().insert(frame_id, wiltered_fame.into());
+                                    data_map_rc_1.borrow_mut().insert(filtered_frame_id, wiltered_fame.into());
                                 }
                                 Err(e) => {
                                     to_main_rc_1_1.send(ActionRs::Err { msg: String::from(e.to_string()) }).expect("send");
@@ -284,7 +290,7 @@ pub async fn run() {
                             }
                         });
                     }
-                    ActionRq::Corr { frame_id, column_vec_ptr } => {
+                    ActionRq::Corr { frame_id, column_vec_ptr, method } => {
 
 
                         pool_worker.run_in_pool( move || {
@@ -293,7 +299,7 @@ pub async fn run() {
 
                             debug!("ActionRq::Corr: names in pool: {:?}", names);
 
-                            let result = data_map_rc_1.borrow().get(&frame_id).expect(format!("no data for frame_id={}", frame_id).as_str()).clc_corrs(frame_id, names);
+                            let result = data_map_rc_1.borrow().get(&frame_id).expect(format!("no data for frame_id={}", frame_id).as_str()).clc_corrs(frame_id, names, method);
                             match result {
                                 Ok(corrs) => {
                                     debug!("wapuku: running in pool, sending data lump");
@@ -309,13 +315,13 @@ pub async fn run() {
                             }
                         });
                     }
-                    ActionRq::PlotColumns { frame_id, column_vec_ptr } => {
+                    ActionRq::PlotColumns { frame_id, column_vec_ptr, max_points } => {
                         pool_worker.run_in_pool( move || {
                             let names = **unsafe { Box::from_raw(column_vec_ptr as *mut Box<Vec<String>>) };
 
                             debug!("ActionRq::PlotColumns: names in pool: {:?}", names);
 
-                            let result = data_map_rc_1.borrow().get(&frame_id).expect(format!("no data for frame_id={}", frame_id).as_str()).plot_columns(frame_id, names);
+                            let result = data_map_rc_1.borrow().get(&frame_id).expect(format!("no data for frame_id={}", frame_id).as_str()).plot_columns(frame_id, names, max_points);
                             match result {
                                 Ok(columns_plot) => {
                                     to_main_rc_1_1.send(ActionRs::ColumnsPlot {
@@ -329,6 +335,208 @@ pub async fn run() {
                             }
                         });
                     }
+                    ActionRq::DataQuality { frame_id } => {
+                        pool_worker.run_in_pool( move || {
+                            let result = data_map_rc_1.borrow().get(&frame_id).expect(format!("no data for frame_id={}", frame_id).as_str()).check_quality(frame_id);
+                            match result {
+                                Ok(data_quality) => {
+                                    to_main_rc_1_1.send(ActionRs::DataQuality {
+                                        frame_id,
+                                        data_quality,
+                                    }).expect("ActionRs::DataQuality");
+                                }
+                                Err(e) => {
+                                    to_main_rc_1_1.send(ActionRs::Err { msg: String::from(e.to_string()) }).expect("send");
+                                }
+                            }
+                        });
+                    }
+                    ActionRq::Outliers { frame_id, method } => {
+                        pool_worker.run_in_pool( move || {
+                            let result = data_map_rc_1.borrow().get(&frame_id).expect(format!("no data for frame_id={}", frame_id).as_str()).find_outliers(frame_id, method);
+                            match result {
+                                Ok(outliers) => {
+                                    to_main_rc_1_1.send(ActionRs::Outliers {
+                                        frame_id,
+                                        outliers,
+                                    }).expect("ActionRs::Outliers");
+                                }
+                                Err(e) => {
+                                    to_main_rc_1_1.send(ActionRs::Err { msg: String::from(e.to_string()) }).expect("send");
+                                }
+                            }
+                        });
+                    }
+                    ActionRq::ValueCounts { frame_id, name_ptr, sort, offset, limit } => {
+                        pool_worker.run_in_pool( move || {
+                            let name = **unsafe { Box::from_raw(name_ptr as *mut Box<String>) };
+
+                            let result = data_map_rc_1.borrow().get(&frame_id).expect(format!("no data for frame_id={}", frame_id).as_str()).value_counts(frame_id, name, sort, offset, limit);
+                            match result {
+                                Ok(value_counts) => {
+                                    to_main_rc_1_1.send(ActionRs::ValueCounts {
+                                        frame_id,
+                                        value_counts,
+                                    }).expect("ActionRs::ValueCounts");
+                                }
+                                Err(e) => {
+                                    to_main_rc_1_1.send(ActionRs::Err { msg: String::from(e.to_string()) }).expect("send");
+                                }
+                            }
+                        });
+                    }
+                    ActionRq::Pivot { frame_id, row_ptr, column_ptr, value_ptr, agg } => {
+                        pool_worker.run_in_pool( move || {
+                            let row = **unsafe { Box::from_raw(row_ptr as *mut Box<String>) };
+                            let column = **unsafe { Box::from_raw(column_ptr as *mut Box<String>) };
+                            let value = **unsafe { Box::from_raw(value_ptr as *mut Box<String>) };
+
+                            let result = data_map_rc_1.borrow().get(&frame_id).expect(format!("no data for frame_id={}", frame_id).as_str()).pivot(frame_id, row, column, value, agg);
+                            match result {
+                                Ok(pivot) => {
+                                    to_main_rc_1_1.send(ActionRs::Pivot {
+                                        frame_id,
+                                        pivot,
+                                    }).expect("ActionRs::Pivot");
+                                }
+                                Err(e) => {
+                                    to_main_rc_1_1.send(ActionRs::Err { msg: String::from(e.to_string()) }).expect("send");
+                                }
+                            }
+                        });
+                    }
+                    ActionRq::Kde { frame_id, name_ptr, bandwidth, split_ptr, target } => {
+                        pool_worker.run_in_pool( move || {
+                            let name = **unsafe { Box::from_raw(name_ptr as *mut Box<String>) };
+                            let split = **unsafe { Box::from_raw(split_ptr as *mut Box<Option<String>>) };
+
+                            let result = data_map_rc_1.borrow().get(&frame_id).expect(format!("no data for frame_id={}", frame_id).as_str()).kde(frame_id, name, bandwidth, split);
+                            match result {
+                                Ok(kde) => {
+                                    to_main_rc_1_1.send(ActionRs::Kde {
+                                        frame_id,
+                                        kde,
+                                        target,
+                                    }).expect("ActionRs::Kde");
+                                }
+                                Err(e) => {
+                                    to_main_rc_1_1.send(ActionRs::Err { msg: String::from(e.to_string()) }).expect("send");
+                                }
+                            }
+                        });
+                    }
+                    ActionRq::Histogram2D { frame_id, column_x_ptr, column_y_ptr, bins_x, bins_y } => {
+                        pool_worker.run_in_pool( move || {
+                            let column_x = **unsafe { Box::from_raw(column_x_ptr as *mut Box<String>) };
+                            let column_y = **unsafe { Box::from_raw(column_y_ptr as *mut Box<String>) };
+
+                            let result = data_map_rc_1.borrow().get(&frame_id).expect(format!("no data for frame_id={}", frame_id).as_str()).build_histogram_2d(frame_id, column_x, column_y, bins_x, bins_y);
+                            match result {
+                                Ok(histogram) => {
+                                    to_main_rc_1_1.send(ActionRs::Histogram2D {
+                                        frame_id,
+                                        histogram,
+                                    }).expect("ActionRs::Histogram2D");
+                                }
+                                Err(e) => {
+                                    to_main_rc_1_1.send(ActionRs::Err { msg: String::from(e.to_string()) }).expect("send");
+                                }
+                            }
+                        });
+                    }
+                    ActionRq::Scatter { frame_id, params, scatter_id } => {
+                        pool_worker.run_in_pool( move || {
+                            let result = data_map_rc_1.borrow().get(&frame_id).expect(format!("no data for frame_id={}", frame_id).as_str()).build_scatter(frame_id, params.clone());
+                            match result {
+                                Ok(scatter) => {
+                                    to_main_rc_1_1.send(ActionRs::Scatter {
+                                        frame_id,
+                                        scatter,
+                                        scatter_id,
+                                    }).expect("ActionRs::Scatter");
+                                }
+                                Err(e) => {
+                                    to_main_rc_1_1.send(ActionRs::Err { msg: String::from(e.to_string()) }).expect("send");
+                                }
+                            }
+                        });
+                    }
+                    ActionRq::BoxPlot { frame_id, column, split_by, box_plot_id } => {
+                        pool_worker.run_in_pool( move || {
+                            let result = data_map_rc_1.borrow().get(&frame_id).expect(format!("no data for frame_id={}", frame_id).as_str()).build_box_plot(frame_id, column.clone(), split_by.clone());
+                            match result {
+                                Ok(box_plot) => {
+                                    to_main_rc_1_1.send(ActionRs::BoxPlot {
+                                        frame_id,
+                                        box_plot,
+                                        box_plot_id,
+                                    }).expect("ActionRs::BoxPlot");
+                                }
+                                Err(e) => {
+                                    to_main_rc_1_1.send(ActionRs::Err { msg: String::from(e.to_string()) }).expect("send");
+                                }
+                            }
+                        });
+                    }
+                    ActionRq::TimeSeries { frame_id, params, time_series_id } => {
+                        pool_worker.run_in_pool( move || {
+                            let result = data_map_rc_1.borrow().get(&frame_id).expect(format!("no data for frame_id={}", frame_id).as_str()).build_time_series(frame_id, params.clone());
+                            match result {
+                                Ok(time_series) => {
+                                    to_main_rc_1_1.send(ActionRs::TimeSeries {
+                                        frame_id,
+                                        time_series,
+                                        time_series_id,
+                                    }).expect("ActionRs::TimeSeries");
+                                }
+                                Err(e) => {
+                                    to_main_rc_1_1.send(ActionRs::Err { msg: String::from(e.to_string()) }).expect("send");
+                                }
+                            }
+                        });
+                    }
+                    ActionRq::PairPlot { frame_id, columns } => {
+                        pool_worker.run_in_pool( move || {
+                            let result = data_map_rc_1.borrow().get(&frame_id).expect(format!("no data for frame_id={}", frame_id).as_str()).build_pair_plot(frame_id, columns.clone());
+                            match result {
+                                Ok(pair_plot) => {
+                                    to_main_rc_1_1.send(ActionRs::PairPlot {
+                                        frame_id,
+                                        pair_plot,
+                                    }).expect("ActionRs::PairPlot");
+                                }
+                                Err(e) => {
+                                    to_main_rc_1_1.send(ActionRs::Err { msg: String::from(e.to_string()) }).expect("send");
+                                }
+                            }
+                        });
+                    }
+                    ActionRq::GroupBy { frame_id, keys_ptr, aggs_ptr } => {
+                        pool_worker.run_in_pool( move || {
+                            let keys = **unsafe { Box::from_raw(keys_ptr as *mut Box<Vec<String>>) };
+                            let aggs = **unsafe { Box::from_raw(aggs_ptr as *mut Box<Vec<(String, GroupByAgg)>>) };
+
+                            let result = data_map_rc_1.borrow().get(&frame_id).expect(format!("no data for frame_id={}", frame_id).as_str()).group_by(frame_id, keys, aggs);
+                            match result {
+                                Ok(grouped_frame) => {
+                                    let frame_id = wa_id();
+
+                                    to_main_rc_1_1.send(ActionRs::LoadFrame {
+                                        frame: WaFrame::new(
+                                            frame_id,
+                                            grouped_frame.data().name(),
+                                            grouped_frame.data().build_summary(frame_id, None),
+                                        )
+                                    }).expect("ActionRs::LoadFrame");
+
+                                    data_map_rc_1.borrow_mut().insert(frame_id, grouped_frame.into());
+                                }
+                                Err(e) => {
+                                    to_main_rc_1_1.send(ActionRs::Err { msg: String::from(e.to_string()) }).expect("send");
+                                }
+                            }
+                        });
+                    }
                 }
             }
             model_borrowed.run_ui_actions();
@@ -342,8 +550,12 @@ pub async fn run() {
                         model_borrowed.add_frame(frame);
                     }
 
-                    ActionRs::Histogram { frame_id, histogram } => {
-                        model_borrowed.add_histogram(frame_id, histogram);
+                    ActionRs::Histogram { frame_id, histogram, histogram_id } => {
+                        if let Some(histogram_id) = histogram_id {
+                            model_borrowed.replace_histogram(frame_id, histogram_id, histogram);
+                        } else {
+                            model_borrowed.add_histogram(frame_id, histogram);
+                        }
                     }
 
                     ActionRs::DataLump { frame_id, lump } => {
@@ -367,6 +579,56 @@ pub async fn run() {
                         model_borrowed.add_columns_plot(frame_id, columns_plot);
                     }
 
+                    ActionRs::DataQuality { frame_id, data_quality } => {
+                        debug!("wapuku: ActionRs::DataQuality frame_id={:?} data_quality_id={:?} issues={}", frame_id, data_quality.id(), data_quality.issues().len() );
+                        model_borrowed.add_data_quality(frame_id, data_quality);
+                    }
+
+                    ActionRs::Outliers { frame_id, outliers } => {
+                        debug!("wapuku: ActionRs::Outliers frame_id={:?} outliers_id={:?} columns={}", frame_id, outliers.id(), outliers.columns().len() );
+                        model_borrowed.add_outliers(frame_id, outliers);
+                    }
+
+                    ActionRs::ValueCounts { frame_id, value_counts } => {
+                        debug!("wapuku: ActionRs::ValueCounts frame_id={:?} value_counts_id={:?} values={}", frame_id, value_counts.id(), value_counts.values().len() );
+                        model_borrowed.add_value_counts(frame_id, value_counts);
+                    }
+
+                    ActionRs::Pivot { frame_id, pivot } => {
+                        debug!("wapuku: ActionRs::Pivot frame_id={:?} pivot_id={:?} rows={} columns={}", frame_id, pivot.id(), pivot.row_keys().len(), pivot.column_keys().len() );
+                        model_borrowed.add_pivot(frame_id, pivot);
+                    }
+
+                    ActionRs::Kde { frame_id, kde, target } => {
+                        debug!("wapuku: ActionRs::Kde frame_id={:?} kde_id={:?} series={}", frame_id, kde.id(), kde.series().len() );
+                        model_borrowed.add_kde(frame_id, kde, target);
+                    }
+
+                    ActionRs::Histogram2D { frame_id, histogram } => {
+                        debug!("wapuku: ActionRs::Histogram2D frame_id={:?} histogram_id={:?} bins={}x{}", frame_id, histogram.id(), histogram.bins_x(), histogram.bins_y() );
+                        model_borrowed.add_histogram_2d(frame_id, histogram);
+                    }
+
+                    ActionRs::Scatter { frame_id, scatter, scatter_id } => {
+                        debug!("wapuku: ActionRs::Scatter frame_id={:?} scatter_id={:?}", frame_id, scatter_id );
+                        model_borrowed.add_scatter(frame_id, scatter, scatter_id);
+                    }
+
+                    ActionRs::BoxPlot { frame_id, box_plot, box_plot_id } => {
+                        debug!("wapuku: ActionRs::BoxPlot frame_id={:?} box_plot_id={:?}", frame_id, box_plot_id );
+                        model_borrowed.add_box_plot(frame_id, box_plot, box_plot_id);
+                    }
+
+                    ActionRs::TimeSeries { frame_id, time_series, time_series_id } => {
+                        debug!("wapuku: ActionRs::TimeSeries frame_id={:?} time_series_id={:?}", frame_id, time_series_id );
+                        model_borrowed.add_time_series(frame_id, time_series, time_series_id);
+                    }
+
+                    ActionRs::PairPlot { frame_id, pair_plot } => {
+                        debug!("wapuku: ActionRs::PairPlot frame_id={:?} columns={:?}", frame_id, pair_plot.columns() );
+                        model_borrowed.add_pair_plot(frame_id, pair_plot);
+                    }
+
                     ActionRs::Err { msg } => {
                         debug!("wapuku: error={:?}", msg);
                         model_borrowed.set_error(msg);
@@ -2,15 +2,15 @@ use std::ops::RangeInclusive;
 use egui::{Color32, Context, FontId, Frame, InnerResponse, RichText, Ui, WidgetText};
 use egui::Id;
 use egui_extras::{Column, TableBuilder, TableRow};
-use egui_plot::{AxisHints, Bar, BarChart, Legend, Line, Plot, PlotBounds, PlotPoints, Polygon};
+use egui_plot::{AxisHints, Bar, BarChart, BoxElem, BoxSpread, GridInput, GridMark, Legend, Line, LineStyle, Plot, PlotBounds, PlotPoint, PlotPoints, Points, Polygon, Text, VLine};
 use log::debug;
 use wapuku_model::data_type::WapukuDataType;
 use wapuku_model::messages::OK;
-use wapuku_model::model::{ColumnsPlot, CompositeType, Condition, ConditionType, Corrs, DataLump, Filter, Histogram, Summary, SummaryColumn, SummaryColumnType, WaModelId};
-use wapuku_model::utils::val_or_na;
+use wapuku_model::model::{Bandwidth, BoxPlot, ColumnsPlot, Kde, CompositeType, Condition, ConditionType, CorrMeasure, CorrMethod, CorrValue, Corrs, CORR_ALPHA, DataLump, DataQuality, Filter, GroupByAgg, OutlierMethod, Outliers, PairPlot, PAIR_PLOT_MAX_COLUMNS, Pivot, PivotAgg, PivotNormalization, ValueCounts, ValueCountsSort, Binning, CalendarUnit, Histogram, Histogram2D, HistogramLayout, Scatter, ScatterParams, HistogramScale, HistogramView, HistogramParams, Summary, SummaryColumn, SummaryColumnType, ResampleAgg, TimeSeries, TimeSeriesParams, WaModelId};
+use wapuku_model::utils::{format_timestamp_ms, val_or_na};
 
-use crate::app::{ActionRq, ModelCtx, UIAction};
-use crate::edit_models::ValidationResult;
+use crate::app::{ActionRq, KdeTarget, ModelCtx, UIAction};
+use crate::edit_models::{KdeParamsCtx, ValidationResult};
 
 #[derive(Debug)]
 pub struct Msg {
@@ -19,6 +19,11 @@ pub struct Msg {
 }
 
 const ICON_FONT:FontId = FontId::proportional(30.0);
+const SCATTER_SIZE_CLASSES:usize = 5;
+const TIME_SERIES_ROLLING_WINDOW:usize = 7;
+const PAIR_PLOT_LABEL_WIDTH:f32 = 80.0; //row labels left of the grid
+const DAY_MS:f64 = 24.0 * 3_600_000.0;
+const TIME_STEPS_MS:[f64; 9] = [60_000.0, 3_600_000.0, 6.0 * 3_600_000.0, DAY_MS, 7.0 * DAY_MS, 30.0 * DAY_MS, 91.0 * DAY_MS, 365.0 * DAY_MS, 10.0 * 365.0 * DAY_MS];
 
 impl Msg {
     pub fn new(text: &str, color: Color32) -> Self {
@@ -105,8 +110,217 @@ impl View for Summary {
                 );
 
             };
+
+            if ui.button("Quality").clicked() {
+                model_ctx.queue_action(ActionRq::DataQuality {
+                    frame_id: self.frame_id(),
+                });
+            };
+
+            if ui.button("Outliers").clicked() {
+                model_ctx.queue_action(ActionRq::Outliers {
+                    frame_id: self.frame_id(),
+                    method: OutlierMethod::Iqr,
+                });
+            };
+
+            if ui.button("Pivot").clicked() {
+                model_ctx.summary_actions_ctx_mut().is_pivot_dialog_open = Some(self.frame_id());
+            };
+
+            if ui.button("Group by").clicked() {
+                model_ctx.summary_actions_ctx_mut().reset_group_by();
+                model_ctx.summary_actions_ctx_mut().is_group_by_dialog_open = Some(self.frame_id());
+            };
+
+            if ui.button("2D histogram").clicked() {
+                model_ctx.summary_actions_ctx_mut().is_histogram_2d_dialog_open = Some(self.frame_id());
+            };
         });
 
+        if model_ctx.summary_actions_ctx().is_histogram_2d_dialog_open.eq(&Some(self.frame_id())) {
+            egui::Window::new("2D histogram").id(Id::new(("histogram_2d_dialog", self.frame_id()))).current_pos(ui.clip_rect().center()).show(ctx, |ui| {
+                egui::Grid::new(("histogram_2d_dialog_grid", self.frame_id())).show(ui, |ui| {
+                    for (label, i) in [("X:", 0), ("Y:", 1)] {
+                        ui.label(label);
+                        let selected = if i == 0 {
+                            model_ctx.summary_actions_ctx_mut().histogram_2d_x_mut()
+                        } else {
+                            model_ctx.summary_actions_ctx_mut().histogram_2d_y_mut()
+                        };
+                        egui::ComboBox::from_id_source(("histogram_2d_column", self.frame_id(), i))
+                            .selected_text(selected.clone())
+                            .show_ui(ui, |ui| {
+                                for c in self.columns().iter().filter(|c| matches!(c.dtype(), SummaryColumnType::Numeric { .. } | SummaryColumnType::Datetime { .. })) {
+                                    ui.selectable_value(selected, c.name().clone(), c.name());
+                                }
+                            });
+
+                        let bins = if i == 0 {
+                            model_ctx.summary_actions_ctx_mut().histogram_2d_bins_x_mut()
+                        } else {
+                            model_ctx.summary_actions_ctx_mut().histogram_2d_bins_y_mut()
+                        };
+                        ui.add(egui::TextEdit::singleline(bins).hint_text("bins").desired_width(40.0));
+                        ui.end_row();
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        model_ctx.summary_actions_ctx_mut().is_histogram_2d_dialog_open = None;
+                    }
+
+                    let params = model_ctx.summary_actions_ctx().histogram_2d_params();
+                    if ui.add_enabled(params.is_some(), egui::Button::new("Build")).clicked() {
+                        if let Some((column_x, column_y, bins_x, bins_y)) = params {
+                            model_ctx.summary_actions_ctx_mut().is_histogram_2d_dialog_open = None;
+
+                            model_ctx.queue_action(ActionRq::Histogram2D {
+                                frame_id: self.frame_id(),
+                                column_x_ptr: Box::into_raw(Box::new(Box::new(column_x))) as u32,
+                                column_y_ptr: Box::into_raw(Box::new(Box::new(column_y))) as u32,
+                                bins_x,
+                                bins_y,
+                            });
+                        }
+                    }
+                });
+            });
+        }
+
+        if model_ctx.summary_actions_ctx().is_group_by_dialog_open.eq(&Some(self.frame_id())) {
+            egui::Window::new("Group by").id(Id::new(("group_by_dialog", self.frame_id()))).current_pos(ui.clip_rect().center()).show(ctx, |ui| {
+                ui.strong("Keys:");
+                ui.horizontal_wrapped(|ui| {
+                    for c in self.columns() {
+                        if ui.selectable_label(model_ctx.summary_actions_ctx().is_group_by_key(c.name()), c.name()).clicked() {
+                            model_ctx.summary_actions_ctx_mut().toggle_group_by_key(c.name());
+                        }
+                    }
+                });
+
+                ui.separator();
+                ui.strong("Aggregations:");
+
+                let mut to_remove = None;
+                for (i, (column, agg)) in model_ctx.summary_actions_ctx().group_by_aggs().iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(agg.alias(column));
+                        if ui.button("-").clicked() {
+                            to_remove = Some(i);
+                        }
+                    });
+                }
+                if let Some(i) = to_remove {
+                    model_ctx.summary_actions_ctx_mut().remove_group_by_agg(i);
+                }
+
+                ui.horizontal(|ui| {
+                    let column = model_ctx.summary_actions_ctx_mut().new_agg_column_mut();
+                    egui::ComboBox::from_id_source(("group_by_column", self.frame_id()))
+                        .selected_text(column.clone())
+                        .show_ui(ui, |ui| {
+                            for c in self.columns() {
+                                ui.selectable_value(column, c.name().clone(), c.name());
+                            }
+                        });
+
+                    let agg = model_ctx.summary_actions_ctx_mut().new_agg_mut();
+                    egui::ComboBox::from_id_source(("group_by_agg", self.frame_id()))
+                        .selected_text(agg.label())
+                        .show_ui(ui, |ui| {
+                            for a in GroupByAgg::all() {
+                                ui.selectable_value(agg, a, a.label());
+                            }
+                        });
+
+                    if matches!(agg, GroupByAgg::Quantile { .. }) {
+                        ui.add(egui::TextEdit::singleline(model_ctx.summary_actions_ctx_mut().quantile_mut()).hint_text("quantile").desired_width(40.0));
+                    }
+
+                    if ui.button("+").clicked() {
+                        model_ctx.summary_actions_ctx_mut().add_group_by_agg();
+                    }
+                });
+                ui.colored_label(*model_ctx.summary_actions_ctx().group_by_msg().color(), model_ctx.summary_actions_ctx().group_by_msg().text().clone());
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        model_ctx.summary_actions_ctx_mut().reset_group_by();
+                    }
+
+                    if ui.add_enabled(!model_ctx.summary_actions_ctx().group_by_keys().is_empty(), egui::Button::new("Group")).clicked() {
+                        model_ctx.queue_action(ActionRq::GroupBy {
+                            frame_id: self.frame_id(),
+                            keys_ptr: Box::into_raw(Box::new(Box::<Vec<String>>::new(model_ctx.summary_actions_ctx().group_by_keys().clone()))) as u32,
+                            aggs_ptr: Box::into_raw(Box::new(Box::<Vec<(String, GroupByAgg)>>::new(model_ctx.summary_actions_ctx().group_by_aggs().clone()))) as u32,
+                        });
+                        model_ctx.summary_actions_ctx_mut().reset_group_by();
+                    }
+                });
+            });
+        }
+
+        if model_ctx.summary_actions_ctx().is_pivot_dialog_open.eq(&Some(self.frame_id())) {
+            egui::Window::new("Pivot").id(Id::new(("pivot_dialog", self.frame_id()))).current_pos(ui.clip_rect().center()).show(ctx, |ui| {
+                egui::Grid::new(("pivot_dialog_grid", self.frame_id())).show(ui, |ui| {
+                    for (label, i) in [("Rows:", 0), ("Columns:", 1), ("Values:", 2)] {
+                        ui.label(label);
+                        let selected = match i {
+                            0 => model_ctx.summary_actions_ctx_mut().pivot_row_mut(),
+                            1 => model_ctx.summary_actions_ctx_mut().pivot_column_mut(),
+                            _ => model_ctx.summary_actions_ctx_mut().pivot_value_mut(),
+                        };
+                        egui::ComboBox::from_id_source(("pivot_column", self.frame_id(), i))
+                            .selected_text(selected.clone())
+                            .show_ui(ui, |ui| {
+                                for c in self.columns() {
+                                    ui.selectable_value(selected, c.name().clone(), c.name());
+                                }
+                            });
+                        ui.end_row();
+                    }
+
+                    ui.label("Aggregation:");
+                    let agg = model_ctx.summary_actions_ctx_mut().pivot_agg_mut();
+                    egui::ComboBox::from_id_source(("pivot_agg", self.frame_id()))
+                        .selected_text(agg.label())
+                        .show_ui(ui, |ui| {
+                            for a in PivotAgg::all() {
+                                ui.selectable_value(agg, a, a.label());
+                            }
+                        });
+                    ui.end_row();
+                });
+
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        model_ctx.summary_actions_ctx_mut().is_pivot_dialog_open = None;
+                    }
+
+                    let params = model_ctx.summary_actions_ctx().pivot_params();
+                    if ui.add_enabled(params.is_some(), egui::Button::new("Build")).clicked() {
+                        if let Some((row, column, value, agg)) = params {
+                            model_ctx.summary_actions_ctx_mut().is_pivot_dialog_open = None;
+
+                            model_ctx.queue_action(ActionRq::Pivot {
+                                frame_id: self.frame_id(),
+                                row_ptr: Box::into_raw(Box::new(Box::new(row))) as u32,
+                                column_ptr: Box::into_raw(Box::new(Box::new(column))) as u32,
+                                value_ptr: Box::into_raw(Box::new(Box::new(value))) as u32,
+                                agg,
+                            });
+                        }
+                    }
+                });
+            });
+        }
+
+        let plot_width = ui.available_width();
+
         let table = TableBuilder::new(ui)
             .striped(true)
             .resizable(true)
@@ -135,11 +349,32 @@ impl View for Summary {
                         ui.add_space(20.0);//TODO
                         ui.set_enabled(model_ctx.summary_actions_ctx().get_columns_for_corr_num(self.frame_id()) >=2);
 
+                        let method = model_ctx.summary_actions_ctx_mut().corr_method_mut();
+                        egui::ComboBox::from_id_source(("corr_method", self.frame_id()))
+                            .selected_text(method.label())
+                            .show_ui(ui, |ui| {
+                                for m in CorrMethod::all() {
+                                    ui.selectable_value(method, m, m.label());
+                                }
+                            });
+
                         if ui.button("➡").clicked() {
                             debug!("➡➡➡➡");
                             model_ctx.queue_action(ActionRq::Corr {
                                 frame_id: self.frame_id(),
                                 column_vec_ptr: Box::into_raw(Box::new(Box::<Vec<String>>::new(model_ctx.summary_actions_ctx().get_columns_for_corr(self.frame_id())))) as u32,
+                                method: model_ctx.summary_actions_ctx().corr_method(),
+                            });
+                        }
+
+                        let ticked = model_ctx.summary_actions_ctx().get_columns_for_corr_num(self.frame_id());
+                        if ui.add_enabled(ticked <= PAIR_PLOT_MAX_COLUMNS, egui::Button::new("pairs"))
+                            .on_hover_text("pair plot of the ticked columns")
+                            .on_disabled_hover_text(format!("{} columns ticked, a pair plot takes at most {}", ticked, PAIR_PLOT_MAX_COLUMNS))
+                            .clicked() {
+                            model_ctx.queue_action(ActionRq::PairPlot {
+                                frame_id: self.frame_id(),
+                                columns: model_ctx.summary_actions_ctx().get_columns_for_corr(self.frame_id()),
                             });
                         }
 
@@ -147,6 +382,7 @@ impl View for Summary {
                             model_ctx.queue_action(ActionRq::PlotColumns {
                                 frame_id: self.frame_id(),
                                 column_vec_ptr: Box::into_raw(Box::new(Box::<Vec<String>>::new(model_ctx.summary_actions_ctx().get_columns_for_corr(self.frame_id())))) as u32,
+                                max_points: (plot_width * 2.0) as usize, //a min and a max per pixel of a plot as wide as this frame
                             });
                         }
                     });
@@ -229,6 +465,46 @@ impl View for Summary {
                             model_ctx.queue_action(ActionRq::Histogram {
                                 frame_id: self.frame_id(),
                                 name_ptr: Box::into_raw(Box::new(Box::<String>::new(column_summary.name().into()))) as u32,
+                                params: if matches!(column_summary.dtype(), SummaryColumnType::Datetime { .. }) {
+                                    HistogramParams::new(Binning::Calendar { unit: None }, None, false)
+                                } else {
+                                    HistogramParams::default()
+                                },
+                                histogram_id: None,
+                                compare: false,
+                            });
+                        }
+                        if ui.button("V").clicked() {
+                            model_ctx.queue_action(ActionRq::ValueCounts {
+                                frame_id: self.frame_id(),
+                                name_ptr: Box::into_raw(Box::new(Box::<String>::new(column_summary.name().into()))) as u32,
+                                sort: ValueCountsSort::CountDesc,
+                                offset: 0,
+                                limit: 100,
+                            });
+                        }
+                        if matches!(column_summary.dtype(), SummaryColumnType::Datetime { .. }) && ui.button("T").on_hover_text("time series").clicked() {
+                            model_ctx.queue_action(ActionRq::TimeSeries {
+                                frame_id: self.frame_id(),
+                                params: TimeSeriesParams::new(column_summary.name().into(), vec![]),
+                                time_series_id: None,
+                            });
+                        }
+                        if matches!(column_summary.dtype(), SummaryColumnType::Numeric { .. }) && ui.button("B").on_hover_text("box plot").clicked() {
+                            model_ctx.queue_action(ActionRq::BoxPlot {
+                                frame_id: self.frame_id(),
+                                column: column_summary.name().into(),
+                                split_by: None,
+                                box_plot_id: None,
+                            });
+                        }
+                        if matches!(column_summary.dtype(), SummaryColumnType::Numeric { .. }) && ui.button("K").clicked() {
+                            model_ctx.queue_action(ActionRq::Kde {
+                                frame_id: self.frame_id(),
+                                name_ptr: Box::into_raw(Box::new(Box::<String>::new(column_summary.name().into()))) as u32,
+                                bandwidth: Bandwidth::Silverman,
+                                split_ptr: Box::into_raw(Box::new(Box::<Option<String>>::new(None))) as u32,
+                                target: KdeTarget::New,
                             });
                         }
                         if ui.checkbox(model_ctx.summary_actions_ctx_mut().get_selected_for_corr(self.frame_id(), column_summary.name().into()), "C").clicked() {
@@ -421,6 +697,15 @@ fn add_conditions(condition_type: &ConditionType, ui: &mut Ui, ctx: &mut ModelCt
                         Condition::Boolean { val } => {
                             ui.label(format!("val: {}", val));
                         }
+                        Condition::Equals { value } => {
+                            ui.label(format!("= {}", value.as_ref().map(|v|v.as_str()).unwrap_or("null")));
+                        }
+                        Condition::Range { label, .. } => {
+                            ui.label(format!("in {}", label));
+                        }
+                        Condition::Fences { lower, upper, outside } => {
+                            ui.label(format!("{} {} .. {}", if *outside { "outside" } else { "within" }, lower, upper));
+                        }
                     }
                     if ui.button("-").clicked() {
                         ctx.ui_action(
@@ -527,6 +812,174 @@ impl View for Histogram {
 
     fn ui(&self, ui: &mut egui::Ui, ctx: &Context, model_ctx: &mut ModelCtx) {
 
+        let params_ctx = model_ctx.histogram_params_ctx_mut(self);
+        let mut params = None;
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source(("histogram_binning", *self.id()))
+                .selected_text(params_ctx.binning().label())
+                .show_ui(ui, |ui| {
+                    for b in Binning::all() {
+                        if ui.selectable_label(params_ctx.binning().same_kind(&b), b.label()).clicked() {
+                            params_ctx.set_binning(b);
+                        }
+                    }
+                });
+
+            if let Some(unit) = params_ctx.calendar_unit_mut() {
+                egui::ComboBox::from_id_source(("histogram_calendar_unit", *self.id()))
+                    .selected_text(unit.map(|u| u.label().to_string()).unwrap_or(String::from("auto")))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(unit, None, "auto");
+                        for u in CalendarUnit::all() {
+                            ui.selectable_value(unit, Some(u), u.label());
+                        }
+                    });
+            }
+
+            if params_ctx.has_value() {
+                let hint = match params_ctx.binning() {
+                    Binning::Width { .. } => "width",
+                    Binning::Edges { .. } => "edges: 0, 10, 100",
+                    _ => "bins",
+                };
+                ui.add(egui::TextEdit::singleline(params_ctx.value_mut()).hint_text(hint).desired_width(80.0));
+            }
+
+            ui.add(egui::TextEdit::singleline(params_ctx.min_mut()).hint_text("min").desired_width(60.0));
+            ui.add(egui::TextEdit::singleline(params_ctx.max_mut()).hint_text("max").desired_width(60.0));
+            ui.checkbox(params_ctx.include_nulls_mut(), "n/a");
+            ui.add(egui::TextEdit::singleline(params_ctx.top_n_mut()).hint_text("top N").desired_width(40.0));
+            ui.add_enabled(self.params().compare().is_none(), egui::TextEdit::singleline(params_ctx.split_mut()).hint_text("split by column").desired_width(100.0))
+                .on_disabled_hover_text("the parent and filtered rows are the series while comparing");
+
+            if ui.button("Apply").clicked() {
+                params = params_ctx.to_params();
+            }
+            ui.colored_label(*params_ctx.msg().color(), params_ctx.msg().text().clone());
+        });
+
+        if let Some(params) = params {
+            model_ctx.queue_action(ActionRq::Histogram {
+                frame_id: self.frame_id(),
+                name_ptr: Box::into_raw(Box::new(Box::<String>::new(self.column().to_string()))) as u32,
+                params,
+                histogram_id: Some(*self.id()),
+                compare: self.params().compare().is_some(),
+            });
+        }
+
+        let histogram_id = *self.id();
+        let frame_id = self.frame_id();
+        let view = self.view();
+
+        let change_view = |model_ctx: &mut ModelCtx, view: HistogramView| {
+            model_ctx.ui_action(UIAction::WaFrame { frame_id, action: Box::new(move |frame| {
+                frame.change_histogram_view(histogram_id, view);
+                None
+            })});
+        };
+
+        ui.horizontal(|ui| {
+            for scale in HistogramScale::all() {
+                if ui.selectable_label(view.scale() == scale, scale.label()).clicked() {
+                    change_view(model_ctx, view.with_scale(scale));
+                }
+            }
+
+            //stacks are drawn from the shown values, stacked logs would not add up
+            let stacked = view.layout() == HistogramLayout::Stacked && !self.series().is_empty();
+            let mut log_y = view.log_y() && !stacked;
+            if ui.add_enabled(!stacked, egui::Checkbox::new(&mut log_y, "log y")).on_disabled_hover_text("not for stacked bars").changed() {
+                change_view(model_ctx, view.with_log_y(log_y));
+            }
+
+            let mut compare = self.params().compare().is_some();
+            if self.frame_parent_id().is_some() && ui.checkbox(&mut compare, "compare with parent").on_hover_text("replaces the split").changed() {
+                model_ctx.queue_action(ActionRq::Histogram {
+                    frame_id,
+                    name_ptr: Box::into_raw(Box::new(Box::<String>::new(self.column().to_string()))) as u32,
+                    params: self.params().clone(),
+                    histogram_id: Some(histogram_id),
+                    compare,
+                });
+
+                if compare {
+                    change_view(model_ctx, view.with_normalized(true));
+                }
+            }
+
+            if !self.series().is_empty() {
+                ui.separator();
+
+                for layout in HistogramLayout::all() {
+                    if ui.selectable_label(view.layout() == layout, layout.label()).clicked() {
+                        change_view(model_ctx, view.with_layout(layout));
+                    }
+                }
+
+                let mut normalized = view.normalized();
+                if ui.checkbox(&mut normalized, "% within group").changed() {
+                    change_view(model_ctx, view.with_normalized(normalized));
+                }
+            }
+
+            if !self.edges().is_empty() && !self.temporal() { //kde is for numeric columns only
+                ui.separator();
+
+                let kde_ctx = model_ctx.kde_params_ctx_mut(histogram_id, Bandwidth::Silverman);
+                let mut bandwidth = None;
+
+                bandwidth_ui(ui, ("histogram_kde_bandwidth", histogram_id), kde_ctx);
+                if ui.add_enabled(self.params().compare().is_none(), egui::Button::new("KDE"))
+                    .on_disabled_hover_text("not available while comparing with parent")
+                    .clicked() {
+                    bandwidth = kde_ctx.to_bandwidth();
+                }
+                ui.colored_label(*kde_ctx.msg().color(), kde_ctx.msg().text().clone());
+
+                if let Some(bandwidth) = bandwidth {
+                    model_ctx.queue_action(ActionRq::Kde {
+                        frame_id,
+                        name_ptr: Box::into_raw(Box::new(Box::<String>::new(self.column().to_string()))) as u32,
+                        bandwidth,
+                        split_ptr: Box::into_raw(Box::new(Box::<Option<String>>::new(self.params().split().cloned()))) as u32,
+                        target: KdeTarget::Histogram { histogram_id },
+                    });
+                }
+            }
+        });
+
+        if let Some(condition) = self.selection_condition() {
+            ui.horizontal(|ui| {
+                ui.label(format!("{} selected", self.selected().len()));
+
+                if ui.button("Add to filter").clicked() {
+                    let condition = condition.clone();
+                    model_ctx.ui_action(UIAction::WaFrame { frame_id, action: Box::new(move |frame| {
+                        let filter_id = frame.filter_or_new();
+                        frame.add_filter_condition(condition, None);
+
+                        Some(UIAction::Layout {frame_id: WaModelId::Filter {frame_id:frame.id(), filter_id}, request: LayoutRequest::Center })
+                    })});
+                }
+
+                if ui.button("Filter").on_hover_text("new frame with the selected bars").clicked() {
+                    let mut filter = Filter::empty(frame_id, vec![]);
+                    filter.add_condition(condition.clone(), None);
+
+                    model_ctx.queue_action(ActionRq::ApplyFilter { frame_id, filter });
+                }
+
+                if ui.button("Clear").clicked() {
+                    model_ctx.ui_action(UIAction::WaFrame { frame_id, action: Box::new(move |frame| {
+                        frame.select_histogram_bars(histogram_id, vec![]);
+                        None
+                    })});
+                }
+            });
+        }
+
         let _max_height = ui.available_height() * 0.8;
         let max_width = ui.available_width() * 0.8;
 
@@ -534,25 +987,85 @@ impl View for Histogram {
         let width = max_width/ values.len() as f32;
         // let max = values.iter().map(|v|v.1).max_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal)).unwrap_or(0);
 
-        let y_ratio = 1.; // (max_height / max as f32) as f64;
+        let stacked = view.layout() == HistogramLayout::Stacked && !self.series().is_empty();
 
-        let bars  = values.iter().enumerate().map(|(i, (k, v))|{
-            Bar::new((i as f32 * width) as f64, *v as f64 * y_ratio )
-                .width(width as f64)
-                .name(val_or_na(k))
-        }).collect();
+        let log_y = view.log_y() && !stacked;
+        let to_y = move |v: f64| if log_y { (v + 1.).log10() } else { v };
+        let from_y = move |y: f64| if log_y { 10f64.powf(y) - 1. } else { y };
 
+        let fmt = move |v: f64| if v.fract() == 0. { format!("{}", v) } else { format!("{:.3}", v) };
 
-        let chart = BarChart::new(
-            bars
-        )
-        .element_formatter(Box::new(move |b, _c|{
-                format!("{}, {}", b.name, (b.value/y_ratio) as u32)
-            }))
-        .color(Color32::LIGHT_BLUE)
-        .name(self._title());
+        let series = if self.series().is_empty() {
+            vec![(self._title().to_string(), self.scaled_values())]
+        } else {
+            self.series().iter().enumerate().map(|(j, (name, _))| (name.clone(), self.series_values(j))).collect()
+        };
+
+        let series_n = series.len() as f32;
+
+        let mut charts:Vec<BarChart> = vec![];
+        let mut lines:Vec<Line> = vec![];
+
+        for (j, (name, series_values)) in series.into_iter().enumerate() {
+            if view.scale().is_cumulative() { //ECDF-style steps
+                let points = series_values.iter().enumerate().flat_map(|(i, v)| {
+                    let left = (i as f32 * width - width / 2.) as f64;
+                    [[left, to_y(if i == 0 { 0. } else { series_values[i - 1] })], [left, to_y(*v)], [left + width as f64, to_y(*v)]]
+                }).collect::<Vec<_>>();
+
+                lines.push(Line::new(PlotPoints::new(points)).name(name));
+                continue;
+            }
+
+            let bars = values.iter().zip(series_values).enumerate().map(|(i, ((k, _), v))|{
+                let (x, w) = if stacked || series_n == 1. {
+                    (i as f32 * width, width)
+                } else {
+                    (i as f32 * width - width / 2. + (j as f32 + 0.5) * width / series_n, width / series_n)
+                };
+
+                let bar = Bar::new(x as f64, to_y(v))
+                    .width(w as f64)
+                    .name(val_or_na(k));
+
+                if self.selected().contains(&i) {
+                    bar.fill(Color32::from_rgb(255, 190, 0).linear_multiply(0.5)).stroke(egui::Stroke::new(2.0, Color32::from_rgb(255, 190, 0)))
+                } else {
+                    bar
+                }
+            }).collect();
+
+            let series_name = name.clone();
+            let chart = BarChart::new(bars)
+                .element_formatter(Box::new(move |b, _c|{
+                    format!("{}, {}: {}", b.name, series_name, fmt(from_y(b.value)))
+                }))
+                .name(name);
+
+            let chart = if self.series().is_empty() {
+                chart.color(Color32::LIGHT_BLUE)
+            } else if stacked {
+                chart.stack_on(&charts.iter().collect::<Vec<_>>())
+            } else {
+                chart
+            };
+
+            charts.push(chart);
+        }
+
+        let markers = self.markers().iter()
+            .filter_map(|(name, value)| self.bin_position(*value).map(|p| VLine::new(p * width as f64 - width as f64 / 2.).name(name)))
+            .collect::<Vec<_>>();
+
+        for (name, points) in self.kde_points() { //density curves mapped onto bin positions
+            let points = points.iter()
+                .filter_map(|(x, y)| self.bin_position(*x).map(|p| [p * width as f64 - width as f64 / 2., to_y(*y)]))
+                .collect::<Vec<_>>();
+
+            lines.push(Line::new(PlotPoints::new(points)).name(name).width(1.5_f32));
+        }
 
-        Plot::new("Histogram")
+        let plot = Plot::new("Histogram")
 
             .label_formatter(|name, _value| {
                     // debug!("wapuku: name={:?}, value={:?}", name, value);
@@ -563,14 +1076,45 @@ impl View for Histogram {
                         "".to_owned()
                     }
                 })
+            .y_axis_formatter(move |mark, _digits, _range| fmt(from_y(mark.value)))
             .allow_zoom(true)
             .allow_drag(true)
-            .custom_x_axes(vec![])
-            .show(ui, |plot_ui| {
-                plot_ui.bar_chart(chart);
+            .custom_x_axes(vec![]);
+
+        let plot = if self.series().is_empty() && markers.is_empty() && lines.is_empty() {
+            plot
+        } else {
+            plot.legend(Legend::default())
+        };
 
+        let plot = plot.show(ui, |plot_ui| {
+                for chart in charts {
+                    plot_ui.bar_chart(chart);
+                }
+                for line in lines {
+                    plot_ui.line(line);
+                }
+                for marker in markers {
+                    plot_ui.vline(marker);
+                }
+                plot_ui.pointer_coordinate()
             });
 
+        if plot.response.clicked() { //shift-click adds to the selection
+            let bar = plot.inner
+                .map(|p| (p.x / width as f64 + 0.5).floor())
+                .filter(|bar| *bar >= 0. && (*bar as usize) < values.len() && self.is_selectable(*bar as usize));
+
+            if let Some(bar) = bar {
+                let selected = self.toggle_selected(bar as usize, ui.input(|i| i.modifiers.shift));
+
+                model_ctx.ui_action(UIAction::WaFrame { frame_id, action: Box::new(move |frame| {
+                    frame.select_histogram_bars(histogram_id, selected);
+                    None
+                })});
+            }
+        }
+
     }
 
     fn model_id(&self) -> WaModelId {
@@ -578,7 +1122,25 @@ impl View for Histogram {
     }
 }
 
-impl View for Corrs {
+fn bandwidth_ui(ui: &mut Ui, id_source: impl std::hash::Hash, kde_ctx: &mut KdeParamsCtx) {
+    let bandwidth = kde_ctx.bandwidth_mut();
+
+    egui::ComboBox::from_id_source(id_source)
+        .selected_text(bandwidth.label().to_string())
+        .show_ui(ui, |ui| {
+            for b in Bandwidth::all() {
+                if ui.selectable_label(bandwidth.label() == b.label(), b.label()).clicked() {
+                    *bandwidth = b;
+                }
+            }
+        });
+
+    if matches!(bandwidth, Bandwidth::Manual { .. }) {
+        ui.add(egui::TextEdit::singleline(kde_ctx.h_mut()).hint_text("h").desired_width(60.0));
+    }
+}
+
+impl View for Kde {
     fn title(&self) -> &str {
         self._title()
     }
@@ -587,109 +1149,338 @@ impl View for Corrs {
         Id::new(self.id())
     }
 
+    fn ui(&self, ui: &mut Ui, _ctx: &Context, model_ctx: &mut ModelCtx) {
+        let kde_id = *self.id();
+        let kde_ctx = model_ctx.kde_params_ctx_mut(kde_id, self.bandwidth());
+        let mut bandwidth = None;
 
-
-    fn ui(&self, ui: &mut Ui, ctx: &Context, model_ctx: &mut ModelCtx) {
         ui.horizontal(|ui| {
-            ui.add(egui::Label::new(self.title()));
-
-            if ui.button("Plot data").clicked() {
-                model_ctx.queue_action(ActionRq::PlotColumns {
-                    frame_id: *self.frame_id(),
-                    column_vec_ptr: Box::into_raw(Box::new(Box::<Vec<String>>::new(self.columns().clone()))) as u32,
-                });
-            }
-        });
+            bandwidth_ui(ui, ("kde_bandwidth", kde_id), kde_ctx);
 
-        let _columns = self.columns().clone();
-        let top_right = _columns.len() as f64;
-        debug!("_columns={:?}", _columns);
-
-        let x_fmt = move |x: f64, _digits, _range: &RangeInclusive<f64>| {
-            let x_1 = x;
-            if x >= 0.0 && (x_1).fract() == 0.0 {
-                _columns
-                    .get(x_1 as usize)
-                    .map(|v| v.clone())
-                    .unwrap_or("".into()) //format!("{:?}", x))
-            } else {
-                "".into()
+            if ui.button("Apply").clicked() {
+                bandwidth = kde_ctx.to_bandwidth();
             }
-        };
-
-        let columns = self.columns().clone();
-
-        let charts = columns.iter().enumerate()
-            .map(|(row, c_in_row)| {
-
-                BarChart::new(
-                    columns.iter().enumerate().map(|(i, c)|
-                        Bar::new(i as f64, 1.0).base_offset(-0.5 + row as f64).name(format!("{}", self.get_val(c_in_row, c)))
-                    ).collect::<Vec<_>>()
-                ).width(1.0)
+            ui.colored_label(*kde_ctx.msg().color(), kde_ctx.msg().text().clone());
 
-            }).collect::<Vec<BarChart>>();
-
-        let y_fmt = move |y: f64, _digits, _range: &RangeInclusive<f64>| {
-            let y_1 = y;
-            if y >= 0.0 && (y_1).fract() == 0.0 {
-                columns
-                    .get(y_1 as usize)
-                    .map(|v| v.clone())
-                    .unwrap_or("".into()) //format!("{:?}", y))
-            } else {
-                "".into()
+            if let Some(sample_size) = self.sample_size() {
+                ui.label(format!("estimated on a sample of {} rows", sample_size));
             }
-        };
+        });
 
-        let mut center = model_ctx.is_init();
-        model_ctx.set_is_init(false);//TODO move to app
-        if ui.button("center").clicked() {
-            center = true;
+        if let Some(bandwidth) = bandwidth {
+            model_ctx.queue_action(ActionRq::Kde {
+                frame_id: self.frame_id(),
+                name_ptr: Box::into_raw(Box::new(Box::<String>::new(self.column().clone()))) as u32,
+                bandwidth,
+                split_ptr: Box::into_raw(Box::new(Box::<Option<String>>::new(self.split().cloned()))) as u32,
+                target: KdeTarget::Replace { kde_id },
+            });
         }
 
-
-
-        let plot = Plot::new("Correlations")
-            .label_formatter(|name, _value| {
-                // debug!("wapuku: name={:?}, value={:?}", name, value);
-
-                if !name.is_empty() {
-                    name.to_owned()
-                } else {
-                    "".to_owned()
-                }
-            })
+        Plot::new(("Kde", kde_id))
+            .legend(Legend::default())
             .allow_zoom(true)
             .allow_drag(true)
-            .custom_x_axes(vec![AxisHints::default().formatter(x_fmt).max_digits(4)])
-            .custom_y_axes(vec![AxisHints::default().formatter(y_fmt).max_digits(4)])
-            .view_aspect(1.0)
-            .data_aspect(1.0)
-            // .include_x([-1., top_right])
-            // .include_y([-1., top_right])
             .allow_scroll(false)
-            // .height(200.)
-            // .width(200.)
-            .show_grid(false)
+            .show_grid(true)
             .show(ui, |plot_ui| {
-                if center {
-                    plot_ui.set_plot_bounds(PlotBounds::from_min_max([-1., -1.], [top_right, top_right]));
-                    // plot_ui.zoom_bounds([-10., -10.].into())
+                for series in self.series() {
+                    let points = series.points().iter().map(|(x, y)| [*x, *y]).collect::<Vec<_>>();
+                    plot_ui.line(Line::new(PlotPoints::new(points)).name(series.name()).width(1.5_f32));
                 }
+            });
+    }
 
+    fn allows_scroll(&self) -> bool {
+        false
+    }
 
-                for chart in charts {
-                    plot_ui.bar_chart(chart);
+    fn model_id(&self) -> WaModelId {
+        WaModelId::Kde{ frame_id: self.frame_id(), kde_id: *self.id() }
+    }
+}
+
+impl View for Histogram2D {
+    fn title(&self) -> &str {
+        self._title()
+    }
+
+    fn ui_id(&self) -> Id {
+        Id::new(self.id())
+    }
+
+    fn ui(&self, ui: &mut Ui, _ctx: &Context, model_ctx: &mut ModelCtx) {
+        ui.label("click a cell to filter by its ranges");
+
+        let max = self.max_count().max(1) as f32;
+        let cell_color = |count: u32| {
+            let t = count as f32 / max;
+            let c = |from: f32, to: f32| (from + (to - from) * t) as u8;
+            Color32::from_rgb(c(235., 8.), c(245., 48.), c(255., 107.))
+        };
+
+        let cells = (0..self.bins_y()).flat_map(|y| (0..self.bins_x()).map(move |x| (x, y)))
+            .filter(|(x, y)| self.count(*x, *y) > 0)
+            .map(|(x, y)| {
+                let (x0, y0) = (x as f64 - 0.5, y as f64 - 0.5);
+                Polygon::new(PlotPoints::new(vec![[x0, y0], [x0 + 1., y0], [x0 + 1., y0 + 1.], [x0, y0 + 1.]]))
+                    .fill_color(cell_color(self.count(x, y)))
+                    .stroke(egui::Stroke::NONE)
+            })
+            .collect::<Vec<_>>();
+
+        let cell_at = |x: f64, y: f64| {
+            let (x, y) = ((x + 0.5).floor(), (y + 0.5).floor());
+            if x >= 0. && y >= 0. && (x as usize) < self.bins_x() && (y as usize) < self.bins_y() {
+                Some((x as usize, y as usize))
+            } else {
+                None
+            }
+        };
+
+        let axis_fmt = |labels: Vec<String>| move |mark: GridMark, _digits: usize, _range: &RangeInclusive<f64>| {
+            if mark.value >= 0.0 && mark.value.fract() == 0.0 {
+                labels.get(mark.value as usize).cloned().unwrap_or_default()
+            } else {
+                String::new()
+            }
+        };
+
+        let (column_x, column_y) = (self.column_x().clone(), self.column_y().clone());
+        let (labels_x, labels_y) = (self.labels_x().clone(), self.labels_y().clone());
+        let counts = self.counts().clone();
+
+        let plot = Plot::new(("Histogram2D", *self.id()))
+            .label_formatter(move |_name, value| {
+                let (x, y) = ((value.x + 0.5).floor(), (value.y + 0.5).floor());
+                if x < 0. || y < 0. {
+                    return String::new();
+                }
+
+                match (labels_x.get(x as usize), labels_y.get(y as usize)) {
+                    (Some(label_x), Some(label_y)) => format!(
+                        "{}: {}\n{}: {}\ncount: {}",
+                        column_x, label_x, column_y, label_y, counts[y as usize][x as usize]
+                    ),
+                    _ => String::new(),
+                }
+            })
+            .custom_x_axes(vec![AxisHints::default().formatter(axis_fmt(self.labels_x().clone())).label(self.column_x())])
+            .custom_y_axes(vec![AxisHints::default().formatter(axis_fmt(self.labels_y().clone())).label(self.column_y())])
+            .allow_zoom(true)
+            .allow_drag(true)
+            .allow_scroll(false)
+            .show_grid(false)
+            .show(ui, |plot_ui| {
+                for cell in cells {
+                    plot_ui.polygon(cell);
+                }
+                plot_ui.pointer_coordinate()
+            });
+
+        if plot.response.clicked() {
+            let condition = plot.inner
+                .and_then(|p| cell_at(p.x, p.y))
+                .filter(|(x, y)| self.count(*x, *y) > 0)
+                .and_then(|(x, y)| self.to_condition(x, y));
+
+            if let Some(condition) = condition {
+                model_ctx.ui_action(
+                    UIAction::WaFrame { frame_id: self.frame_id(), action: Box::new(move |frame| {
+                        let filter_id = frame.filter_or_new();
+                        frame.add_filter_condition(condition, None);
+
+                        Some(UIAction::Layout {frame_id: WaModelId::Filter {frame_id:frame.id(), filter_id}, request: LayoutRequest::Center })
+                    })}
+                );
+            }
+        }
+
+        ui.set_clip_rect(plot.response.rect);
+        ui.shrink_height_to_current();
+        ui.shrink_width_to_current();
+    }
+
+    fn allows_scroll(&self) -> bool {
+        false
+    }
+
+    fn model_id(&self) -> WaModelId {
+        WaModelId::Histogram2D{ frame_id: self.frame_id(), histogram_id: *self.id() }
+    }
+}
+
+fn format_corr(value: f32) -> String {
+    if value != 0.0 && (value.abs() >= 1000.0 || value.abs() < 0.01) {
+        format!("{:.2e}", value)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+fn corr_label(c_0: &str, c_1: &str, corr: &CorrValue) -> String {
+    match corr.value() {
+        Some(value) if c_0 == c_1 => format!("{}\nvariance: {}\nn={}", c_0, format_corr(value), corr.n()),
+        Some(value) => {
+            let ci = match corr.ci() {
+                Some((lo, hi)) => format!(", 95% CI [{:.3}, {:.3}]", lo, hi),
+                None if matches!(corr.measure(), CorrMeasure::CramersV | CorrMeasure::CorrelationRatio) => String::from(", CI n/a"), //no closed form interval
+                None => String::new(),
+            };
+            let p = corr.p_value().map(|p| format!(", p={:.4}", p)).unwrap_or_default();
+            let significance = if corr.is_significant() { "" } else { "\nnot significant" };
+            format!("{} × {}\n{}: {}\nn={}{}{}{}", c_0, c_1, corr.measure().label(), format_corr(value), corr.n(), p, ci, significance)
+        }
+        None => format!("{} × {}\n{}: undefined\nn={}", c_0, c_1, corr.measure().label(), corr.n()),
+    }
+}
+
+impl View for Corrs {
+    fn title(&self) -> &str {
+        self._title()
+    }
+
+    fn ui_id(&self) -> Id {
+        Id::new(self.id())
+    }
+
+
+
+    fn ui(&self, ui: &mut Ui, ctx: &Context, model_ctx: &mut ModelCtx) {
+        let corrs_id = *self.id();
+        let frame_id = *self.frame_id();
+
+        ui.horizontal(|ui| {
+            ui.add(egui::Label::new(self.title()));
+
+            //scatter needs two distinct columns of which neither is categoric
+            let scatter_pair = self.selected()
+                .filter(|(c_x, c_y)| c_x != c_y && !matches!(self.get_measure(c_x, c_y), None | Some(CorrMeasure::CramersV) | Some(CorrMeasure::CorrelationRatio)))
+                .cloned();
+
+            if ui.add_enabled(scatter_pair.is_some(), egui::Button::new("Plot data")).on_disabled_hover_text("click a cell of two numeric columns").clicked() {
+                if let Some((c_x, c_y)) = scatter_pair {
+                    model_ctx.queue_action(ActionRq::Scatter {
+                        frame_id,
+                        params: ScatterParams::new(c_x, c_y),
+                        scatter_id: None,
+                    });
+                }
+            }
+
+            let mut clustered = self.is_clustered();
+            if ui.checkbox(&mut clustered, "cluster order").changed() {
+                model_ctx.ui_action(UIAction::WaFrame { frame_id, action: Box::new(move |frame| {
+                    frame.cluster_corrs(corrs_id, clustered);
+                    None
+                })});
+            }
+
+            ui.weak(format!("faded: not significant (p ≥ {}), grey: undefined, click a cell to select a pair", CORR_ALPHA));
+        });
+
+        let columns = self.ordered_columns();
+        let top_right = columns.len() as f64;
+        let selected = self.selected().cloned();
+
+        //cells in display order, row is y, column is x
+        let cells = columns.iter().enumerate()
+            .flat_map(|(y, c_y)| columns.iter().enumerate().map(move |(x, c_x)| (x, y, c_x, c_y)))
+            .map(|(x, y, c_x, c_y)| {
+                let corr = self.get(c_x, c_y);
+                let scale = corr.map(|c| self.scale(c.measure())).unwrap_or(1.0);
+                let (fill, text) = match corr {
+                    Some(corr) => match corr.value() {
+                        //diagonal of a covariance matrix holds the variances
+                        Some(value) if corr.is_significant() || c_x == c_y => (diverging_color(value / scale), format_corr(value)),
+                        Some(value) => (faded(diverging_color(value / scale)), format_corr(value)),
+                        None => (Color32::from_gray(220), String::from("–")),
+                    },
+                    None if c_x == c_y => (diverging_color(1.0), String::from("1")),
+                    None => (Color32::from_gray(220), String::new()),
+                };
+                let text_color = if corr.and_then(|c| c.value()).map(|v| v.abs() / scale > 0.6).unwrap_or(c_x == c_y) { Color32::WHITE } else { Color32::BLACK };
+
+                let stroke = if selected.as_ref().map(|(s_x, s_y)| s_x == c_x && s_y == c_y).unwrap_or(false) {
+                    egui::Stroke::new(2.5, Color32::BLACK)
+                } else {
+                    egui::Stroke::new(1.0, Color32::WHITE)
+                };
+
+                let (x0, y0) = (x as f64 - 0.5, y as f64 - 0.5);
+                (
+                    Polygon::new(PlotPoints::new(vec![[x0, y0], [x0 + 1., y0], [x0 + 1., y0 + 1.], [x0, y0 + 1.]]))
+                        .fill_color(fill)
+                        .stroke(stroke),
+                    Text::new(PlotPoint::new(x as f64, y as f64), RichText::new(text).color(text_color)),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let labels = columns.iter()
+            .map(|c_y| columns.iter()
+                .map(|c_x| self.get(c_x, c_y).map(|corr| corr_label(c_x, c_y, corr)).unwrap_or_default())
+                .collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let axis_fmt = |labels: Vec<String>| move |mark: GridMark, _digits: usize, _range: &RangeInclusive<f64>| {
+            if mark.value >= 0.0 && mark.value.fract() == 0.0 {
+                labels.get(mark.value as usize).cloned().unwrap_or_default()
+            } else {
+                String::new()
+            }
+        };
+
+        let mut center = model_ctx.is_init();
+        model_ctx.set_is_init(false);//TODO move to app
+        if ui.button("center").clicked() {
+            center = true;
+        }
+
+        let plot = Plot::new(("Correlations", corrs_id))
+            .label_formatter(move |_name, value| {
+                let (x, y) = ((value.x + 0.5).floor(), (value.y + 0.5).floor());
+                if x < 0. || y < 0. {
+                    return String::new();
+                }
+
+                labels.get(y as usize).and_then(|row| row.get(x as usize)).cloned().unwrap_or_default()
+            })
+            .allow_zoom(true)
+            .allow_drag(true)
+            .custom_x_axes(vec![AxisHints::default().formatter(axis_fmt(columns.clone())).max_digits(4)])
+            .custom_y_axes(vec![AxisHints::default().formatter(axis_fmt(columns.clone())).max_digits(4)])
+            .view_aspect(1.0)
+            .data_aspect(1.0)
+            .allow_scroll(false)
+            .show_grid(false)
+            .show(ui, |plot_ui| {
+                if center {
+                    plot_ui.set_plot_bounds(PlotBounds::from_min_max([-1., -1.], [top_right, top_right]));
                 }
 
+                for (cell, text) in cells {
+                    plot_ui.polygon(cell);
+                    plot_ui.text(text);
+                }
 
-                // let bounds = plot_ui.plot_bounds();
-                // debug!(">>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>..... bounds={:?}", bounds);
-                // plot_ui.polygon()
-                // plot_ui.polygon(Polygon::new(PlotPoints::Owned(vec![[-10., -10.].into(), [-10., 10.].into(), [10., 10.].into(), [10., -10.].into() ])).fill_color(Color32::LIGHT_YELLOW))
+                plot_ui.pointer_coordinate()
             });
 
+        if plot.response.clicked() {
+            if let Some((x, y)) = plot.inner.map(|p| ((p.x + 0.5).floor(), (p.y + 0.5).floor())).filter(|(x, y)| *x >= 0. && *y >= 0.) {
+                if let (Some(c_x), Some(c_y)) = (columns.get(x as usize), columns.get(y as usize)) {
+                    let clicked = (c_x.clone(), c_y.clone());
+                    let selected = if selected.as_ref() == Some(&clicked) { None } else { Some(clicked) };
+
+                    model_ctx.ui_action(UIAction::WaFrame { frame_id, action: Box::new(move |frame| {
+                        frame.select_corrs_pair(corrs_id, selected);
+                        None
+                    })});
+                }
+            }
+        }
+
         ui.set_clip_rect(plot.response.rect);
         ui.shrink_height_to_current();
         ui.shrink_width_to_current();
@@ -734,10 +1525,15 @@ impl View for ColumnsPlot {
                         })
                         .collect::<Vec<_>>();
 
+                    let name = match series.downsampled_from() {
+                        Some(from) => format!("{} ({} of {} points)", series.name(), series.points().len(), from),
+                        None => series.name().clone(),
+                    };
+
                     if !points.is_empty() {
                         plot_ui.line(
                             Line::new(points)
-                                .name(series.name())
+                                .name(name)
                                 .width(1.5_f32)
                         );
                     }
@@ -758,7 +1554,7 @@ impl View for ColumnsPlot {
     }
 }
 
-impl View for DataLump {
+impl View for Scatter {
     fn title(&self) -> &str {
         self._title()
     }
@@ -767,80 +1563,951 @@ impl View for DataLump {
         Id::new(self.id())
     }
 
-    fn ui(&self, ui: &mut egui::Ui, ctx: &Context, model_ctx: &mut ModelCtx) {
-        let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
+    fn ui(&self, ui: &mut Ui, _ctx: &Context, model_ctx: &mut ModelCtx) {
+        let scatter_id = *self.id();
+        let frame_id = self.frame_id();
+        let params = self.params();
 
         ui.horizontal(|ui| {
+            let mut color_by = params.color_by().cloned();
+            egui::ComboBox::from_id_source(("scatter_color_by", scatter_id))
+                .selected_text(format!("color: {}", color_by.as_deref().unwrap_or("none")))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut color_by, None, "none");
+                    for column in self.categoric_columns() {
+                        ui.selectable_value(&mut color_by, Some(column.clone()), column);
+                    }
+                });
 
-            if self.offset() > &0 {
+            let mut size_by = params.size_by().cloned();
+            egui::ComboBox::from_id_source(("scatter_size_by", scatter_id))
+                .selected_text(format!("size: {}", size_by.as_deref().unwrap_or("none")))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut size_by, None, "none");
+                    for column in self.numeric_columns() {
+                        ui.selectable_value(&mut size_by, Some(column.clone()), column);
+                    }
+                });
 
-                if ui.button("Prev").clicked() {
+            if color_by.as_ref() != params.color_by() || size_by.as_ref() != params.size_by() {
+                model_ctx.queue_action(ActionRq::Scatter {
+                    frame_id,
+                    params: params.clone().with_color_by(color_by).with_size_by(size_by),
+                    scatter_id: Some(scatter_id),
+                });
+            }
 
-                    model_ctx.queue_action(ActionRq::DataLump {
-                        frame_id: *self.frame_id(),
-                        offset: if self.offset() > &100 {*self.offset() - 100} else {0},
-                        limit: 100
-                    });
-                };
+            if self.is_sampled() {
+                ui.weak(format!("shown {} of {} rows (sampled)", self.shown(), self.rows()));
+            } else {
+                ui.weak(format!("{} rows", self.rows()));
             }
+        });
 
-            ui.add(egui::Label::new(format!("{}-{}", self.offset(), self.offset()+self.data().len())));
+        let size_range = self.size_range();
+        let size_class = |size: f64| match size_range {
+            Some((min, max)) if max > min => (((size - min) / (max - min)) * (SCATTER_SIZE_CLASSES - 1) as f64).round() as usize,
+            _ => 0,
+        };
 
-            if ui.button("Next").clicked() {
+        let (column_x, column_y) = (params.column_x().clone(), params.column_y().clone());
 
-                model_ctx.queue_action(ActionRq::DataLump {
-                    frame_id: *self.frame_id(),
-                    offset: *self.offset() + 100,
-                    limit: 100
-                });
+        let plot = Plot::new(("Scatter", scatter_id))
+            .legend(Legend::default())
+            .label_formatter(move |name, value| {
+                let name = if name.is_empty() { String::new() } else { format!("{}\n", name) };
+                format!("{}{}: {:.3}\n{}: {:.3}", name, column_x, value.x, column_y, value.y)
+            })
+            .custom_x_axes(vec![AxisHints::default().label(params.column_x())])
+            .custom_y_axes(vec![AxisHints::default().label(params.column_y())])
+            .allow_zoom(true)
+            .allow_drag(true)
+            .allow_scroll(false)
+            .show_grid(true)
+            .show(ui, |plot_ui| {
+                for (i, series) in self.series().iter().enumerate() {
+                    let color = series_color(i);
 
-            };
-        });
+                    if series.sizes().is_empty() {
+                        plot_ui.points(Points::new(series.points().clone()).radius(2.0).color(color).name(series.name()));
+                    } else {
+                        //one item per marker size, all under the series name
+                        let mut classes = vec![vec![]; SCATTER_SIZE_CLASSES];
+                        for (point, size) in series.points().iter().zip(series.sizes()) {
+                            classes[size_class(*size)].push(*point);
+                        }
 
-        let mut table = TableBuilder::new(ui)
-            .striped(true)
-            .resizable(true)
-            .cell_layout(egui::Layout::left_to_right(egui::Align::LEFT));
+                        for (class, points) in classes.into_iter().enumerate().filter(|(_, points)| !points.is_empty()) {
+                            plot_ui.points(Points::new(points).radius(1.5 + 1.5 * class as f32).color(color).name(series.name()));
+                        }
+                    }
+                }
+            });
 
+        ui.set_clip_rect(plot.response.rect);
+        ui.shrink_height_to_current();
+        ui.shrink_width_to_current();
+    }
 
-        let columns = self.columns();
+    fn allows_scroll(&self) -> bool {
+        false
+    }
 
-        table = columns.iter().fold(table, |t, _c|{
-            t.column(Column::auto().at_least(20.0).resizable(true).clip(true))
-        });
-            // .column(Column::auto().at_least(200.0).resizable(true).clip(true))
-            // .column(Column::auto().at_least(200.0).resizable(true).clip(true))
-            // .column(Column::remainder());
+    fn model_id(&self) -> WaModelId {
+        WaModelId::Scatter{ frame_id: self.frame_id(), scatter_id: *self.id() }
+    }
+}
 
-        table.header(20.0, |mut header| {
+impl View for BoxPlot {
+    fn title(&self) -> &str {
+        self._title()
+    }
 
-            columns.iter().for_each(|c|{
-                header.col(|ui| {
-                    ui.strong(c.1.clone());
+    fn ui_id(&self) -> Id {
+        Id::new(self.id())
+    }
+
+    fn ui(&self, ui: &mut Ui, _ctx: &Context, model_ctx: &mut ModelCtx) {
+        let box_plot_id = *self.id();
+        let frame_id = self.frame_id();
+
+        ui.horizontal(|ui| {
+            let mut split_by = self.split_by().cloned();
+            egui::ComboBox::from_id_source(("box_plot_split_by", box_plot_id))
+                .selected_text(format!("split: {}", split_by.as_deref().unwrap_or("none")))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut split_by, None, "none");
+                    for column in self.categoric_columns() {
+                        ui.selectable_value(&mut split_by, Some(column.clone()), column);
+                    }
                 });
-            });
 
+            if split_by.as_ref() != self.split_by() {
+                model_ctx.queue_action(ActionRq::BoxPlot {
+                    frame_id,
+                    column: self.column().clone(),
+                    split_by,
+                    box_plot_id: Some(box_plot_id),
+                });
+            }
 
-        }).body(|body| {
-            let mut data_iter = self.data().iter();
+            let mut violin = self.is_violin();
+            if ui.checkbox(&mut violin, "violin").changed() {
+                model_ctx.ui_action(UIAction::WaFrame { frame_id, action: Box::new(move |frame| {
+                    frame.set_box_plot_violin(box_plot_id, violin);
+                    None
+                })});
+            }
 
-            body.rows(2. * text_height, self.data().len(), |mut row| {
-                // let column_summary = &self.data()[row_index];
+            let (outliers, shown) = self.groups().iter().fold((0, 0), |(outliers, shown), g| (outliers + g.outlier_count(), shown + g.outliers().len()));
+            if shown < outliers {
+                ui.weak(format!("shown {} of {} outliers", shown, outliers));
+            }
+        });
 
-                if let Some(row_data) = data_iter.next(){
-                    row_data.iter().for_each(|col_data|{
-                        row.col(|ui| {
-                            ui.label(col_data.as_ref().map(|v|v.clone()).unwrap_or(String::from("n/a")));
-                        });
-                    });
+        let names = self.groups().iter().map(|g| g.name().to_string()).collect::<Vec<_>>();
+        let violin = self.is_violin();
+
+        //groups along y, so violins can be filled with horizontal reference lines
+        let plot = Plot::new(("BoxPlot", box_plot_id))
+            .custom_x_axes(vec![AxisHints::default().label(self.column())])
+            .custom_y_axes(vec![AxisHints::default().formatter(move |mark, _digits, _range| {
+                if mark.value >= 0.0 && mark.value.fract() == 0.0 {
+                    names.get(mark.value as usize).cloned().unwrap_or_default()
+                } else {
+                    String::new()
+                }
+            })])
+            .allow_zoom(true)
+            .allow_drag(true)
+            .allow_scroll(false)
+            .show_grid(true)
+            .show(ui, |plot_ui| {
+                for (i, group) in self.groups().iter().enumerate() {
+                    let y = i as f64;
+                    let color = series_color(i);
+
+                    let max_density = group.density().iter().map(|(_, d)| *d).fold(0.0, f64::max);
+                    if violin && max_density > 0.0 {
+                        for side in [1.0, -1.0] {
+                            let outline = group.density().iter().map(|(v, d)| [*v, y + side * 0.4 * d / max_density]).collect::<Vec<_>>();
+                            plot_ui.line(Line::new(outline).color(color).fill(y as f32).name(group.name()));
+                        }
+                    }
+
+                    let box_width = if violin { 0.1 } else { 0.5 };
+                    let (low, high) = group.whiskers();
+
+                    plot_ui.box_plot(
+                        egui_plot::BoxPlot::new(vec![
+                            BoxElem::new(y, BoxSpread::new(low, group.q1(), group.median(), group.q3(), high))
+                                .name(format!("{} (n={})", group.name(), group.n()))
+                                .box_width(box_width)
+                                .whisker_width(box_width * 0.6)
+                        ])
+                        .horizontal()
+                        .color(color)
+                        .name(group.name())
+                    );
+
+                    if !group.outliers().is_empty() {
+                        let outliers = group.outliers().iter().map(|v| [*v, y]).collect::<Vec<_>>();
+                        plot_ui.points(Points::new(outliers).radius(2.0).color(color).name(group.name()));
+                    }
                 }
+            });
 
+        ui.set_clip_rect(plot.response.rect);
+        ui.shrink_height_to_current();
+        ui.shrink_width_to_current();
+    }
 
-            })
-        });
+    fn allows_scroll(&self) -> bool {
+        false
     }
 
     fn model_id(&self) -> WaModelId {
-        WaModelId::DataLump{ frame_id: *self.frame_id(), lump_id: *self.id() }
+        WaModelId::BoxPlot{ frame_id: self.frame_id(), box_plot_id: *self.id() }
     }
 }
+
+impl View for TimeSeries {
+    fn title(&self) -> &str {
+        self._title()
+    }
+
+    fn ui_id(&self) -> Id {
+        Id::new(self.id())
+    }
+
+    fn ui(&self, ui: &mut Ui, _ctx: &Context, model_ctx: &mut ModelCtx) {
+        let time_series_id = *self.id();
+        let params = self.params();
+
+        ui.horizontal(|ui| {
+            let mut column_x = params.column_x().clone();
+            egui::ComboBox::from_id_source(("time_series_x", time_series_id))
+                .selected_text(format!("x: {}", column_x))
+                .show_ui(ui, |ui| {
+                    for column in self.datetime_columns() {
+                        ui.selectable_value(&mut column_x, column.clone(), column);
+                    }
+                });
+
+            let mut columns_y = params.columns_y().clone();
+            egui::ComboBox::from_id_source(("time_series_y", time_series_id))
+                .selected_text(if columns_y.is_empty() { String::from("y: rows") } else { format!("y: {}", columns_y.join(", ")) })
+                .show_ui(ui, |ui| {
+                    for column in self.numeric_columns() {
+                        let mut checked = columns_y.contains(column);
+                        if ui.checkbox(&mut checked, column).changed() {
+                            if checked {
+                                columns_y.push(column.clone());
+                            } else {
+                                columns_y.retain(|c| c != column);
+                            }
+                        }
+                    }
+                });
+
+            let mut unit = params.unit();
+            egui::ComboBox::from_id_source(("time_series_unit", time_series_id))
+                .selected_text(match unit {
+                    Some(unit) => format!("every: {}", unit.label()),
+                    None => format!("every: auto ({})", self.unit().label()),
+                })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut unit, None, "auto");
+                    for u in CalendarUnit::all() {
+                        ui.selectable_value(&mut unit, Some(u), u.label());
+                    }
+                });
+
+            let mut agg = params.agg();
+            ui.add_enabled_ui(!columns_y.is_empty(), |ui| {
+                egui::ComboBox::from_id_source(("time_series_agg", time_series_id))
+                    .selected_text(agg.label())
+                    .show_ui(ui, |ui| {
+                        for a in ResampleAgg::all() {
+                            ui.selectable_value(&mut agg, a, a.label());
+                        }
+                    });
+            });
+
+            let mut rolling = params.rolling().is_some();
+            ui.checkbox(&mut rolling, "rolling mean");
+
+            //the window is sent once dragging or typing is done, not on every drag step
+            let edited_window = model_ctx.rolling_window_mut(time_series_id, params.rolling().unwrap_or(TIME_SERIES_ROLLING_WINDOW));
+            let mut window = params.rolling().unwrap_or(*edited_window);
+            if rolling {
+                let response = ui.add(egui::DragValue::new(edited_window).clamp_range(2..=365).suffix(" periods"));
+                if response.drag_released() || response.lost_focus() {
+                    window = *edited_window;
+                }
+            }
+
+            let changed = TimeSeriesParams::new(column_x, columns_y)
+                .with_unit(unit)
+                .with_agg(agg)
+                .with_rolling(rolling.then_some(window));
+
+            if changed != *params {
+                model_ctx.queue_action(ActionRq::TimeSeries {
+                    frame_id: self.frame_id(),
+                    params: changed,
+                    time_series_id: Some(time_series_id),
+                });
+            }
+        });
+
+        let plot = Plot::new(("TimeSeries", time_series_id))
+            .legend(Legend::default())
+            .x_grid_spacer(time_grid_spacer)
+            .custom_x_axes(vec![AxisHints::default()
+                .label(params.column_x())
+                .formatter(|mark, _digits, _range| format_timestamp_ms(mark.value, mark.step_size))])
+            .label_formatter(|name, value| {
+                let name = if name.is_empty() { String::new() } else { format!("{}\n", name) };
+                format!("{}{}\n{:.3}", name, format_timestamp_ms(value.x, 0.0), value.y)
+            })
+            .allow_zoom(true)
+            .allow_drag(true)
+            .allow_scroll(false)
+            .show_grid(true)
+            .show(ui, |plot_ui| {
+                for (i, series) in self.series().iter().enumerate() {
+                    let points = series.points().iter().map(|(x, y)| [*x, *y]).collect::<Vec<_>>();
+                    plot_ui.line(Line::new(points).color(series_color(i)).name(series.name()).width(1.5_f32));
+                }
+
+                //overlays share the color of their series
+                for (i, series) in self.rolling().iter().enumerate() {
+                    let points = series.points().iter().map(|(x, y)| [*x, *y]).collect::<Vec<_>>();
+                    plot_ui.line(Line::new(points).color(series_color(i)).style(LineStyle::dashed_loose()).name(series.name()).width(2.0_f32));
+                }
+            });
+
+        ui.set_clip_rect(plot.response.rect);
+        ui.shrink_height_to_current();
+        ui.shrink_width_to_current();
+    }
+
+    fn allows_scroll(&self) -> bool {
+        false
+    }
+
+    fn model_id(&self) -> WaModelId {
+        WaModelId::TimeSeries{ frame_id: self.frame_id(), time_series_id: *self.id() }
+    }
+}
+
+impl View for PairPlot {
+    fn title(&self) -> &str {
+        self._title()
+    }
+
+    fn ui_id(&self) -> Id {
+        Id::new(self.id())
+    }
+
+    fn ui(&self, ui: &mut Ui, _ctx: &Context, _model_ctx: &mut ModelCtx) {
+        let pair_plot_id = *self.id();
+        let columns = self.columns();
+
+        if self.is_sampled() {
+            ui.weak(format!("sample of {} of {} complete rows", self.shown(), self.rows()));
+        } else {
+            ui.weak(format!("{} complete rows", self.rows()));
+        }
+
+        let cell = ((ui.available_width() - PAIR_PLOT_LABEL_WIDTH) / columns.len() as f32).clamp(60.0, 160.0);
+        let color = series_color(0);
+
+        egui::Grid::new(("pair_plot_grid", pair_plot_id)).spacing([2.0, 2.0]).show(ui, |ui| {
+            for (y, column_y) in columns.iter().enumerate() {
+                ui.label(column_y);
+
+                for (x, column_x) in columns.iter().enumerate() {
+                    let plot = Plot::new(("PairPlot", pair_plot_id, x, y))
+                        .width(cell)
+                        .height(cell)
+                        .show_axes(false)
+                        .show_grid(false)
+                        .allow_zoom(false)
+                        .allow_drag(false)
+                        .allow_scroll(false)
+                        .allow_boxed_zoom(false)
+                        .allow_double_click_reset(false);
+
+                    if x == y {
+                        let bars = self.histograms()[x].iter()
+                            .map(|(from, to, count)| Bar::new((from + to) / 2.0, *count as f64).width(if to > from { to - from } else { 1.0 }))
+                            .collect();
+
+                        plot.show(ui, |plot_ui| plot_ui.bar_chart(BarChart::new(bars).color(color)));
+                    } else {
+                        let (column_x, column_y) = (column_x.clone(), column_y.clone());
+
+                        plot
+                            .label_formatter(move |_name, value| format!("{}: {:.3}\n{}: {:.3}", column_x, value.x, column_y, value.y))
+                            .show(ui, |plot_ui| plot_ui.points(Points::new(self.points(x, y)).radius(1.0).color(color)));
+                    }
+                }
+
+                ui.end_row();
+            }
+
+            ui.label("");
+            for column in columns {
+                ui.label(column);
+            }
+            ui.end_row();
+        });
+    }
+
+    fn model_id(&self) -> WaModelId {
+        WaModelId::PairPlot{ frame_id: self.frame_id(), pair_plot_id: *self.id() }
+    }
+}
+
+impl View for DataLump {
+    fn title(&self) -> &str {
+        self._title()
+    }
+
+    fn ui_id(&self) -> Id {
+        Id::new(self.id())
+    }
+
+    fn ui(&self, ui: &mut egui::Ui, ctx: &Context, model_ctx: &mut ModelCtx) {
+        let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
+
+        ui.horizontal(|ui| {
+
+            if self.offset() > &0 {
+
+                if ui.button("Prev").clicked() {
+
+                    model_ctx.queue_action(ActionRq::DataLump {
+                        frame_id: *self.frame_id(),
+                        offset: if self.offset() > &100 {*self.offset() - 100} else {0},
+                        limit: 100
+                    });
+                };
+            }
+
+            ui.add(egui::Label::new(format!("{}-{}", self.offset(), self.offset()+self.data().len())));
+
+            if ui.button("Next").clicked() {
+
+                model_ctx.queue_action(ActionRq::DataLump {
+                    frame_id: *self.frame_id(),
+                    offset: *self.offset() + 100,
+                    limit: 100
+                });
+
+            };
+        });
+
+        let mut table = TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::LEFT));
+
+
+        let columns = self.columns();
+
+        table = columns.iter().fold(table, |t, _c|{
+            t.column(Column::auto().at_least(20.0).resizable(true).clip(true))
+        });
+            // .column(Column::auto().at_least(200.0).resizable(true).clip(true))
+            // .column(Column::auto().at_least(200.0).resizable(true).clip(true))
+            // .column(Column::remainder());
+
+        table.header(20.0, |mut header| {
+
+            columns.iter().for_each(|c|{
+                header.col(|ui| {
+                    ui.strong(c.1.clone());
+                });
+            });
+
+
+        }).body(|body| {
+            let mut data_iter = self.data().iter();
+
+            body.rows(2. * text_height, self.data().len(), |mut row| {
+                // let column_summary = &self.data()[row_index];
+
+                if let Some(row_data) = data_iter.next(){
+                    row_data.iter().for_each(|col_data|{
+                        row.col(|ui| {
+                            ui.label(col_data.as_ref().map(|v|v.clone()).unwrap_or(String::from("n/a")));
+                        });
+                    });
+                }
+
+
+            })
+        });
+    }
+
+    fn model_id(&self) -> WaModelId {
+        WaModelId::DataLump{ frame_id: *self.frame_id(), lump_id: *self.id() }
+    }
+}
+
+impl View for DataQuality {
+    fn title(&self) -> &str {
+        self._title()
+    }
+
+    fn ui_id(&self) -> Id {
+        Id::new(self.id())
+    }
+
+    fn ui(&self, ui: &mut egui::Ui, _ctx: &Context, _model_ctx: &mut ModelCtx) {
+        let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
+        let rows = self.rows();
+
+        ui.horizontal(|ui| {
+            ui.add(egui::Label::new(format!("Rows: {}", rows)));
+            ui.separator();
+            ui.add(egui::Label::new(format!("Issues: {}", self.issues().len())));
+        });
+
+        if self.issues().is_empty() {
+            ui.label("No issues found");
+            return;
+        }
+
+        let table = TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::LEFT))
+            .column(Column::auto().at_least(150.0).resizable(true).clip(true))
+            .column(Column::auto().at_least(150.0).resizable(true).clip(true))
+            .column(Column::auto().at_least(100.0).resizable(true).clip(true))
+            .column(Column::remainder());
+
+        table.header(20.0, |mut header| {
+            header.col(|ui| {
+                ui.strong("Column");
+            });
+            header.col(|ui| {
+                ui.strong("Issue");
+            });
+            header.col(|ui| {
+                ui.strong("Affected");
+            });
+            header.col(|ui| {
+                ui.strong("Details");
+            });
+        }).body(|body| {
+            body.rows(1.5 * text_height, self.issues().len(), |mut row| {
+                let issue = &self.issues()[row.index()];
+
+                row.col(|ui| {
+                    ui.label(issue.column().map(|c| c.clone()).unwrap_or(String::from("*")));
+                });
+                row.col(|ui| {
+                    ui.label(issue.issue_type().label());
+                });
+                row.col(|ui| {
+                    if rows > 0 {
+                        ui.label(format!("{} ({:.1}%)", issue.affected(), issue.affected() as f32 / rows as f32 * 100.0));
+                    } else {
+                        ui.label(format!("{}", issue.affected()));
+                    }
+                });
+                row.col(|ui| {
+                    ui.add(egui::Label::new(issue.details()).wrap(true));
+                });
+            })
+        });
+    }
+
+    fn model_id(&self) -> WaModelId {
+        WaModelId::DataQuality{ frame_id: *self.frame_id(), data_quality_id: *self.id() }
+    }
+}
+
+impl View for Outliers {
+    fn title(&self) -> &str {
+        self._title()
+    }
+
+    fn ui_id(&self) -> Id {
+        Id::new(self.id())
+    }
+
+    fn ui(&self, ui: &mut egui::Ui, _ctx: &Context, model_ctx: &mut ModelCtx) {
+        let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
+        let frame_id = *self.frame_id();
+
+        ui.horizontal(|ui| {
+            ui.label("Method:");
+            for method in OutlierMethod::all() {
+                if ui.selectable_label(self.method() == method, method.label()).clicked() && self.method() != method {
+                    model_ctx.queue_action(ActionRq::Outliers {
+                        frame_id,
+                        method,
+                    });
+                }
+            }
+            ui.separator();
+            ui.label(format!("threshold: {}", self.method().threshold()));
+        });
+
+        let table = TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::LEFT))
+            .column(Column::auto().at_least(150.0).resizable(true).clip(true))
+            .column(Column::auto().at_least(60.0).resizable(true).clip(true))
+            .column(Column::auto().at_least(150.0).resizable(true).clip(true))
+            .column(Column::auto().at_least(200.0).resizable(true).clip(true))
+            .column(Column::remainder());
+
+        table.header(20.0, |mut header| {
+            header.col(|ui| {
+                ui.strong("Column");
+            });
+            header.col(|ui| {
+                ui.strong("Count");
+            });
+            header.col(|ui| {
+                ui.strong("Fences");
+            });
+            header.col(|ui| {
+                ui.strong("Top rows");
+            });
+            header.col(|ui| {
+                ui.strong("Filter");
+            });
+        }).body(|body| {
+            body.rows(2. * text_height, self.columns().len(), |mut row| {
+                let column_outliers = &self.columns()[row.index()];
+
+                row.col(|ui| {
+                    ui.label(column_outliers.column());
+                });
+                row.col(|ui| {
+                    ui.label(format!("{}", column_outliers.count()));
+                });
+                row.col(|ui| {
+                    ui.label(format!("[{:.3}, {:.3}]", column_outliers.lower(), column_outliers.upper()));
+                });
+                row.col(|ui| {
+                    ui.add(egui::Label::new(
+                        column_outliers.top_rows().iter().map(|(r, v)| format!("{}: {}", r, v)).collect::<Vec<_>>().join(", ")
+                    ).wrap(true));
+                });
+                row.col(|ui| {
+                    ui.horizontal(|ui| {
+                        for (label, isolate) in [("Exclude", false), ("Isolate", true)] {
+                            if ui.add_enabled(column_outliers.count() > 0, egui::Button::new(label)).clicked() {
+                                let condition = column_outliers.to_condition(isolate);
+
+                                model_ctx.ui_action(
+                                    UIAction::WaFrame { frame_id, action: Box::new(move |frame| {
+                                        let filter_id = frame.filter_or_new();
+                                        frame.add_filter_condition(condition, None);
+
+                                        Some(UIAction::Layout {frame_id: WaModelId::Filter {frame_id:frame.id(), filter_id}, request: LayoutRequest::Center })
+                                    })}
+                                );
+                            }
+                        }
+                    });
+                });
+            })
+        });
+    }
+
+    fn model_id(&self) -> WaModelId {
+        WaModelId::Outliers{ frame_id: *self.frame_id(), outliers_id: *self.id() }
+    }
+}
+
+impl View for ValueCounts {
+    fn title(&self) -> &str {
+        self._title()
+    }
+
+    fn ui_id(&self) -> Id {
+        Id::new(self.id())
+    }
+
+    fn ui(&self, ui: &mut egui::Ui, _ctx: &Context, model_ctx: &mut ModelCtx) {
+        let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
+        let frame_id = *self.frame_id();
+
+        ui.horizontal(|ui| {
+            for sort in ValueCountsSort::all() {
+                if ui.selectable_label(self.sort() == sort, sort.label()).clicked() && self.sort() != sort {
+                    model_ctx.queue_action(ActionRq::ValueCounts {
+                        frame_id,
+                        name_ptr: Box::into_raw(Box::new(Box::<String>::new(self.column().clone()))) as u32,
+                        sort,
+                        offset: 0,
+                        limit: 100
+                    });
+                }
+            }
+
+            ui.separator();
+
+            if self.offset() > 0 {
+                if ui.button("Prev").clicked() {
+                    model_ctx.queue_action(ActionRq::ValueCounts {
+                        frame_id,
+                        name_ptr: Box::into_raw(Box::new(Box::<String>::new(self.column().clone()))) as u32,
+                        sort: self.sort(),
+                        offset: if self.offset() > 100 {self.offset() - 100} else {0},
+                        limit: 100
+                    });
+                };
+            }
+
+            ui.add(egui::Label::new(format!("{}-{} of {}", self.offset(), self.offset() + self.values().len(), self.distinct())));
+
+            if self.offset() + self.values().len() < self.distinct() {
+                if ui.button("Next").clicked() {
+                    model_ctx.queue_action(ActionRq::ValueCounts {
+                        frame_id,
+                        name_ptr: Box::into_raw(Box::new(Box::<String>::new(self.column().clone()))) as u32,
+                        sort: self.sort(),
+                        offset: self.offset() + 100,
+                        limit: 100
+                    });
+                };
+            }
+        });
+
+        let table = TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::LEFT))
+            .column(Column::auto().at_least(200.0).resizable(true).clip(true))
+            .column(Column::auto().at_least(60.0).resizable(true).clip(true))
+            .column(Column::auto().at_least(60.0).resizable(true).clip(true))
+            .column(Column::remainder());
+
+        table.header(20.0, |mut header| {
+            header.col(|ui| {
+                ui.strong("Value");
+            });
+            header.col(|ui| {
+                ui.strong("Count");
+            });
+            header.col(|ui| {
+                ui.strong("%");
+            });
+            header.col(|ui| {
+                ui.strong("Cum. %");
+            });
+        }).body(|body| {
+            body.rows(1.5 * text_height, self.values().len(), |mut row| {
+                let value_count = &self.values()[row.index()];
+
+                row.col(|ui| {
+                    if ui.link(value_count.value().map(|v|v.as_str()).unwrap_or("n/a")).on_hover_text("filter by value").clicked() {
+                        let condition = value_count.to_condition(self.column().clone());
+
+                        model_ctx.ui_action(
+                            UIAction::WaFrame { frame_id, action: Box::new(move |frame| {
+                                let filter_id = frame.filter_or_new();
+                                frame.add_filter_condition(condition, None);
+
+                                Some(UIAction::Layout {frame_id: WaModelId::Filter {frame_id:frame.id(), filter_id}, request: LayoutRequest::Center })
+                            })}
+                        );
+                    }
+                });
+                row.col(|ui| {
+                    ui.label(format!("{}", value_count.count()));
+                });
+                row.col(|ui| {
+                    ui.label(format!("{:.2}", value_count.percent()));
+                });
+                row.col(|ui| {
+                    ui.label(format!("{:.2}", value_count.cumulative_percent()));
+                });
+            })
+        });
+    }
+
+    fn model_id(&self) -> WaModelId {
+        WaModelId::ValueCounts{ frame_id: *self.frame_id(), value_counts_id: *self.id() }
+    }
+}
+
+impl View for Pivot {
+    fn title(&self) -> &str {
+        self._title()
+    }
+
+    fn ui_id(&self) -> Id {
+        Id::new(self.id())
+    }
+
+    fn ui(&self, ui: &mut egui::Ui, _ctx: &Context, model_ctx: &mut ModelCtx) {
+        let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
+        let frame_id = *self.frame_id();
+        let pivot_id = *self.id();
+
+        ui.horizontal(|ui| {
+            if self.agg().is_additive() {
+                for normalization in PivotNormalization::all() {
+                    if ui.selectable_label(self.normalization() == normalization, normalization.label()).clicked() {
+                        let heat = self.heat();
+                        model_ctx.ui_action(UIAction::WaFrame { frame_id, action: Box::new(move |frame| {
+                            frame.change_pivot_view(pivot_id, normalization, heat);
+                            None
+                        })});
+                    }
+                }
+
+                ui.separator();
+            }
+
+            for (label, heat) in [("Table", false), ("Heat", true)] {
+                if ui.selectable_label(self.heat() == heat, label).clicked() {
+                    let normalization = self.normalization();
+                    model_ctx.ui_action(UIAction::WaFrame { frame_id, action: Box::new(move |frame| {
+                        frame.change_pivot_view(pivot_id, normalization, heat);
+                        None
+                    })});
+                }
+            }
+        });
+
+        let fmt = |v: Option<f64>| v.map(|v| format!("{:.2}", v)).unwrap_or(String::from("-"));
+
+        if self.heat() {
+            let (min, max) = self.cells_range();
+
+            egui::ScrollArea::both().show(ui, |ui| {
+                egui::Grid::new(("pivot_heat", pivot_id)).spacing([2.0, 2.0]).show(ui, |ui| {
+                    ui.label("");
+                    for column_key in self.column_keys() {
+                        ui.strong(column_key);
+                    }
+                    ui.end_row();
+
+                    for (r, row_key) in self.row_keys().iter().enumerate() {
+                        ui.strong(row_key);
+                        for c in 0..self.column_keys().len() {
+                            let value = self.value(Some(r), Some(c));
+                            let t = value.map(|v| if max > min { ((v - min) / (max - min)) as f32 } else { 1.0 }).unwrap_or(0.0);
+
+                            egui::Frame::none()
+                                .fill(heat_color(t))
+                                .inner_margin(4.0)
+                                .show(ui, |ui| {
+                                    ui.colored_label(Color32::BLACK, fmt(value));
+                                });
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+
+            return;
+        }
+
+        let mut table = TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::LEFT))
+            .column(Column::auto().at_least(100.0).resizable(true).clip(true));
+
+        table = self.column_keys().iter().fold(table, |t, _c| {
+            t.column(Column::auto().at_least(60.0).resizable(true).clip(true))
+        }).column(Column::remainder());
+
+        table.header(20.0, |mut header| {
+            header.col(|ui| {
+                ui.strong("");
+            });
+            for column_key in self.column_keys() {
+                header.col(|ui| {
+                    ui.strong(column_key);
+                });
+            }
+            header.col(|ui| {
+                ui.strong("Total");
+            });
+        }).body(|body| {
+            body.rows(1.5 * text_height, self.row_keys().len() + 1, |mut row| {
+                let r = row.index();
+                let row_key = self.row_keys().get(r);
+
+                row.col(|ui| {
+                    match row_key {
+                        Some(row_key) => ui.strong(row_key),
+                        None => ui.strong("Total"),
+                    };
+                });
+
+                let r = row_key.map(|_| r);
+                for c in 0..self.column_keys().len() {
+                    row.col(|ui| {
+                        ui.label(fmt(self.value(r, Some(c))));
+                    });
+                }
+                row.col(|ui| {
+                    ui.strong(fmt(self.value(r, None)));
+                });
+            })
+        });
+    }
+
+    fn model_id(&self) -> WaModelId {
+        WaModelId::Pivot{ frame_id: *self.frame_id(), pivot_id: *self.id() }
+    }
+}
+
+//-1 blue, 0 light grey, +1 red
+fn diverging_color(v: f32) -> Color32 {
+    let v = v.clamp(-1.0, 1.0);
+    let (r, g, b) = if v < 0.0 { (59.0, 76.0, 192.0) } else { (180.0, 4.0, 38.0) };
+    let c = |to: f32| (247.0 + (to - 247.0) * v.abs()) as u8;
+    Color32::from_rgb(c(r), c(g), c(b))
+}
+
+fn faded(color: Color32) -> Color32 {
+    let c = |v: u8| ((v as f32 + 2.0 * 235.0) / 3.0) as u8;
+    Color32::from_rgb(c(color.r()), c(color.g()), c(color.b()))
+}
+
+fn heat_color(t: f32) -> Color32 {
+    let t = t.clamp(0.0, 1.0);
+    Color32::from_rgb(255, (255.0 * (1.0 - t * 0.8)) as u8, (255.0 * (1.0 - t)) as u8)
+}
+
+//spread hues the way egui_plot picks its auto colors, so size classes of a series share one color
+fn series_color(i: usize) -> Color32 {
+    let golden_ratio = (5.0_f32.sqrt() - 1.0) / 2.0;
+    let h = i as f32 * golden_ratio;
+    egui::ecolor::Hsva::new(h.fract(), 0.85, 0.5, 1.0).into()
+}
+
+//grid marks on minute to decade boundaries of an epoch milliseconds axis, at most about 8 of them
+fn time_grid_spacer(input: GridInput) -> Vec<GridMark> {
+    let (min, max) = input.bounds;
+    let span = max - min;
+    if span <= 0.0 || !span.is_finite() {
+        return vec![];
+    }
+
+    let step = TIME_STEPS_MS.iter().copied()
+        .find(|step| span / step <= 8.0)
+        .unwrap_or_else(|| (span / 8.0 / TIME_STEPS_MS[8]).ceil() * TIME_STEPS_MS[8]);
+
+    ((min / step).ceil() as i64..=(max / step).floor() as i64)
+        .map(|i| GridMark { value: i as f64 * step, step_size: step })
+        .collect()
+}
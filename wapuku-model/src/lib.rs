@@ -2,6 +2,7 @@ pub mod data_type;
 pub mod messages;
 pub mod model;
 pub mod polars_df;
+pub mod stats;
 pub mod test_data;
 pub mod utils;
 
@@ -7,6 +7,7 @@ use uuid::Uuid;
 
 
 use crate::data_type::*;
+use crate::stats::average_linkage_order;
 
 
 ///////////////Data management model////////////////
@@ -24,7 +25,17 @@ pub enum WaModelId {
     DataLump{ frame_id: u128, lump_id:u128},
     Histogram{ frame_id: u128, histogram_id: u128},
     Corrs{ frame_id: u128, corrs_id: u128},
-    ColumnsPlot{ frame_id: u128, columns_plot_id: u128}
+    ColumnsPlot{ frame_id: u128, columns_plot_id: u128},
+    DataQuality{ frame_id: u128, data_quality_id: u128},
+    Outliers{ frame_id: u128, outliers_id: u128},
+    ValueCounts{ frame_id: u128, value_counts_id: u128},
+    Pivot{ frame_id: u128, pivot_id: u128},
+    Kde{ frame_id: u128, kde_id: u128},
+    Histogram2D{ frame_id: u128, histogram_id: u128},
+    Scatter{ frame_id: u128, scatter_id: u128},
+    BoxPlot{ frame_id: u128, box_plot_id: u128},
+    TimeSeries{ frame_id: u128, time_series_id: u128},
+    PairPlot{ frame_id: u128, pair_plot_id: u128}
 }
 
 impl WaModelId {
@@ -48,6 +59,36 @@ impl WaModelId {
             WaModelId::Filter { frame_id:_, filter_id } => {
                 filter_id
             }
+            WaModelId::DataQuality { frame_id:_, data_quality_id } => {
+                data_quality_id
+            }
+            WaModelId::Outliers { frame_id:_, outliers_id } => {
+                outliers_id
+            }
+            WaModelId::ValueCounts { frame_id:_, value_counts_id } => {
+                value_counts_id
+            }
+            WaModelId::Pivot { frame_id:_, pivot_id } => {
+                pivot_id
+            }
+            WaModelId::Kde { frame_id:_, kde_id } => {
+                kde_id
+            }
+            WaModelId::Histogram2D { frame_id:_, histogram_id } => {
+                histogram_id
+            }
+            WaModelId::Scatter { frame_id:_, scatter_id } => {
+                scatter_id
+            }
+            WaModelId::BoxPlot { frame_id:_, box_plot_id } => {
+                box_plot_id
+            }
+            WaModelId::TimeSeries { frame_id:_, time_series_id } => {
+                time_series_id
+            }
+            WaModelId::PairPlot { frame_id:_, pair_plot_id } => {
+                pair_plot_id
+            }
         }
     }
 
@@ -71,6 +112,36 @@ impl WaModelId {
             WaModelId::Filter { frame_id, .. } => {
                 Some(frame_id)
             }
+            WaModelId::DataQuality { frame_id, .. } => {
+                Some(frame_id)
+            }
+            WaModelId::Outliers { frame_id, .. } => {
+                Some(frame_id)
+            }
+            WaModelId::ValueCounts { frame_id, .. } => {
+                Some(frame_id)
+            }
+            WaModelId::Pivot { frame_id, .. } => {
+                Some(frame_id)
+            }
+            WaModelId::Kde { frame_id, .. } => {
+                Some(frame_id)
+            }
+            WaModelId::Histogram2D { frame_id, .. } => {
+                Some(frame_id)
+            }
+            WaModelId::Scatter { frame_id, .. } => {
+                Some(frame_id)
+            }
+            WaModelId::BoxPlot { frame_id, .. } => {
+                Some(frame_id)
+            }
+            WaModelId::TimeSeries { frame_id, .. } => {
+                Some(frame_id)
+            }
+            WaModelId::PairPlot { frame_id, .. } => {
+                Some(frame_id)
+            }
         }
     }
 }
@@ -84,8 +155,19 @@ pub struct WaFrame {
     histograms:HashMap<u128, Histogram>,
     corrs:HashMap<u128, Corrs>,
     columns_plots:HashMap<u128, ColumnsPlot>,
+    value_counts:HashMap<u128, ValueCounts>,
+    pivots:HashMap<u128, Pivot>,
+    kdes:HashMap<u128, Kde>,
+    histograms_2d:HashMap<u128, Histogram2D>,
+    scatters:HashMap<u128, Scatter>,
+    box_plots:HashMap<u128, BoxPlot>,
+    time_series:HashMap<u128, TimeSeries>,
+    pair_plots:HashMap<u128, PairPlot>,
     data_lump:Option<DataLump>,
-    filter:Option<Filter>
+    data_quality:Option<DataQuality>,
+    outliers:Option<Outliers>,
+    filter:Option<Filter>,
+    parent:Option<(u128, Filter)>, //frame and filter this frame was derived with
 }
 
 impl WaFrame {
@@ -97,11 +179,39 @@ impl WaFrame {
             histograms: HashMap::new(),
             corrs: HashMap::new(),
             columns_plots: HashMap::new(),
+            value_counts: HashMap::new(),
+            pivots: HashMap::new(),
+            kdes: HashMap::new(),
+            histograms_2d: HashMap::new(),
+            scatters: HashMap::new(),
+            box_plots: HashMap::new(),
+            time_series: HashMap::new(),
+            pair_plots: HashMap::new(),
             data_lump: None,
-            filter: None
+            data_quality: None,
+            outliers: None,
+            filter: None,
+            parent: None,
         }
     }
 
+    pub fn with_parent(mut self, parent_id: u128, filter: Filter) -> Self {
+        self.parent = Some((parent_id, filter));
+        self
+    }
+
+    pub fn parent_id(&self) -> Option<u128> {
+        self.parent.as_ref().map(|(parent_id, _)| *parent_id)
+    }
+
+    //rows of this frame as a condition on the parent's data
+    pub fn parent_condition(&self) -> Option<(u128, ConditionType)> {
+        self.parent.as_ref().map(|(parent_id, filter)| (
+            *parent_id,
+            filter.conditions().cloned().unwrap_or(ConditionType::Compoiste { conditions: vec![], ctype: CompositeType::AND }),
+        ))
+    }
+
     pub fn add_filter(&mut self) -> u128 {
 
         let new_filter = Filter::empty(
@@ -115,6 +225,13 @@ impl WaFrame {
         filter_id
     }
 
+    pub fn filter_or_new(&mut self) -> u128 {
+        match self.filter.as_ref() {
+            Some(filter) => *filter.id(),
+            None => self.add_filter()
+        }
+    }
+
     pub fn add_filter_condition(&mut self,  new_condition:ConditionType, target_condition:Option<*const ConditionType>) {
         if let Some(filter) = self.filter.as_mut() {
             filter.add_condition(new_condition, target_condition);
@@ -152,10 +269,19 @@ impl WaFrame {
         self.id
     }
 
-    pub fn add_histogram(&mut self, histogram:Histogram) {
+    pub fn add_histogram(&mut self, mut histogram:Histogram) {
+        histogram.set_frame_parent_id(self.parent_id());
         self.histograms.insert(*histogram.id(), histogram);
     }
 
+    pub fn replace_histogram(&mut self, histogram_id:u128, histogram:Histogram) {
+        if let Some(existing) = self.histograms.get_mut(&histogram_id) {
+            existing.replace_data(histogram);
+        } else {
+            self.add_histogram(histogram);
+        }
+    }
+
     pub fn add_data_lump(&mut self, data_lump:DataLump) {
         if let Some(lump) = self.data_lump.as_mut() {
             lump.replace_data(data_lump);
@@ -177,6 +303,22 @@ impl WaFrame {
         self.corrs.values().into_iter()
     }
 
+    pub fn select_corrs_pair(&mut self, corrs_id:u128, selected:Option<(String, String)>) {
+        if let Some(corrs) = self.corrs.get_mut(&corrs_id) {
+            corrs.set_selected(selected);
+        } else {
+            error!("select_corrs_pair: no corrs_id={}", corrs_id)
+        }
+    }
+
+    pub fn cluster_corrs(&mut self, corrs_id:u128, clustered:bool) {
+        if let Some(corrs) = self.corrs.get_mut(&corrs_id) {
+            corrs.set_clustered(clustered);
+        } else {
+            error!("cluster_corrs: no corrs_id={}", corrs_id)
+        }
+    }
+
     pub fn add_columns_plot(&mut self, columns_plot:ColumnsPlot) {
         self.columns_plots.insert(*columns_plot.id(), columns_plot);
     }
@@ -185,10 +327,167 @@ impl WaFrame {
         self.columns_plots.values().into_iter()
     }
 
+    pub fn add_value_counts(&mut self, value_counts:ValueCounts) {
+        if let Some(existing) = self.value_counts.values_mut().find(|v|v.column.eq(&value_counts.column)) {
+            existing.replace_data(value_counts);
+        } else {
+            self.value_counts.insert(*value_counts.id(), value_counts);
+        }
+    }
+
+    pub fn value_counts(&self)->impl Iterator<Item = &ValueCounts> {
+        self.value_counts.values().into_iter()
+    }
+
+    pub fn add_pivot(&mut self, pivot:Pivot) {
+        self.pivots.insert(*pivot.id(), pivot);
+    }
+
+    pub fn pivots(&self)->impl Iterator<Item = &Pivot> {
+        self.pivots.values().into_iter()
+    }
+
+    pub fn add_kde(&mut self, kde:Kde) {
+        self.kdes.insert(*kde.id(), kde);
+    }
+
+    pub fn replace_kde(&mut self, kde_id:u128, kde:Kde) {
+        if let Some(existing) = self.kdes.get_mut(&kde_id) {
+            existing.replace_data(kde);
+        } else {
+            self.add_kde(kde);
+        }
+    }
+
+    pub fn kdes(&self)->impl Iterator<Item = &Kde> {
+        self.kdes.values().into_iter()
+    }
+
+    pub fn add_histogram_2d(&mut self, histogram:Histogram2D) {
+        self.histograms_2d.insert(*histogram.id(), histogram);
+    }
+
+    pub fn histograms_2d(&self)->impl Iterator<Item = &Histogram2D> {
+        self.histograms_2d.values().into_iter()
+    }
+
+    pub fn add_scatter(&mut self, scatter:Scatter) {
+        self.scatters.insert(*scatter.id(), scatter);
+    }
+
+    pub fn replace_scatter(&mut self, scatter_id:u128, scatter:Scatter) {
+        if let Some(existing) = self.scatters.get_mut(&scatter_id) {
+            existing.replace_data(scatter);
+        } else {
+            self.add_scatter(scatter);
+        }
+    }
+
+    pub fn scatters(&self)->impl Iterator<Item = &Scatter> {
+        self.scatters.values().into_iter()
+    }
+
+    pub fn add_box_plot(&mut self, box_plot:BoxPlot) {
+        self.box_plots.insert(*box_plot.id(), box_plot);
+    }
+
+    pub fn replace_box_plot(&mut self, box_plot_id:u128, box_plot:BoxPlot) {
+        if let Some(existing) = self.box_plots.get_mut(&box_plot_id) {
+            existing.replace_data(box_plot);
+        } else {
+            self.add_box_plot(box_plot);
+        }
+    }
+
+    pub fn box_plots(&self)->impl Iterator<Item = &BoxPlot> {
+        self.box_plots.values().into_iter()
+    }
+
+    pub fn add_time_series(&mut self, time_series:TimeSeries) {
+        self.time_series.insert(*time_series.id(), time_series);
+    }
+
+    pub fn replace_time_series(&mut self, time_series_id:u128, time_series:TimeSeries) {
+        if let Some(existing) = self.time_series.get_mut(&time_series_id) {
+            existing.replace_data(time_series);
+        } else {
+            self.add_time_series(time_series);
+        }
+    }
+
+    pub fn time_series(&self)->impl Iterator<Item = &TimeSeries> {
+        self.time_series.values().into_iter()
+    }
+
+    pub fn add_pair_plot(&mut self, pair_plot:PairPlot) {
+        self.pair_plots.insert(*pair_plot.id(), pair_plot);
+    }
+
+    pub fn pair_plots(&self)->impl Iterator<Item = &PairPlot> {
+        self.pair_plots.values().into_iter()
+    }
+
+    pub fn set_box_plot_violin(&mut self, box_plot_id:u128, violin:bool) {
+        if let Some(box_plot) = self.box_plots.get_mut(&box_plot_id) {
+            box_plot.set_violin(violin);
+        } else {
+            error!("set_box_plot_violin: no box_plot_id={}", box_plot_id)
+        }
+    }
+
+    pub fn set_histogram_kde(&mut self, histogram_id:u128, kde:Kde) {
+        if let Some(histogram) = self.histograms.get_mut(&histogram_id) {
+            histogram.set_kde(kde.series);
+        } else {
+            error!("set_histogram_kde: no histogram_id={}", histogram_id)
+        }
+    }
+
+    pub fn select_histogram_bars(&mut self, histogram_id:u128, selected:Vec<usize>) {
+        if let Some(histogram) = self.histograms.get_mut(&histogram_id) {
+            histogram.set_selected(selected);
+        } else {
+            error!("select_histogram_bars: no histogram_id={}", histogram_id)
+        }
+    }
+
+    pub fn change_histogram_view(&mut self, histogram_id:u128, view:HistogramView) {
+        if let Some(histogram) = self.histograms.get_mut(&histogram_id) {
+            histogram.set_view(view);
+        } else {
+            error!("change_histogram_view: no histogram_id={}", histogram_id)
+        }
+    }
+
+    pub fn change_pivot_view(&mut self, pivot_id:u128, normalization:PivotNormalization, heat:bool) {
+        if let Some(pivot) = self.pivots.get_mut(&pivot_id) {
+            pivot.set_normalization(normalization);
+            pivot.set_heat(heat);
+        } else {
+            error!("change_pivot_view: no pivot_id={}", pivot_id)
+        }
+    }
+
     pub fn data_lump(&self)->Option<&DataLump> {
         self.data_lump.as_ref()
     }
 
+    pub fn add_data_quality(&mut self, data_quality:DataQuality) {
+        self.data_quality.replace(data_quality);
+    }
+
+    pub fn data_quality(&self)->Option<&DataQuality> {
+        self.data_quality.as_ref()
+    }
+
+    pub fn add_outliers(&mut self, outliers:Outliers) {
+        self.outliers.replace(outliers);
+    }
+
+    pub fn outliers(&self)->Option<&Outliers> {
+        self.outliers.as_ref()
+    }
+
     pub fn purge(&mut self, id: WaModelId) {
         match id {
             WaModelId::Histogram{frame_id:_, histogram_id} => {
@@ -206,6 +505,36 @@ impl WaFrame {
             WaModelId::Filter {frame_id:_, filter_id:_} => {
                 self.filter.take();
             },
+            WaModelId::DataQuality {frame_id:_, data_quality_id:_} => {
+                self.data_quality.take();
+            },
+            WaModelId::Outliers {frame_id:_, outliers_id:_} => {
+                self.outliers.take();
+            },
+            WaModelId::ValueCounts {frame_id:_, value_counts_id} => {
+                self.value_counts.remove(&value_counts_id);
+            },
+            WaModelId::Pivot {frame_id:_, pivot_id} => {
+                self.pivots.remove(&pivot_id);
+            },
+            WaModelId::Kde {frame_id:_, kde_id} => {
+                self.kdes.remove(&kde_id);
+            },
+            WaModelId::Histogram2D {frame_id:_, histogram_id} => {
+                self.histograms_2d.remove(&histogram_id);
+            },
+            WaModelId::Scatter {frame_id:_, scatter_id} => {
+                self.scatters.remove(&scatter_id);
+            },
+            WaModelId::BoxPlot {frame_id:_, box_plot_id} => {
+                self.box_plots.remove(&box_plot_id);
+            },
+            WaModelId::TimeSeries {frame_id:_, time_series_id} => {
+                self.time_series.remove(&time_series_id);
+            },
+            WaModelId::PairPlot {frame_id:_, pair_plot_id} => {
+                self.pair_plots.remove(&pair_plot_id);
+            },
             _=>{}
         }
     }
@@ -334,62 +663,1949 @@ pub struct Summary {
 
 impl Summary {
 
-    pub fn new(id:u128, frame_id: u128, title:String, columns: Vec<SummaryColumn>, shape:String) -> Self {
-        Self {
-            _title: title,
-            id,
-            frame_id,
-            columns,
-            shape
+    pub fn new(id:u128, frame_id: u128, title:String, columns: Vec<SummaryColumn>, shape:String) -> Self {
+        Self {
+            _title: title,
+            id,
+            frame_id,
+            columns,
+            shape
+        }
+    }
+
+    pub fn frame_id(&self) -> u128 {
+        self.frame_id
+    }
+
+    pub fn _title(&self) -> &str {
+        &self._title
+    }
+
+    pub fn id(&self) -> u128 {
+        self.id
+    }
+
+    pub fn shape(&self) -> &str {
+        &self.shape
+    }
+
+    pub fn columns(&self) -> &Vec<SummaryColumn> {
+        &self.columns
+    }
+
+    pub fn change_column_type(&mut self, column_name:String, dtype:SummaryColumn) {
+        if let Some(column) = self.columns.iter_mut().find(|c|c.name.eq(&column_name)) {
+            column.dtype = dtype.dtype
+        } else {
+            error!("change_column_type: no column_name={}", column_name)
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CalendarUnit {
+    Hour,
+    Day,
+    Week,
+    Month,
+    Quarter,
+    Year
+}
+
+impl CalendarUnit {
+    pub fn label(&self) -> &str {
+        match self {
+            CalendarUnit::Hour => {
+                "hour"
+            }
+            CalendarUnit::Day => {
+                "day"
+            }
+            CalendarUnit::Week => {
+                "week"
+            }
+            CalendarUnit::Month => {
+                "month"
+            }
+            CalendarUnit::Quarter => {
+                "quarter"
+            }
+            CalendarUnit::Year => {
+                "year"
+            }
+        }
+    }
+
+    pub fn every(&self) -> &str { //polars duration string
+        match self {
+            CalendarUnit::Hour => "1h",
+            CalendarUnit::Day => "1d",
+            CalendarUnit::Week => "1w",
+            CalendarUnit::Month => "1mo",
+            CalendarUnit::Quarter => "1q",
+            CalendarUnit::Year => "1y",
+        }
+    }
+
+    pub fn auto(span_ms:i64) -> CalendarUnit {
+        const DAY_MS:i64 = 24 * 60 * 60 * 1000;
+
+        match span_ms {
+            s if s <= 3 * DAY_MS => CalendarUnit::Hour,
+            s if s <= 92 * DAY_MS => CalendarUnit::Day,
+            s if s <= 2 * 365 * DAY_MS => CalendarUnit::Week,
+            s if s <= 8 * 365 * DAY_MS => CalendarUnit::Month,
+            s if s <= 25 * 365 * DAY_MS => CalendarUnit::Quarter,
+            _ => CalendarUnit::Year
+        }
+    }
+
+    pub fn all() -> [CalendarUnit; 6] {
+        [CalendarUnit::Hour, CalendarUnit::Day, CalendarUnit::Week, CalendarUnit::Month, CalendarUnit::Quarter, CalendarUnit::Year]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResampleAgg {
+    Sum,
+    Mean,
+    Count,
+    Min,
+    Max
+}
+
+impl ResampleAgg {
+    pub fn label(&self) -> &str {
+        match self {
+            ResampleAgg::Sum => {
+                "sum"
+            }
+            ResampleAgg::Mean => {
+                "mean"
+            }
+            ResampleAgg::Count => {
+                "count"
+            }
+            ResampleAgg::Min => {
+                "min"
+            }
+            ResampleAgg::Max => {
+                "max"
+            }
+        }
+    }
+
+    pub fn all() -> [ResampleAgg; 5] {
+        [ResampleAgg::Sum, ResampleAgg::Mean, ResampleAgg::Count, ResampleAgg::Min, ResampleAgg::Max]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Binning {
+    Count{bins:usize},
+    Width{width:f64},
+    Sturges,
+    Scott,
+    FreedmanDiaconis,
+    Quantile{bins:usize},
+    Edges{edges:Vec<f64>},
+    Calendar{unit:Option<CalendarUnit>}, //None - picked from the span
+    DayOfWeek,
+    HourOfDay
+}
+
+impl Binning {
+    pub fn label(&self) -> &str {
+        match self {
+            Binning::Count { .. } => {
+                "fixed count"
+            }
+            Binning::Width { .. } => {
+                "fixed width"
+            }
+            Binning::Sturges => {
+                "Sturges"
+            }
+            Binning::Scott => {
+                "Scott"
+            }
+            Binning::FreedmanDiaconis => {
+                "Freedman–Diaconis"
+            }
+            Binning::Quantile { .. } => {
+                "quantile"
+            }
+            Binning::Edges { .. } => {
+                "explicit edges"
+            }
+            Binning::Calendar { .. } => {
+                "calendar"
+            }
+            Binning::DayOfWeek => {
+                "day of week"
+            }
+            Binning::HourOfDay => {
+                "hour of day"
+            }
+        }
+    }
+
+    pub fn is_calendar(&self) -> bool {
+        matches!(self, Binning::Calendar { .. } | Binning::DayOfWeek | Binning::HourOfDay)
+    }
+
+    pub fn all() -> [Binning; 10] {
+        [
+            Binning::Count { bins: 10 },
+            Binning::Width { width: 1.0 },
+            Binning::Sturges,
+            Binning::Scott,
+            Binning::FreedmanDiaconis,
+            Binning::Quantile { bins: 4 },
+            Binning::Edges { edges: vec![] },
+            Binning::Calendar { unit: None },
+            Binning::DayOfWeek,
+            Binning::HourOfDay,
+        ]
+    }
+
+    pub fn same_kind(&self, other:&Binning) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+pub const HISTOGRAM_TOP_N:usize = 20;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistogramParams {
+    binning:Binning,
+    range:Option<(f64, f64)>,
+    include_nulls:bool,
+    top_n:usize, //categorical columns, the rest goes to "Other"
+    split:Option<String>,
+    compare:Option<ConditionType>, //rows of a filtered child frame, counted against all rows of the parent
+}
+
+impl HistogramParams {
+    pub fn new(binning: Binning, range: Option<(f64, f64)>, include_nulls: bool) -> Self {
+        Self { binning, range, include_nulls, top_n: HISTOGRAM_TOP_N, split: None, compare: None }
+    }
+
+    pub fn with_binning(mut self, binning: Binning) -> Self {
+        self.binning = binning;
+        self
+    }
+
+    pub fn with_top_n(mut self, top_n: usize) -> Self {
+        self.top_n = top_n;
+        self
+    }
+
+    pub fn with_split(mut self, split: Option<String>) -> Self {
+        self.split = split;
+        self
+    }
+
+    pub fn binning(&self) -> &Binning {
+        &self.binning
+    }
+
+    pub fn range(&self) -> Option<(f64, f64)> {
+        self.range
+    }
+
+    pub fn include_nulls(&self) -> bool {
+        self.include_nulls
+    }
+
+    pub fn top_n(&self) -> usize {
+        self.top_n
+    }
+
+    pub fn split(&self) -> Option<&String> {
+        self.split.as_ref()
+    }
+
+    pub fn with_compare(mut self, compare: Option<ConditionType>) -> Self {
+        self.compare = compare;
+        self
+    }
+
+    pub fn compare(&self) -> Option<&ConditionType> {
+        self.compare.as_ref()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HistogramLayout {
+    Grouped,
+    Stacked
+}
+
+impl HistogramLayout {
+    pub fn label(&self) -> &str {
+        match self {
+            HistogramLayout::Grouped => {
+                "grouped"
+            }
+            HistogramLayout::Stacked => {
+                "stacked"
+            }
+        }
+    }
+
+    pub fn all() -> [HistogramLayout; 2] {
+        [HistogramLayout::Grouped, HistogramLayout::Stacked]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HistogramScale {
+    Count,
+    Percent,
+    Density,
+    CumulativeCount,
+    CumulativePercent
+}
+
+impl HistogramScale {
+    pub fn label(&self) -> &str {
+        match self {
+            HistogramScale::Count => {
+                "count"
+            }
+            HistogramScale::Percent => {
+                "%"
+            }
+            HistogramScale::Density => {
+                "density"
+            }
+            HistogramScale::CumulativeCount => {
+                "cumulative"
+            }
+            HistogramScale::CumulativePercent => {
+                "cumulative %"
+            }
+        }
+    }
+
+    pub fn is_cumulative(&self) -> bool {
+        matches!(self, HistogramScale::CumulativeCount | HistogramScale::CumulativePercent)
+    }
+
+    pub fn all() -> [HistogramScale; 5] {
+        [HistogramScale::Count, HistogramScale::Percent, HistogramScale::Density, HistogramScale::CumulativeCount, HistogramScale::CumulativePercent]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistogramView {
+    layout:HistogramLayout,
+    normalized:bool, //percent within split category
+    scale:HistogramScale,
+    log_y:bool
+}
+
+impl HistogramView {
+    pub fn layout(&self) -> HistogramLayout {
+        self.layout
+    }
+
+    pub fn normalized(&self) -> bool {
+        self.normalized
+    }
+
+    pub fn scale(&self) -> HistogramScale {
+        self.scale
+    }
+
+    pub fn log_y(&self) -> bool {
+        self.log_y
+    }
+
+    pub fn with_layout(self, layout:HistogramLayout) -> Self {
+        Self { layout, ..self }
+    }
+
+    pub fn with_normalized(self, normalized:bool) -> Self {
+        Self { normalized, ..self }
+    }
+
+    pub fn with_scale(self, scale:HistogramScale) -> Self {
+        Self { scale, ..self }
+    }
+
+    pub fn with_log_y(self, log_y:bool) -> Self {
+        Self { log_y, ..self }
+    }
+}
+
+impl Default for HistogramView {
+    fn default() -> Self {
+        Self { layout: HistogramLayout::Grouped, normalized: false, scale: HistogramScale::Count, log_y: false }
+    }
+}
+
+impl Default for HistogramParams {
+    fn default() -> Self {
+        Self::new(Binning::Count { bins: 10 }, None, false)
+    }
+}
+
+#[derive(Debug)]
+pub struct Histogram {
+    id:u128,
+    frame_id: u128,
+    title: String,
+    column:String,
+    params:HistogramParams,
+    values:Vec<(String, u32)>,
+    series:Vec<(String, Vec<u32>)>, //per split category counts, aligned with values
+    edges:Vec<f64>, //physical bin edges, numeric and temporal bins only
+    temporal:bool, //edges are physical dates or times
+    frame_parent_id:Option<u128>, //compare with parent is offered for frames created by a filter only
+    markers:Vec<(String, f64)>, //mean, median in physical units
+    kde:Vec<ColumnPlotSeries>, //overlay, physical x and density y
+    view:HistogramView,
+    conditions:Vec<Option<Condition>>, //per bar, None if the bar can't be filtered on
+    selected:Vec<usize>,
+}
+
+impl Histogram {
+
+    pub fn new(frame_id: u128, column:String, params:HistogramParams, values:Vec<(String, u32)>) -> Self {
+        Self {
+            id: wa_id(),
+            frame_id,
+            title: format!("histogram/{}", column),
+            column,
+            params,
+            values,
+            series: vec![],
+            edges: vec![],
+            temporal: false,
+            frame_parent_id: None,
+            markers: vec![],
+            kde: vec![],
+            view: HistogramView::default(),
+            conditions: vec![],
+            selected: vec![],
+        }
+    }
+
+    pub fn id(&self) -> &u128 {
+        &self.id
+    }
+
+    pub fn frame_id(&self) -> u128 {
+        self.frame_id
+    }
+
+    pub fn _title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn column(&self) -> &str {
+        &self.column
+    }
+
+    pub fn params(&self) -> &HistogramParams {
+        &self.params
+    }
+
+    pub fn values(&self) -> &Vec<(String, u32)> {
+        &self.values
+    }
+
+    pub fn series(&self) -> &Vec<(String, Vec<u32>)> {
+        &self.series
+    }
+
+    pub fn set_series(&mut self, series:Vec<(String, Vec<u32>)>) {
+        self.series = series;
+    }
+
+    pub fn edges(&self) -> &Vec<f64> {
+        &self.edges
+    }
+
+    pub fn set_edges(&mut self, edges:Vec<f64>) {
+        self.edges = edges;
+    }
+
+    pub fn temporal(&self) -> bool {
+        self.temporal
+    }
+
+    pub fn set_temporal(&mut self, temporal:bool) {
+        self.temporal = temporal;
+    }
+
+    pub fn frame_parent_id(&self) -> Option<u128> {
+        self.frame_parent_id
+    }
+
+    pub fn set_frame_parent_id(&mut self, frame_parent_id:Option<u128>) {
+        self.frame_parent_id = frame_parent_id;
+    }
+
+    pub fn markers(&self) -> &Vec<(String, f64)> {
+        &self.markers
+    }
+
+    pub fn set_markers(&mut self, markers:Vec<(String, f64)>) {
+        self.markers = markers;
+    }
+
+    pub fn kde(&self) -> &Vec<ColumnPlotSeries> {
+        &self.kde
+    }
+
+    pub fn set_kde(&mut self, kde:Vec<ColumnPlotSeries>) {
+        self.kde = kde;
+    }
+
+    //kde curves in the units of the current scale as physical x and y, each scaled by the rows of its own group and the width of the bin under x
+    pub fn kde_points(&self) -> Vec<(&str, Vec<(f64, f64)>)> {
+        if matches!(self.view.scale, HistogramScale::CumulativeCount | HistogramScale::CumulativePercent) {
+            return vec![];
+        }
+
+        let counts = self.values.iter().map(|(_, c)| *c).collect::<Vec<_>>();
+        let binned = |counts:&[u32]| counts.iter().take(self.edges.len().saturating_sub(1)).sum::<u32>() as f64; //without the n/a bar
+        let total = self.total(&counts) as f64;
+        let normalized = self.view.normalized && !self.series.is_empty();
+
+        self.kde.iter().map(|kde| {
+            //kde series are named after the split category, followed by the bandwidth
+            let group = if self.series.is_empty() {
+                binned(&counts)
+            } else {
+                self.series.iter()
+                    .find(|(name, _)| kde.name().starts_with(&format!("{} (h=", name)))
+                    .map(|(_, counts)| binned(counts))
+                    .unwrap_or(0.0)
+            };
+            let total = if normalized { group } else { total }.max(1.0);
+
+            let points = kde.points().iter().filter_map(|(x, density)| {
+                let bin_width = self.edges.windows(2).find(|w| *x >= w[0] && *x <= w[1]).map(|w| w[1] - w[0])?;
+
+                match self.view.scale {
+                    HistogramScale::Count if normalized => Some((*x, density * 100.0 * bin_width)),
+                    HistogramScale::Count => Some((*x, density * group * bin_width)),
+                    HistogramScale::Percent => Some((*x, density * 100.0 * bin_width * group / total)),
+                    HistogramScale::Density => Some((*x, density * group / total)),
+                    _ => None,
+                }
+            }).collect();
+
+            (kde.name().as_str(), points)
+        }).collect()
+    }
+
+    pub fn view(&self) -> HistogramView {
+        self.view
+    }
+
+    pub fn set_view(&mut self, view:HistogramView) {
+        self.view = view;
+    }
+
+    //values as shown for the current scale, shares and densities of the rows with a value
+    pub fn scaled_values(&self) -> Vec<f64> {
+        let counts = self.values.iter().map(|(_, c)| *c).collect::<Vec<_>>();
+        self.scale(self.view.scale, &counts, self.total(&counts))
+    }
+
+    //series values as shown, relative to the category total when normalized and to the overall total otherwise
+    pub fn series_values(&self, i:usize) -> Vec<f64> {
+        self.series.get(i).map(|(_, counts)| {
+            let total = if self.view.normalized {
+                self.total(counts)
+            } else {
+                self.total(&self.values.iter().map(|(_, c)| *c).collect::<Vec<_>>())
+            };
+
+            if self.view.normalized && self.view.scale == HistogramScale::Count {
+                self.scale(HistogramScale::Percent, counts, total)
+            } else {
+                self.scale(self.view.scale, counts, total)
+            }
+        }).unwrap_or_default()
+    }
+
+    //the n/a bar is left out of the totals
+    fn total(&self, counts:&[u32]) -> u32 {
+        counts.iter().enumerate().filter(|(i, _)| !self.is_na(*i)).map(|(_, c)| *c).sum()
+    }
+
+    fn is_na(&self, bar:usize) -> bool {
+        matches!(self.conditions.get(bar), Some(Some(Condition::Equals { value: None })))
+    }
+
+    fn scale(&self, scale:HistogramScale, counts:&[u32], total:u32) -> Vec<f64> {
+        let total = total.max(1) as f64;
+        let bin_width = |i:usize| self.edges.get(i + 1).zip(self.edges.get(i)).map(|(b, a)| b - a).filter(|w| *w > 0.0).unwrap_or(1.0);
+
+        match scale {
+            HistogramScale::Count => counts.iter().map(|c| *c as f64).collect(),
+            HistogramScale::Percent => counts.iter().map(|c| *c as f64 * 100.0 / total).collect(),
+            HistogramScale::Density => counts.iter().enumerate().map(|(i, c)| *c as f64 / (total * bin_width(i))).collect(),
+            HistogramScale::CumulativeCount | HistogramScale::CumulativePercent => {
+                let k = if scale == HistogramScale::CumulativePercent { 100.0 / total } else { 1.0 };
+                counts.iter().enumerate().scan(0u32, |sum, (i, c)| {
+                    if self.is_na(i) { //not part of the running sum
+                        return Some(*c as f64 * k);
+                    }
+
+                    *sum += c;
+                    Some(*sum as f64 * k)
+                }).collect()
+            }
+        }
+    }
+
+    //fractional bin position of a physical value, 0.0 is the left edge of the first bin
+    pub fn bin_position(&self, value:f64) -> Option<f64> {
+        let i = self.edges.windows(2).position(|w| value >= w[0] && value <= w[1])?;
+        let (a, b) = (self.edges[i], self.edges[i + 1]);
+        Some(i as f64 + if b > a { (value - a) / (b - a) } else { 0.5 })
+    }
+
+    pub fn set_conditions(&mut self, conditions:Vec<Option<Condition>>) {
+        self.conditions = conditions;
+    }
+
+    pub fn is_selectable(&self, bar:usize) -> bool {
+        matches!(self.conditions.get(bar), Some(Some(_)))
+    }
+
+    pub fn selected(&self) -> &Vec<usize> {
+        &self.selected
+    }
+
+    pub fn set_selected(&mut self, selected:Vec<usize>) {
+        self.selected = selected.into_iter().filter(|bar| self.is_selectable(*bar)).collect();
+    }
+
+    //selection after a click on the bar, multi adds or removes it
+    pub fn toggle_selected(&self, bar:usize, multi:bool) -> Vec<usize> {
+        let mut selected = if multi { self.selected.clone() } else { vec![] };
+
+        if let Some(i) = selected.iter().position(|b| *b == bar) {
+            selected.remove(i);
+        } else if multi || !self.selected.eq(&vec![bar]) {
+            selected.push(bar);
+        }
+
+        selected.sort();
+        selected
+    }
+
+    //ranges for bins, equality for categories, OR-ed for several bars
+    pub fn selection_condition(&self) -> Option<ConditionType> {
+        let mut conditions = self.selected.iter()
+            .filter_map(|bar| self.conditions.get(*bar).cloned().flatten())
+            .map(|condition| ConditionType::Single { column_name: self.column.clone(), condition })
+            .collect::<Vec<_>>();
+
+        match conditions.len() {
+            0 => None,
+            1 => conditions.pop(),
+            _ => Some(ConditionType::Compoiste { conditions, ctype: CompositeType::OR }),
+        }
+    }
+
+    pub fn replace_data(&mut self, other:Histogram) {
+        //the overlay was computed for other rows or groups
+        if self.params.split() != other.params.split() || self.params.compare() != other.params.compare() || self.params.range() != other.params.range() {
+            self.kde = vec![];
+        }
+
+        self.params = other.params;
+        self.values = other.values;
+        self.series = other.series;
+        self.edges = other.edges;
+        self.temporal = other.temporal;
+        self.markers = other.markers;
+        self.conditions = other.conditions;
+        self.selected = vec![];
+    }
+}
+
+/////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CorrMethod {
+    Pearson,
+    Spearman,
+    Kendall,
+    Covariance
+}
+
+impl CorrMethod {
+    pub fn label(&self) -> &str {
+        match self {
+            CorrMethod::Pearson => {
+                "Pearson"
+            }
+            CorrMethod::Spearman => {
+                "Spearman"
+            }
+            CorrMethod::Kendall => {
+                "Kendall tau-b"
+            }
+            CorrMethod::Covariance => {
+                "covariance"
+            }
+        }
+    }
+
+    pub fn all() -> [CorrMethod; 4] {
+        [CorrMethod::Pearson, CorrMethod::Spearman, CorrMethod::Kendall, CorrMethod::Covariance]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CorrMeasure {
+    Pearson,
+    Spearman,
+    Kendall,
+    CramersV,
+    CorrelationRatio,
+    PointBiserial,
+    Covariance
+}
+
+impl CorrMeasure {
+    pub fn label(&self) -> &str {
+        match self {
+            CorrMeasure::Pearson => {
+                "Pearson r"
+            }
+            CorrMeasure::Spearman => {
+                "Spearman ρ"
+            }
+            CorrMeasure::Kendall => {
+                "Kendall τ-b"
+            }
+            CorrMeasure::CramersV => {
+                "Cramér's V"
+            }
+            CorrMeasure::CorrelationRatio => {
+                "correlation ratio η"
+            }
+            CorrMeasure::PointBiserial => {
+                "point-biserial r"
+            }
+            CorrMeasure::Covariance => {
+                "covariance"
+            }
+        }
+    }
+}
+
+impl From<CorrMethod> for CorrMeasure {
+    fn from(method: CorrMethod) -> Self {
+        match method {
+            CorrMethod::Pearson => CorrMeasure::Pearson,
+            CorrMethod::Spearman => CorrMeasure::Spearman,
+            CorrMethod::Kendall => CorrMeasure::Kendall,
+            CorrMethod::Covariance => CorrMeasure::Covariance,
+        }
+    }
+}
+
+pub const CORR_ALPHA: f64 = 0.05;
+
+#[derive(Debug, Clone)]
+pub struct CorrValue {
+    measure:CorrMeasure,
+    value:Option<f32>, //None if undefined, e.g. a constant column
+    n:usize, //complete pairs
+    p_value:Option<f64>,
+    ci:Option<(f32, f32)>, //95%
+    r:Option<f32>, //Pearson r of the same pairs, covariances only
+}
+
+impl CorrValue {
+    pub fn new(measure: CorrMeasure, value: Option<f32>, n: usize) -> Self {
+        Self {
+            measure,
+            value: value.filter(|v| v.is_finite()),
+            n,
+            p_value: None,
+            ci: None,
+            r: None,
+        }
+    }
+
+    pub fn with_r(mut self, r: Option<f32>) -> Self {
+        self.r = r.filter(|r| r.is_finite());
+        self
+    }
+
+    pub fn with_p_value(mut self, p_value: Option<f64>) -> Self {
+        self.p_value = p_value.filter(|p| p.is_finite());
+        self
+    }
+
+    pub fn with_ci(mut self, ci: Option<(f32, f32)>) -> Self {
+        self.ci = ci.filter(|(lo, hi)| lo.is_finite() && hi.is_finite());
+        self
+    }
+
+    pub fn measure(&self) -> CorrMeasure {
+        self.measure
+    }
+
+    pub fn value(&self) -> Option<f32> {
+        self.value
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    pub fn p_value(&self) -> Option<f64> {
+        self.p_value
+    }
+
+    pub fn ci(&self) -> Option<(f32, f32)> {
+        self.ci
+    }
+
+    pub fn r(&self) -> Option<f32> {
+        self.r
+    }
+
+    pub fn is_significant(&self) -> bool {
+        self.value.is_some() && self.p_value.map(|p| p < CORR_ALPHA).unwrap_or(false)
+    }
+}
+
+#[derive(Debug)]
+pub struct Corrs {
+    id:u128,
+    frame_id: u128,
+    columns:Vec<String>,
+    method:CorrMethod,
+    values:HashMap<(String, String), CorrValue>,
+    order:Vec<usize>, //display order of columns
+    clustered:bool,
+    selected:Option<(String, String)>, //(x, y) cell
+    title: String,
+}
+
+impl Corrs {
+    pub fn new(frame_id: u128, columns:Vec<String>, method:CorrMethod, values:HashMap<(String, String), CorrValue>) -> Self {
+        Self {
+            id: wa_id(),
+            frame_id,
+            method,
+            values,
+            order: (0..columns.len()).collect(),
+            columns,
+            clustered: false,
+            selected: None,
+            title: if method == CorrMethod::Covariance { String::from("Covariances") } else { format!("Correlations ({})", method.label()) },//TODO columns
+        }
+    }
+
+    pub fn method(&self) -> CorrMethod {
+        self.method
+    }
+
+
+
+    pub fn _title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn values(&self) -> &HashMap<(String, String), CorrValue> {
+        &self.values
+    }
+
+    //pairs are stored once, in columns order, covariances also keep the variances on the diagonal
+    pub fn get(&self, c_0:&String, c_1:&String) -> Option<&CorrValue> {
+        self.values.get(&(c_0.into(), c_1.into())).or_else(|| self.values.get(&(c_1.into(), c_0.into())))
+    }
+
+    pub fn get_val(&self, c_0:&String, c_1:&String) -> Option<f32> {
+        self.get(c_0, c_1).and_then(|v| v.value())
+    }
+
+    pub fn get_measure(&self, c_0:&String, c_1:&String) -> Option<CorrMeasure> {
+        self.get(c_0, c_1).map(|v| v.measure())
+    }
+
+    pub fn ordered_columns(&self) -> Vec<String> {
+        self.order.iter().map(|i| self.columns[*i].clone()).collect()
+    }
+
+    pub fn is_clustered(&self) -> bool {
+        self.clustered
+    }
+
+    pub fn selected(&self) -> Option<&(String, String)> {
+        self.selected.as_ref()
+    }
+
+    pub fn set_selected(&mut self, selected:Option<(String, String)>) {
+        self.selected = selected;
+    }
+
+    //value on the -1..1 scale, covariances use r of the same pairs
+    pub fn strength(&self, c_0:&String, c_1:&String) -> Option<f32> {
+        let corr = self.get(c_0, c_1)?;
+        if corr.measure() == CorrMeasure::Covariance {
+            corr.r()
+        } else {
+            corr.value()
+        }
+    }
+
+    //max |value| over the covariance cells for covariances, 1 for coefficients, also for η and V in a covariance matrix
+    pub fn scale(&self, measure:CorrMeasure) -> f32 {
+        if measure == CorrMeasure::Covariance {
+            self.values.values()
+                .filter(|v| v.measure() == CorrMeasure::Covariance)
+                .filter_map(|v| v.value())
+                .fold(0.0f32, |max, v| max.max(v.abs()))
+                .max(f32::MIN_POSITIVE)
+        } else {
+            1.0
+        }
+    }
+
+    //hierarchical clustering on 1 - |strength|, undefined pairs count as uncorrelated
+    pub fn set_clustered(&mut self, clustered:bool) {
+        self.clustered = clustered;
+        self.order = if clustered {
+            let distances = self.columns.iter()
+                .map(|c_0| self.columns.iter()
+                    .map(|c_1| if c_0 == c_1 { 0.0 } else { 1.0 - self.strength(c_0, c_1).map(|v| f64::from(v.abs())).unwrap_or(0.0) })
+                    .collect())
+                .collect::<Vec<Vec<f64>>>();
+            average_linkage_order(&distances)
+        } else {
+            (0..self.columns.len()).collect()
+        };
+    }
+
+    pub fn id(&self) -> &u128 {
+        &self.id
+    }
+    pub fn frame_id(&self) -> &u128 {
+        &self.frame_id
+    }
+    pub fn columns(&self) -> &Vec<String> {
+        &self.columns
+    }
+}
+/////////////////////////
+#[derive(Debug)]
+pub struct ColumnPlotSeries {
+    name:String,
+    points:Vec<(f64, f64)>,
+    downsampled_from:Option<usize>, //points before downsampling
+}
+
+impl ColumnPlotSeries {
+    pub fn new(name:String, points:Vec<(f64, f64)>) -> Self {
+        Self {
+            name,
+            points,
+            downsampled_from: None,
+        }
+    }
+
+    pub fn with_downsampled_from(mut self, downsampled_from:Option<usize>) -> Self {
+        self.downsampled_from = downsampled_from;
+        self
+    }
+
+    pub fn downsampled_from(&self) -> Option<usize> {
+        self.downsampled_from
+    }
+
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn points(&self) -> &Vec<(f64, f64)> {
+        &self.points
+    }
+}
+
+#[derive(Debug)]
+pub struct ColumnsPlot {
+    id:u128,
+    frame_id: u128,
+    title: String,
+    series:Vec<ColumnPlotSeries>,
+}
+
+impl ColumnsPlot {
+    pub fn new(frame_id: u128, columns:Vec<String>, series:Vec<ColumnPlotSeries>) -> Self {
+        Self {
+            id: wa_id(),
+            frame_id,
+            title: format!("Data plot/{}", columns.join(", ")),
+            series,
+        }
+    }
+
+    pub fn id(&self) -> &u128 {
+        &self.id
+    }
+
+    pub fn frame_id(&self) -> &u128 {
+        &self.frame_id
+    }
+
+    pub fn _title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn series(&self) -> &Vec<ColumnPlotSeries> {
+        &self.series
+    }
+}
+/////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bandwidth {
+    Silverman,
+    Scott,
+    Manual{h:f64}
+}
+
+impl Bandwidth {
+    pub fn label(&self) -> &str {
+        match self {
+            Bandwidth::Silverman => {
+                "Silverman"
+            }
+            Bandwidth::Scott => {
+                "Scott"
+            }
+            Bandwidth::Manual { .. } => {
+                "manual"
+            }
+        }
+    }
+
+    pub fn all() -> [Bandwidth; 3] {
+        [Bandwidth::Silverman, Bandwidth::Scott, Bandwidth::Manual { h: 1.0 }]
+    }
+}
+
+#[derive(Debug)]
+pub struct Kde {
+    id:u128,
+    frame_id: u128,
+    title: String,
+    column:String,
+    bandwidth:Bandwidth,
+    split:Option<String>,
+    sample_size:Option<usize>, //Some if the column was sampled
+    series:Vec<ColumnPlotSeries>,
+}
+
+impl Kde {
+    pub fn new(frame_id: u128, column:String, bandwidth:Bandwidth, split:Option<String>, sample_size:Option<usize>, series:Vec<ColumnPlotSeries>) -> Self {
+        Self {
+            id: wa_id(),
+            frame_id,
+            title: format!("KDE/{}", column),
+            column,
+            bandwidth,
+            split,
+            sample_size,
+            series,
+        }
+    }
+
+    pub fn id(&self) -> &u128 {
+        &self.id
+    }
+
+    pub fn frame_id(&self) -> u128 {
+        self.frame_id
+    }
+
+    pub fn _title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn column(&self) -> &String {
+        &self.column
+    }
+
+    pub fn bandwidth(&self) -> Bandwidth {
+        self.bandwidth
+    }
+
+    pub fn split(&self) -> Option<&String> {
+        self.split.as_ref()
+    }
+
+    pub fn sample_size(&self) -> Option<usize> {
+        self.sample_size
+    }
+
+    pub fn series(&self) -> &Vec<ColumnPlotSeries> {
+        &self.series
+    }
+
+    pub fn replace_data(&mut self, other:Kde) {
+        self.bandwidth = other.bandwidth;
+        self.split = other.split;
+        self.sample_size = other.sample_size;
+        self.series = other.series;
+    }
+}
+/////////////////////////
+#[derive(Debug)]
+pub struct Histogram2D {
+    id:u128,
+    frame_id: u128,
+    title: String,
+    column_x:String,
+    column_y:String,
+    edges_x:Vec<f64>, //physical values, dates and datetimes as in the column
+    edges_y:Vec<f64>,
+    labels_x:Vec<String>,
+    labels_y:Vec<String>,
+    counts:Vec<Vec<u32>>, //[y][x]
+}
+
+impl Histogram2D {
+    pub fn new(frame_id: u128, column_x:String, column_y:String, edges_x:Vec<f64>, edges_y:Vec<f64>, labels_x:Vec<String>, labels_y:Vec<String>, counts:Vec<Vec<u32>>) -> Self {
+        Self {
+            id: wa_id(),
+            frame_id,
+            title: format!("histogram/{} x {}", column_x, column_y),
+            column_x,
+            column_y,
+            edges_x,
+            edges_y,
+            labels_x,
+            labels_y,
+            counts,
+        }
+    }
+
+    pub fn id(&self) -> &u128 {
+        &self.id
+    }
+
+    pub fn frame_id(&self) -> u128 {
+        self.frame_id
+    }
+
+    pub fn _title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn column_x(&self) -> &String {
+        &self.column_x
+    }
+
+    pub fn column_y(&self) -> &String {
+        &self.column_y
+    }
+
+    pub fn bins_x(&self) -> usize {
+        self.labels_x.len()
+    }
+
+    pub fn bins_y(&self) -> usize {
+        self.labels_y.len()
+    }
+
+    pub fn labels_x(&self) -> &Vec<String> {
+        &self.labels_x
+    }
+
+    pub fn labels_y(&self) -> &Vec<String> {
+        &self.labels_y
+    }
+
+    pub fn counts(&self) -> &Vec<Vec<u32>> {
+        &self.counts
+    }
+
+    pub fn count(&self, x:usize, y:usize) -> u32 {
+        self.counts.get(y).and_then(|row| row.get(x)).copied().unwrap_or(0)
+    }
+
+    pub fn max_count(&self) -> u32 {
+        self.counts.iter().flat_map(|row| row.iter()).copied().max().unwrap_or(0)
+    }
+
+    pub fn to_condition(&self, x:usize, y:usize) -> Option<ConditionType> {
+        let range = |column:&String, edges:&Vec<f64>, labels:&Vec<String>, i:usize| Some(ConditionType::Single {
+            column_name: column.clone(),
+            condition: Condition::Range {
+                min: *edges.get(i)?,
+                max: *edges.get(i + 1)?,
+                closed: i + 2 == edges.len(),
+                label: labels.get(i)?.clone(),
+            },
+        });
+
+        Some(ConditionType::Compoiste {
+            conditions: vec![
+                range(&self.column_x, &self.edges_x, &self.labels_x, x)?,
+                range(&self.column_y, &self.edges_y, &self.labels_y, y)?,
+            ],
+            ctype: CompositeType::AND,
+        })
+    }
+}
+
+/////////////////////////
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScatterParams {
+    column_x:String,
+    column_y:String,
+    color_by:Option<String>, //categories as series
+    size_by:Option<String>, //numeric column scaled to the marker radius
+}
+
+impl ScatterParams {
+    pub fn new(column_x: String, column_y: String) -> Self {
+        Self { column_x, column_y, color_by: None, size_by: None }
+    }
+
+    pub fn with_color_by(mut self, color_by: Option<String>) -> Self {
+        self.color_by = color_by;
+        self
+    }
+
+    pub fn with_size_by(mut self, size_by: Option<String>) -> Self {
+        self.size_by = size_by;
+        self
+    }
+
+    pub fn column_x(&self) -> &String {
+        &self.column_x
+    }
+
+    pub fn column_y(&self) -> &String {
+        &self.column_y
+    }
+
+    pub fn color_by(&self) -> Option<&String> {
+        self.color_by.as_ref()
+    }
+
+    pub fn size_by(&self) -> Option<&String> {
+        self.size_by.as_ref()
+    }
+}
+
+#[derive(Debug)]
+pub struct ScatterSeries {
+    name:String,
+    points:Vec<[f64; 2]>,
+    sizes:Vec<f64>, //one per point, empty without size_by
+}
+
+impl ScatterSeries {
+    pub fn new(name: String, points: Vec<[f64; 2]>, sizes: Vec<f64>) -> Self {
+        Self { name, points, sizes }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn points(&self) -> &Vec<[f64; 2]> {
+        &self.points
+    }
+
+    pub fn sizes(&self) -> &Vec<f64> {
+        &self.sizes
+    }
+}
+
+#[derive(Debug)]
+pub struct Scatter {
+    id:u128,
+    frame_id: u128,
+    title: String,
+    params:ScatterParams,
+    series:Vec<ScatterSeries>,
+    rows:usize, //complete rows before sampling
+    numeric_columns:Vec<String>, //options for size_by
+    categoric_columns:Vec<String>, //options for color_by
+}
+
+impl Scatter {
+    pub fn new(frame_id: u128, params:ScatterParams, series:Vec<ScatterSeries>, rows:usize, numeric_columns:Vec<String>, categoric_columns:Vec<String>) -> Self {
+        Self {
+            id: wa_id(),
+            frame_id,
+            title: format!("scatter/{} x {}", params.column_x(), params.column_y()),
+            params,
+            series,
+            rows,
+            numeric_columns,
+            categoric_columns,
+        }
+    }
+
+    pub fn replace_data(&mut self, other: Scatter) {
+        self.params = other.params;
+        self.series = other.series;
+        self.rows = other.rows;
+        self.numeric_columns = other.numeric_columns;
+        self.categoric_columns = other.categoric_columns;
+    }
+
+    pub fn id(&self) -> &u128 {
+        &self.id
+    }
+
+    pub fn frame_id(&self) -> u128 {
+        self.frame_id
+    }
+
+    pub fn _title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn params(&self) -> &ScatterParams {
+        &self.params
+    }
+
+    pub fn series(&self) -> &Vec<ScatterSeries> {
+        &self.series
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn shown(&self) -> usize {
+        self.series.iter().map(|s| s.points().len()).sum()
+    }
+
+    pub fn is_sampled(&self) -> bool {
+        self.shown() < self.rows
+    }
+
+    pub fn size_range(&self) -> Option<(f64, f64)> {
+        self.series.iter()
+            .flat_map(|s| s.sizes().iter())
+            .fold(None, |range, v| match range {
+                Some((min, max)) => Some((v.min(min), v.max(max))),
+                None => Some((*v, *v)),
+            })
+    }
+
+    pub fn numeric_columns(&self) -> &Vec<String> {
+        &self.numeric_columns
+    }
+
+    pub fn categoric_columns(&self) -> &Vec<String> {
+        &self.categoric_columns
+    }
+}
+
+/////////////////////////
+#[derive(Debug)]
+pub struct BoxGroup {
+    name:String,
+    n:usize,
+    quartiles:[f64; 3],
+    whiskers:(f64, f64), //most extreme values within 1.5 IQR of the box
+    outliers:Vec<f64>, //sampled if there are too many to draw
+    outlier_count:usize,
+    density:Vec<(f64, f64)>, //kde for the violin, empty if it can't be estimated
+}
+
+impl BoxGroup {
+    pub fn new(name: String, n: usize, quartiles: [f64; 3], whiskers: (f64, f64), outliers: Vec<f64>, outlier_count: usize, density: Vec<(f64, f64)>) -> Self {
+        Self { name, n, quartiles, whiskers, outliers, outlier_count, density }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    pub fn q1(&self) -> f64 {
+        self.quartiles[0]
+    }
+
+    pub fn median(&self) -> f64 {
+        self.quartiles[1]
+    }
+
+    pub fn q3(&self) -> f64 {
+        self.quartiles[2]
+    }
+
+    pub fn whiskers(&self) -> (f64, f64) {
+        self.whiskers
+    }
+
+    pub fn outliers(&self) -> &Vec<f64> {
+        &self.outliers
+    }
+
+    pub fn outlier_count(&self) -> usize {
+        self.outlier_count
+    }
+
+    pub fn density(&self) -> &Vec<(f64, f64)> {
+        &self.density
+    }
+}
+
+#[derive(Debug)]
+pub struct BoxPlot {
+    id:u128,
+    frame_id: u128,
+    title: String,
+    column:String,
+    split_by:Option<String>,
+    groups:Vec<BoxGroup>,
+    categoric_columns:Vec<String>, //options for split_by
+    violin:bool,
+}
+
+impl BoxPlot {
+    pub fn new(frame_id: u128, column:String, split_by:Option<String>, groups:Vec<BoxGroup>, categoric_columns:Vec<String>) -> Self {
+        Self {
+            id: wa_id(),
+            frame_id,
+            title: format!("box plot/{}", column),
+            column,
+            split_by,
+            groups,
+            categoric_columns,
+            violin: false,
+        }
+    }
+
+    pub fn replace_data(&mut self, other: BoxPlot) {
+        self.split_by = other.split_by;
+        self.groups = other.groups;
+        self.categoric_columns = other.categoric_columns;
+    }
+
+    pub fn id(&self) -> &u128 {
+        &self.id
+    }
+
+    pub fn frame_id(&self) -> u128 {
+        self.frame_id
+    }
+
+    pub fn _title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn column(&self) -> &String {
+        &self.column
+    }
+
+    pub fn split_by(&self) -> Option<&String> {
+        self.split_by.as_ref()
+    }
+
+    pub fn groups(&self) -> &Vec<BoxGroup> {
+        &self.groups
+    }
+
+    pub fn categoric_columns(&self) -> &Vec<String> {
+        &self.categoric_columns
+    }
+
+    pub fn is_violin(&self) -> bool {
+        self.violin
+    }
+
+    pub fn set_violin(&mut self, violin:bool) {
+        self.violin = violin;
+    }
+}
+
+/////////////////////////
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeSeriesParams {
+    column_x:String,
+    columns_y:Vec<String>, //empty plots the row count per period
+    unit:Option<CalendarUnit>, //None picks one from the time span
+    agg:ResampleAgg,
+    rolling:Option<usize>, //window of the rolling mean overlay, in periods
+}
+
+impl TimeSeriesParams {
+    pub fn new(column_x: String, columns_y: Vec<String>) -> Self {
+        Self { column_x, columns_y, unit: None, agg: ResampleAgg::Mean, rolling: None }
+    }
+
+    pub fn with_columns_y(mut self, columns_y: Vec<String>) -> Self {
+        self.columns_y = columns_y;
+        self
+    }
+
+    pub fn with_unit(mut self, unit: Option<CalendarUnit>) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    pub fn with_agg(mut self, agg: ResampleAgg) -> Self {
+        self.agg = agg;
+        self
+    }
+
+    pub fn with_rolling(mut self, rolling: Option<usize>) -> Self {
+        self.rolling = rolling;
+        self
+    }
+
+    pub fn column_x(&self) -> &String {
+        &self.column_x
+    }
+
+    pub fn columns_y(&self) -> &Vec<String> {
+        &self.columns_y
+    }
+
+    pub fn unit(&self) -> Option<CalendarUnit> {
+        self.unit
+    }
+
+    pub fn agg(&self) -> ResampleAgg {
+        self.agg
+    }
+
+    pub fn rolling(&self) -> Option<usize> {
+        self.rolling
+    }
+}
+
+#[derive(Debug)]
+pub struct TimeSeries {
+    id:u128,
+    frame_id: u128,
+    title: String,
+    params:TimeSeriesParams,
+    unit:CalendarUnit, //resampling period actually used
+    series:Vec<ColumnPlotSeries>, //x in epoch milliseconds
+    rolling:Vec<ColumnPlotSeries>,
+    datetime_columns:Vec<String>, //options for column_x
+    numeric_columns:Vec<String>, //options for columns_y
+}
+
+impl TimeSeries {
+    pub fn new(frame_id: u128, params:TimeSeriesParams, unit:CalendarUnit, series:Vec<ColumnPlotSeries>, rolling:Vec<ColumnPlotSeries>, datetime_columns:Vec<String>, numeric_columns:Vec<String>) -> Self {
+        Self {
+            id: wa_id(),
+            frame_id,
+            title: format!("time series/{}", params.column_x()),
+            params,
+            unit,
+            series,
+            rolling,
+            datetime_columns,
+            numeric_columns,
+        }
+    }
+
+    pub fn replace_data(&mut self, other: TimeSeries) {
+        self.title = other.title;
+        self.params = other.params;
+        self.unit = other.unit;
+        self.series = other.series;
+        self.rolling = other.rolling;
+        self.datetime_columns = other.datetime_columns;
+        self.numeric_columns = other.numeric_columns;
+    }
+
+    pub fn id(&self) -> &u128 {
+        &self.id
+    }
+
+    pub fn frame_id(&self) -> u128 {
+        self.frame_id
+    }
+
+    pub fn _title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn params(&self) -> &TimeSeriesParams {
+        &self.params
+    }
+
+    pub fn unit(&self) -> CalendarUnit {
+        self.unit
+    }
+
+    pub fn series(&self) -> &Vec<ColumnPlotSeries> {
+        &self.series
+    }
+
+    pub fn rolling(&self) -> &Vec<ColumnPlotSeries> {
+        &self.rolling
+    }
+
+    pub fn datetime_columns(&self) -> &Vec<String> {
+        &self.datetime_columns
+    }
+
+    pub fn numeric_columns(&self) -> &Vec<String> {
+        &self.numeric_columns
+    }
+}
+
+/////////////////////////
+pub const PAIR_PLOT_MAX_COLUMNS:usize = 8; //n*n cells are drawn
+
+#[derive(Debug)]
+pub struct PairPlot {
+    id:u128,
+    frame_id: u128,
+    title: String,
+    columns:Vec<String>,
+    values:Vec<Vec<f64>>, //per column, all over the same sampled rows
+    histograms:Vec<Vec<(f64, f64, u32)>>, //per column bins of the sample, from, to and count
+    rows:usize, //complete rows before sampling
+}
+
+impl PairPlot {
+    pub fn new(frame_id: u128, columns:Vec<String>, values:Vec<Vec<f64>>, histograms:Vec<Vec<(f64, f64, u32)>>, rows:usize) -> Self {
+        Self {
+            id: wa_id(),
+            frame_id,
+            title: format!("pair plot/{}", columns.join(", ")),
+            columns,
+            values,
+            histograms,
+            rows,
+        }
+    }
+
+    pub fn id(&self) -> &u128 {
+        &self.id
+    }
+
+    pub fn frame_id(&self) -> u128 {
+        self.frame_id
+    }
+
+    pub fn _title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn columns(&self) -> &Vec<String> {
+        &self.columns
+    }
+
+    pub fn values(&self) -> &Vec<Vec<f64>> {
+        &self.values
+    }
+
+    pub fn histograms(&self) -> &Vec<Vec<(f64, f64, u32)>> {
+        &self.histograms
+    }
+
+    pub fn points(&self, x:usize, y:usize) -> Vec<[f64; 2]> {
+        std::iter::zip(&self.values[x], &self.values[y]).map(|(x, y)| [*x, *y]).collect()
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn shown(&self) -> usize {
+        self.values.first().map(|v| v.len()).unwrap_or(0)
+    }
+
+    pub fn is_sampled(&self) -> bool {
+        self.shown() < self.rows
+    }
+}
+
+/////////////////////////
+#[derive(Debug)]
+pub struct DataLump {
+    id:u128,
+    frame_id: u128,
+    offset:usize,
+    title: String,
+    data:Vec<Vec<Option<String>>>,
+    columns:Vec<(WapukuDataType, String)>
+}
+
+impl DataLump {
+
+    pub fn new(frame_id: u128, offset:usize, rows:usize, columns:usize) -> Self {
+        let column_vec = (0..columns).map(|_|None).collect::<Vec<Option<String>>>();
+        Self {
+            id: wa_id(),
+            frame_id,
+            offset,
+            title: format!("data"),
+            data: (0..rows).map(|_|column_vec.clone()).collect(),
+            columns: vec![]
+        }
+    }
+
+    pub fn add_column(&mut self,  dtype:WapukuDataType, name:impl Into<String>) {
+        self.columns.push((dtype, name.into()));
+    }
+
+    pub fn set_value(&mut self, row:usize, col:usize, val:String) {
+        self.data[row][col].replace(val);
+    }
+
+    pub fn data(&self) ->&Vec<Vec<Option<String>>> {
+        &self.data
+    }
+
+
+
+    pub fn id(&self) -> &u128 {
+        &self.id
+    }
+
+
+
+    pub fn _title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn columns(&self) -> &Vec<(WapukuDataType, String)> {
+        &self.columns
+    }
+    pub fn frame_id(&self) -> &u128 {
+        &self.frame_id
+    }
+    pub fn offset(&self) -> &usize {
+        &self.offset
+    }
+    pub fn replace_data(&mut self, other:DataLump) {
+        self.offset = other.offset;
+        self.title = other.title;
+        self.data = other.data;
+    }
+}
+/////////////////////////
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataQualityIssueType {
+    HighNullShare,
+    Constant,
+    DuplicateRows,
+    MixedTypes,
+    Whitespace,
+    Sentinel,
+    NonUniqueId
+}
+
+impl DataQualityIssueType {
+    pub fn label(&self) -> &str {
+        match self {
+            DataQualityIssueType::HighNullShare => {
+                "high null share"
+            }
+            DataQualityIssueType::Constant => {
+                "constant"
+            }
+            DataQualityIssueType::DuplicateRows => {
+                "duplicate rows"
+            }
+            DataQualityIssueType::MixedTypes => {
+                "mixed types"
+            }
+            DataQualityIssueType::Whitespace => {
+                "whitespace"
+            }
+            DataQualityIssueType::Sentinel => {
+                "sentinel values"
+            }
+            DataQualityIssueType::NonUniqueId => {
+                "non unique id"
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DataQualityIssue {
+    column:Option<String>,
+    issue_type:DataQualityIssueType,
+    affected:usize,
+    details:String
+}
+
+impl DataQualityIssue {
+    pub fn new(column: Option<String>, issue_type: DataQualityIssueType, affected: usize, details: impl Into<String>) -> Self {
+        Self { column, issue_type, affected, details:details.into() }
+    }
+
+    pub fn column(&self) -> Option<&String> {
+        self.column.as_ref()
+    }
+
+    pub fn issue_type(&self) -> &DataQualityIssueType {
+        &self.issue_type
+    }
+
+    pub fn affected(&self) -> usize {
+        self.affected
+    }
+
+    pub fn details(&self) -> &str {
+        &self.details
+    }
+}
+
+#[derive(Debug)]
+pub struct DataQuality {
+    id:u128,
+    frame_id: u128,
+    title: String,
+    rows:usize,
+    issues:Vec<DataQualityIssue>
+}
+
+impl DataQuality {
+    pub fn new(frame_id: u128, rows:usize, issues:Vec<DataQualityIssue>) -> Self {
+        Self {
+            id: wa_id(),
+            frame_id,
+            title: format!("Data quality"),
+            rows,
+            issues
+        }
+    }
+
+    pub fn id(&self) -> &u128 {
+        &self.id
+    }
+
+    pub fn frame_id(&self) -> &u128 {
+        &self.frame_id
+    }
+
+    pub fn _title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn issues(&self) -> &Vec<DataQualityIssue> {
+        &self.issues
+    }
+
+    pub fn issues_of(&self, issue_type:DataQualityIssueType) -> impl Iterator<Item = &DataQualityIssue> {
+        self.issues.iter().filter(move |i|i.issue_type == issue_type)
+    }
+}
+/////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueCountsSort {
+    CountDesc,
+    CountAsc,
+    ValueAsc,
+    ValueDesc
+}
+
+impl ValueCountsSort {
+    pub fn label(&self) -> &str {
+        match self {
+            ValueCountsSort::CountDesc => {
+                "count ⏷"
+            }
+            ValueCountsSort::CountAsc => {
+                "count ⏶"
+            }
+            ValueCountsSort::ValueAsc => {
+                "value ⏶"
+            }
+            ValueCountsSort::ValueDesc => {
+                "value ⏷"
+            }
         }
     }
 
-    pub fn frame_id(&self) -> u128 {
-        self.frame_id
+    pub fn all() -> [ValueCountsSort; 4] {
+        [ValueCountsSort::CountDesc, ValueCountsSort::CountAsc, ValueCountsSort::ValueAsc, ValueCountsSort::ValueDesc]
     }
+}
 
-    pub fn _title(&self) -> &str {
-        &self._title
+#[derive(Debug, Clone)]
+pub struct ValueCount {
+    value:Option<String>,
+    count:usize,
+    percent:f32,
+    cumulative_percent:f32
+}
+
+impl ValueCount {
+    pub fn new(value: Option<String>, count: usize, percent: f32, cumulative_percent: f32) -> Self {
+        Self { value, count, percent, cumulative_percent }
     }
 
-    pub fn id(&self) -> u128 {
-        self.id
+    pub fn value(&self) -> Option<&String> {
+        self.value.as_ref()
     }
 
-    pub fn shape(&self) -> &str {
-        &self.shape
+    pub fn count(&self) -> usize {
+        self.count
     }
 
-    pub fn columns(&self) -> &Vec<SummaryColumn> {
-        &self.columns
+    pub fn percent(&self) -> f32 {
+        self.percent
     }
 
-    pub fn change_column_type(&mut self, column_name:String, dtype:SummaryColumn) {
-        if let Some(column) = self.columns.iter_mut().find(|c|c.name.eq(&column_name)) {
-            column.dtype = dtype.dtype
-        } else {
-            error!("change_column_type: no column_name={}", column_name)
+    pub fn cumulative_percent(&self) -> f32 {
+        self.cumulative_percent
+    }
+
+    pub fn to_condition(&self, column_name:String) -> ConditionType {
+        ConditionType::Single {
+            column_name,
+            condition: Condition::Equals { value: self.value.clone() },
         }
     }
 }
 
 #[derive(Debug)]
-pub struct Histogram {
+pub struct ValueCounts {
     id:u128,
     frame_id: u128,
     title: String,
     column:String,
-    values:Vec<(String, u32)>,
+    sort:ValueCountsSort,
+    offset:usize,
+    distinct:usize,
+    values:Vec<ValueCount>
 }
 
-impl Histogram {
-
-    pub fn new(frame_id: u128, column:String, values:Vec<(String, u32)>) -> Self {
+impl ValueCounts {
+    pub fn new(frame_id: u128, column:String, sort:ValueCountsSort, offset:usize, distinct:usize, values:Vec<ValueCount>) -> Self {
         Self {
             id: wa_id(),
             frame_id,
-            title: format!("histogram/{}", column),
+            title: format!("value counts/{}", column),
             column,
+            sort,
+            offset,
+            distinct,
             values
         }
     }
@@ -398,189 +2614,435 @@ impl Histogram {
         &self.id
     }
 
-    pub fn frame_id(&self) -> u128 {
-        self.frame_id
+    pub fn frame_id(&self) -> &u128 {
+        &self.frame_id
     }
 
     pub fn _title(&self) -> &str {
         &self.title
     }
 
-    pub fn column(&self) -> &str {
+    pub fn column(&self) -> &String {
         &self.column
     }
 
+    pub fn sort(&self) -> ValueCountsSort {
+        self.sort
+    }
 
-    pub fn values(&self) -> &Vec<(String, u32)> {
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn distinct(&self) -> usize {
+        self.distinct
+    }
+
+    pub fn values(&self) -> &Vec<ValueCount> {
         &self.values
     }
+
+    pub fn replace_data(&mut self, other:ValueCounts) {
+        self.sort = other.sort;
+        self.offset = other.offset;
+        self.distinct = other.distinct;
+        self.values = other.values;
+    }
+}
+/////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GroupByAgg {
+    Count,
+    Sum,
+    Mean,
+    Median,
+    Std,
+    Quantile{q:f64},
+    First,
+    Last,
+    NUnique
+}
+
+impl GroupByAgg {
+    pub fn label(&self) -> String {
+        match self {
+            GroupByAgg::Count => {
+                String::from("count")
+            }
+            GroupByAgg::Sum => {
+                String::from("sum")
+            }
+            GroupByAgg::Mean => {
+                String::from("mean")
+            }
+            GroupByAgg::Median => {
+                String::from("median")
+            }
+            GroupByAgg::Std => {
+                String::from("std")
+            }
+            GroupByAgg::Quantile { q } => {
+                format!("q{}", q)
+            }
+            GroupByAgg::First => {
+                String::from("first")
+            }
+            GroupByAgg::Last => {
+                String::from("last")
+            }
+            GroupByAgg::NUnique => {
+                String::from("n_unique")
+            }
+        }
+    }
+
+    pub fn all() -> [GroupByAgg; 9] {
+        [GroupByAgg::Count, GroupByAgg::Sum, GroupByAgg::Mean, GroupByAgg::Median, GroupByAgg::Std, GroupByAgg::Quantile {q: 0.5}, GroupByAgg::First, GroupByAgg::Last, GroupByAgg::NUnique]
+    }
+
+    pub fn alias(&self, column:&str) -> String {
+        format!("{}_{}", column, self.label())
+    }
 }
 
 /////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PivotAgg {
+    Count,
+    Sum,
+    Mean,
+    Min,
+    Max,
+    NUnique
+}
+
+impl PivotAgg {
+    pub fn label(&self) -> &str {
+        match self {
+            PivotAgg::Count => {
+                "count"
+            }
+            PivotAgg::Sum => {
+                "sum"
+            }
+            PivotAgg::Mean => {
+                "mean"
+            }
+            PivotAgg::Min => {
+                "min"
+            }
+            PivotAgg::Max => {
+                "max"
+            }
+            PivotAgg::NUnique => {
+                "n_unique"
+            }
+        }
+    }
+
+    pub fn all() -> [PivotAgg; 6] {
+        [PivotAgg::Count, PivotAgg::Sum, PivotAgg::Mean, PivotAgg::Min, PivotAgg::Max, PivotAgg::NUnique]
+    }
+
+    //cells add up to the totals, so shares of them make sense
+    pub fn is_additive(&self) -> bool {
+        matches!(self, PivotAgg::Count | PivotAgg::Sum)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PivotNormalization {
+    None,
+    Row,
+    Column,
+    Total
+}
+
+impl PivotNormalization {
+    pub fn label(&self) -> &str {
+        match self {
+            PivotNormalization::None => {
+                "values"
+            }
+            PivotNormalization::Row => {
+                "row %"
+            }
+            PivotNormalization::Column => {
+                "column %"
+            }
+            PivotNormalization::Total => {
+                "total %"
+            }
+        }
+    }
+
+    pub fn all() -> [PivotNormalization; 4] {
+        [PivotNormalization::None, PivotNormalization::Row, PivotNormalization::Column, PivotNormalization::Total]
+    }
+}
+
 #[derive(Debug)]
-pub struct Corrs {
+pub struct Pivot {
     id:u128,
     frame_id: u128,
-    columns:Vec<String>,
-    values:HashMap<(String, String), f32>,
     title: String,
-}
-
-impl Corrs {
-    pub fn new(frame_id: u128, columns:Vec<String>, values:HashMap<(String, String), f32>) -> Self {
+    agg:PivotAgg,
+    row_keys:Vec<String>,
+    column_keys:Vec<String>,
+    cells:HashMap<(usize, usize), f64>,
+    row_totals:Vec<Option<f64>>,
+    column_totals:Vec<Option<f64>>,
+    total:Option<f64>,
+    normalization:PivotNormalization,
+    heat:bool
+}
+
+impl Pivot {
+    pub fn new(frame_id: u128, row_column:&str, column_column:&str, value_column:&str, agg:PivotAgg, row_keys:Vec<String>, column_keys:Vec<String>) -> Self {
         Self {
             id: wa_id(),
             frame_id,
-            columns,
-            values,
-            title: format!("Correlations"),//TODO columns
+            title: format!("pivot/{} x {}/{}({})", row_column, column_column, agg.label(), value_column),
+            agg,
+            cells: HashMap::new(),
+            row_totals: vec![None; row_keys.len()],
+            column_totals: vec![None; column_keys.len()],
+            total: None,
+            row_keys,
+            column_keys,
+            normalization: PivotNormalization::None,
+            heat: false
         }
     }
 
+    pub fn set_cell(&mut self, row:usize, column:usize, value:f64) {
+        self.cells.insert((row, column), value);
+    }
 
-
-    pub fn _title(&self) -> &str {
-        &self.title
+    pub fn set_row_total(&mut self, row:usize, value:f64) {
+        self.row_totals[row] = Some(value);
     }
 
-    pub fn values(&self) -> &HashMap<(String, String), f32> {
-        &self.values
+    pub fn set_column_total(&mut self, column:usize, value:f64) {
+        self.column_totals[column] = Some(value);
     }
 
-    pub fn get_val(&self, c_0:&String, c_1:&String) -> f32{
-        self.values.get(&(c_0.into(), c_1.into())).map(|v|*v).unwrap_or(-1.0)
+    pub fn set_total(&mut self, value:f64) {
+        self.total = Some(value);
     }
 
     pub fn id(&self) -> &u128 {
         &self.id
     }
+
     pub fn frame_id(&self) -> &u128 {
         &self.frame_id
     }
-    pub fn columns(&self) -> &Vec<String> {
-        &self.columns
+
+    pub fn _title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn agg(&self) -> PivotAgg {
+        self.agg
+    }
+
+    pub fn row_keys(&self) -> &Vec<String> {
+        &self.row_keys
+    }
+
+    pub fn column_keys(&self) -> &Vec<String> {
+        &self.column_keys
+    }
+
+    pub fn normalization(&self) -> PivotNormalization {
+        self.normalization
+    }
+
+    pub fn heat(&self) -> bool {
+        self.heat
+    }
+
+    pub fn set_normalization(&mut self, normalization:PivotNormalization) {
+        self.normalization = normalization;
+    }
+
+    pub fn set_heat(&mut self, heat:bool) {
+        self.heat = heat;
+    }
+
+    //row/column None is the totals row/column
+    pub fn value(&self, row:Option<usize>, column:Option<usize>) -> Option<f64> {
+        let value = match (row, column) {
+            (Some(row), Some(column)) => self.cells.get(&(row, column)).copied(),
+            (Some(row), None) => self.row_totals.get(row).copied().flatten(),
+            (None, Some(column)) => self.column_totals.get(column).copied().flatten(),
+            (None, None) => self.total,
+        }?;
+
+        if !self.agg.is_additive() {
+            return Some(value);
+        }
+
+        let divisor = match self.normalization {
+            PivotNormalization::None => {
+                return Some(value);
+            }
+            PivotNormalization::Row => row.and_then(|r| self.row_totals.get(r).copied().flatten()).or(self.total),
+            PivotNormalization::Column => column.and_then(|c| self.column_totals.get(c).copied().flatten()).or(self.total),
+            PivotNormalization::Total => self.total,
+        }?;
+
+        if divisor == 0.0 {
+            None
+        } else {
+            Some(value / divisor * 100.0)
+        }
+    }
+
+    pub fn cells_range(&self) -> (f64, f64) {
+        (0..self.row_keys.len())
+            .flat_map(|r| (0..self.column_keys.len()).map(move |c| (r, c)))
+            .filter_map(|(r, c)| self.value(Some(r), Some(c)))
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| (min.min(v), max.max(v)))
     }
 }
 /////////////////////////
-#[derive(Debug)]
-pub struct ColumnPlotSeries {
-    name:String,
-    points:Vec<(f64, f64)>,
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutlierMethod {
+    Iqr,
+    ZScore,
+    ModifiedZScore
 }
 
-impl ColumnPlotSeries {
-    pub fn new(name:String, points:Vec<(f64, f64)>) -> Self {
-        Self {
-            name,
-            points,
+impl OutlierMethod {
+    pub fn label(&self) -> &str {
+        match self {
+            OutlierMethod::Iqr => {
+                "IQR fences"
+            }
+            OutlierMethod::ZScore => {
+                "z-score"
+            }
+            OutlierMethod::ModifiedZScore => {
+                "modified z-score (MAD)"
+            }
         }
     }
 
-    pub fn name(&self) -> &String {
-        &self.name
+    pub fn threshold(&self) -> f64 {
+        match self {
+            OutlierMethod::Iqr => {
+                1.5
+            }
+            OutlierMethod::ZScore => {
+                3.0
+            }
+            OutlierMethod::ModifiedZScore => {
+                3.5
+            }
+        }
     }
 
-    pub fn points(&self) -> &Vec<(f64, f64)> {
-        &self.points
+    pub fn all() -> [OutlierMethod; 3] {
+        [OutlierMethod::Iqr, OutlierMethod::ZScore, OutlierMethod::ModifiedZScore]
     }
 }
 
-#[derive(Debug)]
-pub struct ColumnsPlot {
-    id:u128,
-    frame_id: u128,
-    title: String,
-    series:Vec<ColumnPlotSeries>,
+#[derive(Debug, Clone)]
+pub struct ColumnOutliers {
+    column:String,
+    count:usize,
+    lower:f64,
+    upper:f64,
+    min:f64,
+    max:f64,
+    top_rows:Vec<(usize, f64)>
 }
 
-impl ColumnsPlot {
-    pub fn new(frame_id: u128, columns:Vec<String>, series:Vec<ColumnPlotSeries>) -> Self {
-        Self {
-            id: wa_id(),
-            frame_id,
-            title: format!("Data plot/{}", columns.join(", ")),
-            series,
-        }
+impl ColumnOutliers {
+    pub fn new(column: impl Into<String>, count: usize, lower: f64, upper: f64, min: f64, max: f64, top_rows: Vec<(usize, f64)>) -> Self {
+        Self { column:column.into(), count, lower, upper, min, max, top_rows }
     }
 
-    pub fn id(&self) -> &u128 {
-        &self.id
+    pub fn column(&self) -> &String {
+        &self.column
     }
 
-    pub fn frame_id(&self) -> &u128 {
-        &self.frame_id
+    pub fn count(&self) -> usize {
+        self.count
     }
 
-    pub fn _title(&self) -> &str {
-        &self.title
+    pub fn lower(&self) -> f64 {
+        self.lower
     }
 
-    pub fn series(&self) -> &Vec<ColumnPlotSeries> {
-        &self.series
+    pub fn upper(&self) -> f64 {
+        self.upper
+    }
+
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    pub fn top_rows(&self) -> &Vec<(usize, f64)> {
+        &self.top_rows
+    }
+
+    pub fn to_condition(&self, isolate:bool) -> ConditionType { //isolate: only values outside of fences
+        ConditionType::Single {
+            column_name: self.column.clone(),
+            condition: Condition::Fences { lower: self.lower, upper: self.upper, outside: isolate },
+        }
     }
 }
-/////////////////////////
+
 #[derive(Debug)]
-pub struct DataLump {
+pub struct Outliers {
     id:u128,
     frame_id: u128,
-    offset:usize,
     title: String,
-    data:Vec<Vec<Option<String>>>,
-    columns:Vec<(WapukuDataType, String)>
+    method:OutlierMethod,
+    columns:Vec<ColumnOutliers>
 }
 
-impl DataLump {
-
-    pub fn new(frame_id: u128, offset:usize, rows:usize, columns:usize) -> Self {
-        let column_vec = (0..columns).map(|_|None).collect::<Vec<Option<String>>>();
+impl Outliers {
+    pub fn new(frame_id: u128, method:OutlierMethod, columns:Vec<ColumnOutliers>) -> Self {
         Self {
             id: wa_id(),
             frame_id,
-            offset,
-            title: format!("data"),
-            data: (0..rows).map(|_|column_vec.clone()).collect(),
-            columns: vec![]
+            title: format!("Outliers/{}", method.label()),
+            method,
+            columns
         }
     }
 
-    pub fn add_column(&mut self,  dtype:WapukuDataType, name:impl Into<String>) {
-        self.columns.push((dtype, name.into()));
-    }
-
-    pub fn set_value(&mut self, row:usize, col:usize, val:String) {
-        self.data[row][col].replace(val);
-    }
-
-    pub fn data(&self) ->&Vec<Vec<Option<String>>> {
-        &self.data
-    }
-
-
-
     pub fn id(&self) -> &u128 {
         &self.id
     }
 
-
+    pub fn frame_id(&self) -> &u128 {
+        &self.frame_id
+    }
 
     pub fn _title(&self) -> &str {
         &self.title
     }
 
-    pub fn columns(&self) -> &Vec<(WapukuDataType, String)> {
-        &self.columns
+    pub fn method(&self) -> OutlierMethod {
+        self.method
     }
-    pub fn frame_id(&self) -> &u128 {
-        &self.frame_id
-    }
-    pub fn offset(&self) -> &usize {
-        &self.offset
+
+    pub fn columns(&self) -> &Vec<ColumnOutliers> {
+        &self.columns
     }
-    pub fn replace_data(&mut self, other:DataLump) {
-        self.offset = other.offset;
-        self.title = other.title;
-        self.data = other.data;
+
+    pub fn column(&self, name:&str) -> Option<&ColumnOutliers> {
+        self.columns.iter().find(|c|c.column.eq(name))
     }
 }
 /////////////////////////
@@ -856,12 +3318,27 @@ pub trait Data:Debug {
     fn all_properties(&self) -> HashSet<&dyn Property>;
     fn build_grid(&self, property_x: PropertyRange, property_y: PropertyRange, groups_nr_x: u8, groups_nr_y: u8, name: &str) -> GroupsGrid;
     fn build_summary(&self, frame_id: u128, column: Option<String>) -> Summary;
-    fn build_histogram(&self, frame_id: u128, column:String, bins: Option<usize>) -> Result<Histogram, WapukuError>;
+    fn build_histogram(&self, frame_id: u128, column:String, params: HistogramParams) -> Result<Histogram, WapukuError>;
     fn fetch_data(&self, frame_id: u128, offset: usize, limit: usize) -> Result<DataLump, WapukuError>;
     fn apply_filter(&self, frame_id: u128, filter:Filter) -> Result<FilteredFame, WapukuError>;
     fn convert_column(&mut self, frame_id: u128, column:String, pattern:String) -> Result<SummaryColumn, WapukuError>;
-    fn clc_corrs(&self, frame_id: u128, columns:Vec<String>) -> Result<Corrs, WapukuError>;
-    fn plot_columns(&self, frame_id: u128, columns:Vec<String>) -> Result<ColumnsPlot, WapukuError>;
+    fn clc_corrs(&self, frame_id: u128, columns:Vec<String>, method:CorrMethod) -> Result<Corrs, WapukuError>;
+    fn plot_columns(&self, frame_id: u128, columns:Vec<String>, max_points:usize) -> Result<ColumnsPlot, WapukuError>;
+    fn kde(&self, frame_id: u128, column:String, bandwidth:Bandwidth, split:Option<String>) -> Result<Kde, WapukuError>;
+    fn build_histogram_2d(&self, frame_id: u128, column_x:String, column_y:String, bins_x:usize, bins_y:usize) -> Result<Histogram2D, WapukuError>;
+
+    fn build_scatter(&self, frame_id: u128, params:ScatterParams) -> Result<Scatter, WapukuError>;
+
+    fn build_box_plot(&self, frame_id: u128, column:String, split_by:Option<String>) -> Result<BoxPlot, WapukuError>;
+
+    fn build_time_series(&self, frame_id: u128, params:TimeSeriesParams) -> Result<TimeSeries, WapukuError>;
+
+    fn build_pair_plot(&self, frame_id: u128, columns:Vec<String>) -> Result<PairPlot, WapukuError>;
+    fn check_quality(&self, frame_id: u128) -> Result<DataQuality, WapukuError>;
+    fn find_outliers(&self, frame_id: u128, method: OutlierMethod) -> Result<Outliers, WapukuError>;
+    fn value_counts(&self, frame_id: u128, column:String, sort: ValueCountsSort, offset: usize, limit: usize) -> Result<ValueCounts, WapukuError>;
+    fn pivot(&self, frame_id: u128, row_column:String, column_column:String, value_column:String, agg: PivotAgg) -> Result<Pivot, WapukuError>;
+    fn group_by(&self, frame_id: u128, keys:Vec<String>, aggs:Vec<(String, GroupByAgg)>) -> Result<FilteredFame, WapukuError>;
 }
 
 #[derive(Debug)]
@@ -1180,22 +3657,25 @@ impl Filter {
 
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ConditionType {
     Single{column_name:String, condition:Condition},
     Compoiste {conditions:Vec<ConditionType>, ctype:CompositeType},
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum CompositeType {
     AND,
     OR
 }
 
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum  Condition {
     Numeric{min:f32, max:f32},
     String{pattern:String},
-    Boolean{val:bool}
+    Boolean{val:bool},
+    Equals{value:Option<String>}, //None is null
+    Range{min:f64, max:f64, closed:bool, label:String}, //physical values, [min, max) or [min, max] if closed
+    Fences{lower:f64, upper:f64, outside:bool}, //outliers strictly below lower or above upper, or the rest if not outside
 }
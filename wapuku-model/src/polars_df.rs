@@ -1,16 +1,14 @@
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::io::{Cursor, Read};
 use std::iter::once;
 
 use ::zip::result::*;
 use ::zip::*;
-use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use chrono::{Datelike, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
 use itertools::Either;
 use log::{debug, warn};
-use polars::datatypes::TimeUnit::Milliseconds;
 use polars::prelude::StartBy::WindowBound;
 use polars::prelude::*;
-use polars::series::IsSorted;
 use polars_io::prelude::CsvReader;
 #[cfg(feature = "parquet")]
 use polars_io::prelude::ParquetReader;
@@ -19,6 +17,7 @@ use polars::time::Duration;
 
 use crate::data_type::WapukuDataType;
 use crate::model::*;
+use crate::stats::{chi2_p, correlation_ratio, cramers_v, f_p, fisher_ci, gaussian_kde, kendall_tau, min_max_downsample, normal_p, quantile_sorted, scott_bandwidth, silverman_bandwidth, stride_sample, student_t_p};
 use crate::utils::*;
 
 pub fn format_date_str<E: AsRef<[Expr]>>(format: &str, args: E) -> PolarsResult<Expr> {
@@ -50,18 +49,6 @@ pub fn format_date_str<E: AsRef<[Expr]>>(format: &str, args: E) -> PolarsResult<
     Ok(concat_str(exprs, "", true))
 }
 
-fn scalar_to_i64(value: &Scalar) -> Option<i64> {
-    match value.value() {
-        AnyValue::Date(v) => Some(i64::from(*v)),
-        AnyValue::Datetime(v, _, _) | AnyValue::DatetimeOwned(v, _, _) => Some(*v),
-        AnyValue::Duration(v, _) | AnyValue::Time(v) | AnyValue::Int64(v) => Some(*v),
-        AnyValue::Int32(v) => Some(i64::from(*v)),
-        AnyValue::UInt32(v) => Some(i64::from(*v)),
-        AnyValue::UInt64(v) => i64::try_from(*v).ok(),
-        _ => None,
-    }
-}
-
 fn any_value_to_u32(value: AnyValue<'_>) -> Option<u32> {
     match value {
         AnyValue::UInt8(v) => Some(u32::from(v)),
@@ -76,22 +63,6 @@ fn any_value_to_u32(value: AnyValue<'_>) -> Option<u32> {
     }
 }
 
-fn any_value_to_f32(value: AnyValue<'_>) -> Option<f32> {
-    match value {
-        AnyValue::Float32(v) => Some(v),
-        AnyValue::Float64(v) => Some(v as f32),
-        AnyValue::UInt8(v) => Some(f32::from(v)),
-        AnyValue::UInt16(v) => Some(f32::from(v)),
-        AnyValue::UInt32(v) => Some(v as f32),
-        AnyValue::UInt64(v) => Some(v as f32),
-        AnyValue::Int8(v) => Some(f32::from(v)),
-        AnyValue::Int16(v) => Some(f32::from(v)),
-        AnyValue::Int32(v) => Some(v as f32),
-        AnyValue::Int64(v) => Some(v as f32),
-        _ => None,
-    }
-}
-
 fn any_value_to_f64(value: AnyValue<'_>) -> Option<f64> {
     match value {
         AnyValue::Boolean(v) => Some(if v { 1.0 } else { 0.0 }),
@@ -166,6 +137,40 @@ impl From<&PolarsError> for WapukuError {
 }
 
 const NA: &str = "n/a";
+const NULL_KEY: &str = "\u{0}null"; //group key of nulls, apart from any real value
+
+const NULL_SHARE_THRESHOLD: f32 = 0.5;
+const MIXED_TYPES_THRESHOLD: f32 = 0.8; //share of numeric looking strings to call the rest stray tokens
+const NUMERIC_STR_PATTERN: &str = r"^\s*[-+]?(\d+\.?\d*|\.\d+)([eE][-+]?\d+)?\s*$";
+const SENTINEL_STR_PATTERN: &str = r"(?i)^\s*(-9{3,}|9{4,}|9999-12-31.*|1900-01-01.*|n/?a|null|none|nan|\?|-)\s*$";
+const SENTINEL_NUMBERS: [f64; 5] = [-999.0, -9999.0, 9999.0, 99999.0, 999999.0];
+
+const OUTLIERS_TOP_ROWS: usize = 10;
+const PIVOT_MAX_KEYS: usize = 200;
+const HISTOGRAM_MAX_SERIES: usize = 10;
+const KDE_MAX_SAMPLE: usize = 10_000;
+const KDE_POINTS: usize = 200;
+const BOX_MAX_OUTLIERS: usize = 1_000;
+const OTHER: &str = "Other";
+const ROWS: &str = "rows";
+const PARENT: &str = "parent";
+const FILTERED: &str = "filtered";
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const HISTOGRAM_MAX_BINS: usize = 1000;
+const HISTOGRAM_2D_MAX_BINS: usize = 100;
+const SCATTER_MAX_POINTS: usize = 5_000;
+const PAIR_PLOT_MAX_POINTS: usize = 2_000; //per cell, a grid of n*n cells is drawn
+const PAIR_PLOT_BINS: usize = 20;
+const PLOT_MIN_POINTS: usize = 100; //floor for the width based target of plot_columns
+const UNIX_EPOCH_DAYS_FROM_CE: i32 = 719_163;
+const MAD_SCALE: f64 = 0.6745;
+const DAY_MS: f64 = 86_400_000.0;
+const DURATION_STEPS_MS: [f64; 25] = [
+    1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0,
+    1_000.0, 2_000.0, 5_000.0, 10_000.0, 15_000.0, 30_000.0,
+    60_000.0, 120_000.0, 300_000.0, 600_000.0, 900_000.0, 1_800_000.0,
+    3_600_000.0, 7_200_000.0, 21_600_000.0, 43_200_000.0,
+];
 
 pub fn demo_df() -> DataFrame {
     df!(
@@ -216,370 +221,981 @@ impl PolarsData {
         }
     }
 
-    fn group_by_categoric(&self, frame_id: u128, column: String) -> Result<Histogram, WapukuError> {
-        debug!("group_by_categoric column={:?}", column);
+    fn group_by_categoric(&self, frame_id: u128, column: String, params: HistogramParams) -> Result<Histogram, WapukuError> {
+        debug!("group_by_categoric column={:?} top_n={}", column, params.top_n());
+
+        let value_str = "wapuku_value";
+        let count_str = "wapuku_count";
+
+        let null_count = self.df.column(column.as_str())?.null_count();
 
         let groupby_df = self
             .df
             .clone()
             .lazy()
-            .group_by([col(column.as_str())])
-            .agg([len().alias("count")])
-            .sort(["count"], SortMultipleOptions::default())
+            .select([col(column.as_str()).cast(DataType::String).alias(value_str)])
+            .filter(col(value_str).is_not_null())
+            .group_by([col(value_str)])
+            .agg([len().alias(count_str)])
+            .sort_by_exprs(
+                [col(count_str), col(value_str)],
+                SortMultipleOptions::default().with_order_descending_multi([true, false]),
+            )
             .collect()?;
 
         debug!("groupby_df={:?}", groupby_df);
 
-        if groupby_df.shape().0 > 1000 {
-            return Err(WapukuError::DataLoad {
-                msg: "Tooo big".into(),
-            }); //TODO
+        let mut values = std::iter::zip(
+            groupby_df.column(value_str)?.as_materialized_series().iter(),
+            groupby_df.column(count_str)?.as_materialized_series().iter(),
+        )
+        .fold(Vec::new(), |mut vec, vv| {
+            let (ref v1, ref v2) = vv;
+            if let Some(v2) = any_value_to_u32(v2.clone()) {
+                vec.push((v1.str_value().into_owned(), v2));
+            } else {
+                warn!("unexpected values in build_histogram: {:?}", vv);
+            }
+            vec
+        });
+
+        let other_label = if values.len() > params.top_n() {
+            let other = values.split_off(params.top_n());
+            let other_label = format!("Other ({} values)", other.len());
+            values.push((other_label.clone(), other.iter().map(|(_, count)| count).sum()));
+            Some(other_label)
+        } else {
+            None
+        };
+
+        //nulls keep their own key, not the one of Other
+        let value_col = col(column.as_str()).cast(DataType::String);
+        let key = values.iter().take(params.top_n()).rev().fold(
+            when(value_col.clone().is_null())
+                .then(lit(NULL).cast(DataType::String))
+                .otherwise(other_label.clone().map(lit).unwrap_or(lit(NULL).cast(DataType::String))),
+            |e, (value, _)| when(value_col.clone().eq(lit(value.clone()))).then(lit(value.clone())).otherwise(e),
+        );
+
+        let with_nulls = params.include_nulls() && null_count > 0;
+        if with_nulls {
+            values.push((String::from(NA), null_count as u32));
         }
 
-        let mut val_count = groupby_df.materialized_column_iter();
+        let keys = values.iter().enumerate()
+            .map(|(i, (value, _))| if with_nulls && i == values.len() - 1 { None } else { Some(value.clone()) })
+            .collect::<Vec<_>>();
 
-        Ok(Histogram::new(
-            frame_id,
-            column,
-            std::iter::zip(
-                val_count.next().expect("val").iter(),
-                val_count.next().expect("count").iter(),
-            )
-            .fold(Vec::new(), |mut vec, vv| {
-                let (ref v1, ref v2) = vv;
-                if let Ok(v2) = v2.try_extract::<u32>() {
-                    vec.push((v1.str_value().into_owned(), v2));
-                } else {
-                    warn!("unexpected values in build_histogram: {:?}", vv);
-                }
-                vec
-            }),
-        ))
+        let conditions = keys.iter()
+            .map(|key| match key {
+                Some(value) if other_label.as_ref() == Some(value) => None,
+                value => Some(Condition::Equals { value: value.clone() }),
+            })
+            .collect();
+
+        let mut histogram = Histogram::new(frame_id, column, params, values);
+        histogram.set_conditions(conditions);
+
+        self.with_split_series(histogram, lit(true), key, keys)
     }
 
-    fn group_by_datetime(&self, frame_id: u128, column: String) -> Result<Histogram, WapukuError> {
-        debug!("group_by_datetime column={:?}", column);
+    fn group_by_bins(&self, frame_id: u128, column: String, params: HistogramParams) -> Result<Histogram, WapukuError> {
+        debug!("group_by_bins column={:?} params={:?}", column, params);
 
-        let df = self
-            .df
-            .clone()
-            .lazy()
-            .filter(col(column.as_str()).is_not_null())
-            .collect()?;
-        let column_series = df
-            .column(column.as_str())?
-            .sort(SortOptions::default())?;
+        if params.binning().is_calendar() {
+            return Err(WapukuError::DataLoad {
+                msg: format!("{} binning needs a date or datetime column", params.binning().label()),
+            });
+        }
 
-        // let mut min = NaiveDateTime::MIN.timestamp_millis();
-        // let mut max = NaiveDateTime::MAX.timestamp_millis();
+        let value_str = "wapuku_value";
+        let bin_str = "wapuku_bin";
+        let count_str = "wapuku_count";
 
-        debug!("s.dtype()={:?}", column_series.dtype());
+        let column_series = self.df.column(column.as_str())?;
+        let dtype = column_series.dtype().clone();
+        let null_count = column_series.null_count();
 
-        let (min_value, max_value) = (
-            scalar_to_i64(&column_series.min_reduce()?).unwrap_or(0),
-            scalar_to_i64(&column_series.max_reduce()?).unwrap_or(0),
-        );
+        let physical = if dtype.is_temporal() {
+            col(column.as_str()).to_physical().cast(DataType::Float64)
+        } else {
+            col(column.as_str()).cast(DataType::Float64)
+        };
 
-        let mut time_unit = TimeUnit::Milliseconds;
-
-        match column_series.dtype() {
-            DataType::Date => {}
-            DataType::Datetime(unit, _) => {
-                match unit {
-                    TimeUnit::Nanoseconds => {
-                        time_unit = TimeUnit::Nanoseconds;
-                        // min_value /= 1_000_000;
-                        // max_value /= 1_000_000;
-                        debug!(
-                            "nanos min_value={:?} max_value={:?} min_dt={:?}, max_dt={:?}",
-                            min_value,
-                            max_value,
-                            chrono::NaiveDateTime::from_timestamp_millis(min_value / 1_000_000),
-                            chrono::NaiveDateTime::from_timestamp_millis(max_value / 1_000_000)
-                        );
-                    }
-                    TimeUnit::Microseconds => {
-                        time_unit = TimeUnit::Microseconds;
-                        // min_value /= 1_000;
-                        // max_value /= 1_000;
-                        debug!(
-                            "micros min_value={:?} max_value={:?} min_dt={:?}, max_dt={:?}",
-                            min_value,
-                            max_value,
-                            chrono::NaiveDateTime::from_timestamp_micros(min_value),
-                            chrono::NaiveDateTime::from_timestamp_millis(max_value)
-                        );
+        let mut values_lf = self.df.clone()
+            .lazy()
+            .select([physical.clone().alias(value_str)])
+            .filter(col(value_str).is_not_null().and(col(value_str).is_not_nan()));
+
+        if let Some((min, max)) = params.range() {
+            values_lf = values_lf.filter(col(value_str).gt_eq(lit(min)).and(col(value_str).lt_eq(lit(max))));
+        }
+
+        let stats_df = values_lf.clone().select([
+            col(value_str).min().alias("min"),
+            col(value_str).max().alias("max"),
+            col(value_str).count().alias("n"),
+            col(value_str).std(1).alias("std"),
+            col(value_str).mean().alias("mean"),
+            col(value_str).median().alias("median"),
+            col(value_str).quantile(lit(0.25), QuantileMethod::Linear).alias("q1"),
+            col(value_str).quantile(lit(0.75), QuantileMethod::Linear).alias("q3"),
+        ]).collect()?;
+
+        let stat = |name: &str| stats_df.column(name).ok().and_then(|c| c.get(0).ok()).and_then(any_value_to_f64);
+
+        let mut values = vec![];
+        let mut keys = vec![];
+        let mut key = lit(NULL).cast(DataType::Int64);
+        let mut bin_edges = vec![];
+        let mut conditions = vec![];
+
+        if let (Some(min), Some(max), Some(n)) = (stat("min"), stat("max"), stat("n")) {
+            let width_bins = |width: f64| {
+                if width > 0.0 && width.is_finite() {
+                    ((max - min) / width).ceil().max(1.0) as usize
+                } else {
+                    1
+                }
+            };
+
+            let edges = match params.binning() {
+                Binning::Count { bins } => uniform_edges(min, max, *bins),
+                Binning::Width { width } => {
+                    let bins = width_bins(*width);
+                    if bins > HISTOGRAM_MAX_BINS {
+                        return Err(WapukuError::DataLoad {
+                            msg: format!("width {} gives {} bins for {}, at most {} are allowed", width, bins, column, HISTOGRAM_MAX_BINS),
+                        });
                     }
-                    Milliseconds => {
-                        debug!(
-                            "millis: min_value={:?} max_value={:?} min_dt={:?}, max_dt={:?}",
-                            min_value,
-                            max_value,
-                            chrono::NaiveDateTime::from_timestamp_millis(min_value),
-                            chrono::NaiveDateTime::from_timestamp_millis(max_value)
-                        );
+                    (0..=bins).map(|i| min + i as f64 * width).collect()
+                }
+                Binning::Sturges => uniform_edges(min, max, (n.log2().ceil() as usize) + 1),
+                Binning::Scott => uniform_edges(min, max, width_bins(3.49 * stat("std").unwrap_or(0.0) * n.powf(-1.0 / 3.0))),
+                Binning::FreedmanDiaconis => uniform_edges(
+                    min,
+                    max,
+                    width_bins(2.0 * (stat("q3").unwrap_or(0.0) - stat("q1").unwrap_or(0.0)) * n.powf(-1.0 / 3.0)),
+                ),
+                Binning::Quantile { bins } => {
+                    let bins = (*bins).clamp(1, HISTOGRAM_MAX_BINS);
+                    let quantiles_df = values_lf.clone().select(
+                        (0..=bins)
+                            .map(|i| col(value_str).quantile(lit(i as f64 / bins as f64), QuantileMethod::Linear).alias(format!("q{}", i).as_str()))
+                            .collect::<Vec<_>>()
+                    ).collect()?;
+
+                    let mut edges = quantiles_df.get_columns().iter()
+                        .filter_map(|c| c.get(0).ok().and_then(any_value_to_f64))
+                        .collect::<Vec<_>>();
+                    edges.dedup();
+                    edges
+                }
+                Binning::Edges { edges } => {
+                    let mut edges = edges.iter().copied().filter(|e| e.is_finite()).collect::<Vec<_>>();
+                    edges.sort_by(|a, b| a.total_cmp(b));
+                    edges.dedup();
+                    edges
+                }
+                Binning::Calendar { .. } | Binning::DayOfWeek | Binning::HourOfDay => vec![],
+            };
+
+            //durations get whole seconds, minutes, hours or days per bin
+            let edges = match &dtype {
+                DataType::Duration(time_unit) if edges.len() >= 2 && matches!(params.binning(), Binning::Count { .. } | Binning::Sturges | Binning::Scott | Binning::FreedmanDiaconis) => {
+                    duration_edges(min, max, edges.len() - 1, time_unit)
+                }
+                _ => edges,
+            };
+
+            debug!("group_by_bins: edges={:?}", edges);
+
+            if edges.len() >= 2 {
+                let bins = edges.len() - 1;
+                let (first, last) = (edges[0], edges[bins]);
+
+                let bin_of = |value: Expr| if matches!(params.binning(), Binning::Quantile { .. } | Binning::Edges { .. }) {
+                    edges[1..bins].iter().fold(lit(0i64), |e, edge| e + value.clone().gt_eq(lit(*edge)).cast(DataType::Int64))
+                } else {
+                    uniform_bin(value, &edges)
+                };
+
+                key = when(physical.clone().is_null())
+                    .then(lit(NULL).cast(DataType::Int64))
+                    .otherwise(
+                        when(physical.clone().gt_eq(lit(first)).and(physical.clone().lt_eq(lit(last))))
+                            .then(bin_of(physical.clone()))
+                            .otherwise(lit(-1i64))
+                    );
+                keys = (0..bins).map(|i| Some(i.to_string())).collect();
+                bin_edges = edges.clone();
+
+                let counts_df = values_lf
+                    .filter(col(value_str).gt_eq(lit(first)).and(col(value_str).lt_eq(lit(last))))
+                    .group_by([bin_of(col(value_str)).alias(bin_str)])
+                    .agg([len().alias(count_str)])
+                    .collect()?;
+
+                let mut counts = vec![0u32; bins];
+                for (bin, count) in std::iter::zip(
+                    counts_df.column(bin_str)?.as_materialized_series().iter(),
+                    counts_df.column(count_str)?.as_materialized_series().iter(),
+                ) {
+                    if let (Some(bin), Some(count)) = (any_value_to_f64(bin), any_value_to_u32(count)) {
+                        if let Some(c) = counts.get_mut(bin as usize) {
+                            *c += count;
+                        }
                     }
                 }
-            }
-            DataType::Duration(_) => {}
-            DataType::Time => {}
-            _ => {
-                warn!("Unexpected datatype"); //TODO
+
+                let labels = bin_labels(&edges, &dtype);
+
+                conditions = labels.iter().enumerate()
+                    .map(|(i, label)| Some(Condition::Range { min: edges[i], max: edges[i + 1], closed: i == bins - 1, label: label.clone() }))
+                    .collect();
+                values = std::iter::zip(labels, counts).collect();
+            } else if let Some(edge) = edges.first() {
+                values.push((format_physical(*edge, &dtype), n as u32));
+                conditions.push(Some(Condition::Range { min: *edge, max: *edge, closed: true, label: format_physical(*edge, &dtype) }));
+                key = when(physical.clone().is_null()).then(lit(NULL).cast(DataType::Int64)).otherwise(lit(0i64));
+                keys = vec![Some(String::from("0"))];
             }
         }
 
-        // let start = s.min::<f64>().unwrap().floor() - 1.0;
-        // let stop = s.max::<f64>().unwrap().ceil() + 1.0;
+        if params.include_nulls() && null_count > 0 {
+            values.push((String::from(NA), null_count as u32));
+            keys.push(None);
+            conditions.push(Some(Condition::Equals { value: None }));
+        }
 
-        let bin_count = 10;
+        let markers = [("mean", stat("mean")), ("median", stat("median"))]
+            .into_iter()
+            .filter_map(|(name, value)| value.map(|v| (format!("{} {}", name, format_physical(v, &dtype)), v)))
+            .collect();
 
-        let interval = (max_value - min_value) / bin_count;
-        let breaks: Vec<i64> = (0..(bin_count)).map(|b| min_value + b * interval).collect();
+        let filter = range_filter(physical, params.range());
 
-        let breakpoint_str = "break_point";
-        let bins = Series::new(breakpoint_str.into(), breaks);
+        let mut histogram = Histogram::new(frame_id, column, params, values);
+        histogram.set_edges(bin_edges);
+        histogram.set_temporal(dtype.is_temporal());
+        histogram.set_markers(markers);
+        histogram.set_conditions(conditions);
 
-        let mut bins = bins.extend_constant(AnyValue::Datetime(max_value, time_unit, None), 1)?;
-        bins.set_sorted_flag(IsSorted::Ascending);
+        self.with_split_series(histogram, filter, key, keys)
+    }
 
-        let cuts_df = df![
-            breakpoint_str => bins
-        ]?;
+    fn group_by_boolean(&self, frame_id: u128, column: String, params: HistogramParams) -> Result<Histogram, WapukuError> {
+        debug!("group_by_boolean column={:?}", column);
 
-        let category_str = "category";
+        let column_series = self.df.column(column.as_str())?;
+        let null_count = column_series.null_count();
 
-        let cuts_df = cuts_df
-            .lazy()
-            .with_column(
-                format_date_str(
-                    "({}, {}]",
-                    [
-                        col(breakpoint_str)
-                            .shift_and_fill(1, min_value)
-                            .cast(column_series.dtype().clone()),
-                        col(breakpoint_str).cast(column_series.dtype().to_owned()),
-                    ],
-                )?
-                .alias(category_str),
-            )
-            .collect()?;
+        let counts = group_counts(column_series.clone().into_frame().lazy(), col(column.as_str()))?;
+
+        let key = col(column.as_str()).cast(DataType::Int64);
+
+        let mut values = vec![
+            (String::from("true"), counts.get(&1).copied().unwrap_or(0)),
+            (String::from("false"), counts.get(&0).copied().unwrap_or(0)),
+        ];
+        let mut keys = vec![Some(String::from("1")), Some(String::from("0"))];
+        let mut conditions = vec![Some(Condition::Boolean { val: true }), Some(Condition::Boolean { val: false })];
+
+        if params.include_nulls() && null_count > 0 {
+            values.push((String::from(NA), null_count as u32));
+            keys.push(None);
+            conditions.push(Some(Condition::Equals { value: None }));
+        }
+
+        let mut histogram = Histogram::new(frame_id, column, params, values);
+        histogram.set_conditions(conditions);
+
+        self.with_split_series(histogram, lit(true), key, keys)
+    }
+
+    fn group_by_calendar(&self, frame_id: u128, column: String, params: HistogramParams) -> Result<Histogram, WapukuError> {
+        debug!("group_by_calendar column={:?} params={:?}", column, params);
+
+        let ts_str = "wapuku_ts";
+        let count_str = "wapuku_count";
+
+        let column_series = self.df.column(column.as_str())?;
+        let null_count = column_series.null_count();
 
-        debug!("cuts_df={:?}", cuts_df);
+        let dtype = column_series.dtype().clone();
+        let is_time = matches!(dtype, DataType::Time);
+
+        if !(matches!(column_series.dtype(), DataType::Date | DataType::Datetime(_, _)) || is_time && matches!(params.binning(), Binning::HourOfDay)) {
+            return Err(WapukuError::DataLoad {
+                msg: format!("{} binning needs a date or datetime column, {} is {}", params.binning().label(), column, column_series.dtype()),
+            });
+        }
 
-        let cuts = cuts_df
+        let mut values_lf = self.df.clone()
             .lazy()
-            .with_column(
-                col(breakpoint_str)
-                    .cast(column_series.dtype().clone())
-                    .set_sorted_flag(IsSorted::Ascending),
-            )
-            .collect()?;
+            .select([col(column.as_str()).alias(ts_str)])
+            .filter(col(ts_str).is_not_null());
 
-        let out = column_series.clone().into_frame()._join_asof(
-            &cuts,
-            column_series.as_materialized_series(),
-            cuts.column(breakpoint_str)?.as_materialized_series(),
-            AsofStrategy::Forward,
-            None,
-            None,
-            None,
-            true,
-            true,
-            true,
-        )?;
+        if let Some((min, max)) = params.range() {
+            let physical = col(ts_str).to_physical().cast(DataType::Float64);
+            values_lf = values_lf.filter(physical.clone().gt_eq(lit(min)).and(physical.lt_eq(lit(max))));
+        }
+
+        let ts = if is_time {
+            col(column.as_str())
+        } else {
+            values_lf = values_lf.select([col(ts_str).cast(DataType::Datetime(TimeUnit::Milliseconds, None))]);
+            col(column.as_str()).cast(DataType::Datetime(TimeUnit::Milliseconds, None))
+        };
+
+        let mut keys = vec![];
+        let mut key = lit(NULL).cast(DataType::Int64);
+        let mut conditions = vec![];
+
+        let mut values = match params.binning() {
+            Binning::DayOfWeek => {
+                let counts = group_counts(values_lf, col(ts_str).dt().weekday())?;
+                key = ts.dt().weekday();
+                keys = (1..=7).map(|day| Some(day.to_string())).collect();
+                WEEKDAYS.iter().enumerate()
+                    .map(|(i, day)| (day.to_string(), counts.get(&(i as i64 + 1)).copied().unwrap_or(0)))
+                    .collect()
+            }
+            Binning::HourOfDay => {
+                let counts = group_counts(values_lf, col(ts_str).dt().hour())?;
+                key = ts.dt().hour();
+                keys = (0..24).map(|hour| Some(hour.to_string())).collect();
+                (0..24i64)
+                    .map(|hour| (format!("{:02}:00", hour), counts.get(&hour).copied().unwrap_or(0)))
+                    .collect()
+            }
+            Binning::Calendar { unit } => {
+                let unit = match unit {
+                    Some(unit) => *unit,
+                    None => auto_calendar_unit(values_lf.clone(), ts_str)?,
+                };
+
+                let every = Duration::parse(unit.every());
+
+                let counts_df = values_lf
+                    .sort([ts_str], SortMultipleOptions::default())
+                    .group_by_dynamic(
+                        col(ts_str),
+                        [],
+                        DynamicGroupOptions {
+                            index_column: ts_str.into(),
+                            every,
+                            period: every,
+                            offset: Duration::new(0),
+                            include_boundaries: false,
+                            closed_window: ClosedWindow::Left,
+                            start_by: WindowBound,
+                            label: Label::Left,
+                        },
+                    )
+                    .agg([len().alias(count_str)])
+                    .select([col(ts_str).cast(DataType::Int64), col(count_str)])
+                    .collect()?;
+
+                debug!("group_by_calendar: counts_df shape={:?}", counts_df.shape());
+
+                let counts = std::iter::zip(
+                    counts_df.column(ts_str)?.as_materialized_series().iter(),
+                    counts_df.column(count_str)?.as_materialized_series().iter(),
+                )
+                .filter_map(|(ts, count)| any_value_to_f64(ts).map(|ts| ts as i64).zip(any_value_to_u32(count)))
+                .collect::<BTreeMap<_, _>>();
+
+                let mut values = vec![];
+
+                let first = counts.keys().next().and_then(|ts| chrono::DateTime::from_timestamp_millis(*ts));
+                let last = counts.keys().last().and_then(|ts| chrono::DateTime::from_timestamp_millis(*ts));
+
+                if let (Some(first), Some(last)) = (first, last) { //empty periods are not in group_by_dynamic output
+                    let mut period = Some(first.naive_utc());
+                    while let Some(p) = period.filter(|p| *p <= last.naive_utc() && values.len() < HISTOGRAM_MAX_BINS) {
+                        let ms = p.and_utc().timestamp_millis();
+                        let label = format_period(&p, unit);
+                        period = next_period(&p, unit);
+
+                        conditions.push(period.map(|next| Condition::Range {
+                            min: ms_to_physical(ms, &dtype),
+                            max: ms_to_physical(next.and_utc().timestamp_millis(), &dtype),
+                            closed: false,
+                            label: label.clone(),
+                        }));
+                        values.push((label, counts.get(&ms).copied().unwrap_or(0)));
+                        keys.push(Some(ms.to_string()));
+                    }
+                }
 
-        let out = out
-            .select(["category", column_series.name()])?
-            .group_by(["category"])?
-            .count()?;
+                key = ts.dt().truncate(lit(unit.every())).cast(DataType::Int64);
 
-        debug!("out={:?}", out);
+                values
+            }
+            _ => vec![],
+        };
 
-        let groupby_df = cuts
-            .left_join(&out, [category_str], [category_str])?
-            .fill_null(FillNullStrategy::Zero)?
-            .sort(["category"], SortMultipleOptions::default())?;
+        conditions.resize(values.len(), None); //weekdays and hours have no matching condition
 
-        debug!("groupby_df={:?}", groupby_df);
+        if params.include_nulls() && null_count > 0 {
+            values.push((String::from(NA), null_count as u32));
+            keys.push(None);
+            conditions.push(Some(Condition::Equals { value: None }));
+        }
 
-        let mut val_count = groupby_df.materialized_column_iter();
-        val_count.next(); //skip break_point
+        let filter = range_filter(col(column.as_str()).to_physical().cast(DataType::Float64), params.range());
 
-        Ok(Histogram::new(
-            frame_id,
-            column,
-            std::iter::zip(
-                val_count.next().expect("cat").iter(),
-                val_count.next().expect("property_2_count").iter(),
-            )
-            .fold(Vec::new(), |mut vec, vv| {
-                let (ref cat, ref count) = vv;
-                if let Ok(count) = count.try_extract::<u32>() {
-                    vec.push((FloatReformatter::exec(cat.str_value().as_ref()).to_string(), count));
-                } else {
-                    warn!("unexpected values in build_histogram: {:?}", vv);
+        let mut histogram = Histogram::new(frame_id, column, params, values);
+        histogram.set_conditions(conditions);
+
+        self.with_split_series(histogram, filter, key, keys)
+    }
+
+    fn with_split_series(&self, mut histogram: Histogram, filter: Expr, key: Expr, keys: Vec<Option<String>>) -> Result<Histogram, WapukuError> {
+        if let Some(compare) = histogram.params().compare() {
+            let filtered_expr = when(conditions_to_expr(compare)).then(lit(FILTERED)).otherwise(lit(NULL).cast(DataType::String));
+            let mut series = self.split_counts(filter, key, &keys, filtered_expr, vec![String::from(FILTERED)])?;
+
+            series.insert(0, (String::from(PARENT), histogram.values().iter().map(|(_, count)| *count).collect()));
+
+            debug!("with_split_series: compare={:?} series={:?}", compare, series);
+
+            histogram.set_series(series);
+        } else if let Some(split) = histogram.params().split() {
+            let (split_expr, categories) = self.split_expr(split)?;
+
+            let mut series = self.split_counts(filter, key, &keys, split_expr, categories)?;
+            series.retain(|(_, counts)| counts.iter().any(|c| *c > 0));
+
+            debug!("with_split_series: split={} series={:?}", split, series);
+
+            histogram.set_series(series);
+        }
+
+        Ok(histogram)
+    }
+
+    //counts per key for each category of split_expr, aligned with keys
+    fn split_counts(&self, filter: Expr, key: Expr, keys: &Vec<Option<String>>, split_expr: Expr, categories: Vec<String>) -> Result<Vec<(String, Vec<u32>)>, WapukuError> {
+        let key_str = "wapuku_key";
+        let split_str = "wapuku_split";
+        let count_str = "wapuku_count";
+
+        let counts_df = self.df.clone()
+            .lazy()
+            .filter(filter)
+            .select([
+                key.cast(DataType::String).alias(key_str),
+                split_expr.alias(split_str),
+            ])
+            .group_by([col(key_str), col(split_str)])
+            .agg([len().alias(count_str)])
+            .collect()?;
+
+        let mut series = categories.into_iter()
+            .map(|name| (name, vec![0u32; keys.len()]))
+            .collect::<Vec<_>>();
+
+        for ((key, split), count) in counts_df.column(key_str)?.as_materialized_series().iter()
+            .zip(counts_df.column(split_str)?.as_materialized_series().iter())
+            .zip(counts_df.column(count_str)?.as_materialized_series().iter())
+        {
+            let key = match key {
+                AnyValue::Null => None,
+                key => Some(key.str_value().into_owned()),
+            };
+
+            if let (Some(i), Some(count)) = (keys.iter().position(|k| *k == key), any_value_to_u32(count)) {
+                if let Some((_, counts)) = series.iter_mut().find(|(name, _)| *name == split.str_value()) {
+                    counts[i] += count;
                 }
-                vec
-            }),
-        ))
+            }
+        }
+
+        Ok(series)
     }
 
-    fn group_by_numeric(
-        &self,
-        frame_id: u128,
-        column: String,
-        bins: Option<usize>,
-    ) -> Result<Histogram, WapukuError> {
-        debug!("group_by_numeric column={:?}", column);
+    //top HISTOGRAM_MAX_SERIES categories of the split column as String, the rest mapped to Other and nulls to n/a
+    fn split_expr(&self, split: &str) -> Result<(Expr, Vec<String>), WapukuError> {
+        let split_str = "wapuku_split";
+        let count_str = "wapuku_count";
 
-        let frame = self
-            .df
-            .clone()
+        let split_col = col(split).cast(DataType::String);
+
+        let categories_df = self.df.clone()
             .lazy()
-            .select([col(column.as_str()).hist(None, bins.or(Some(10)), true, false)]) //TODO include_breakpoint false
+            .select([split_col.clone().alias(split_str)])
+            .filter(col(split_str).is_not_null())
+            .group_by([col(split_str)])
+            .agg([len().alias(count_str)])
+            .sort_by_exprs(
+                [col(count_str), col(split_str)],
+                SortMultipleOptions::default().with_order_descending_multi([true, false]),
+            )
             .collect()?;
-        let groupby_df = frame.column(column.as_str())?;
 
-        // ┌─────────────┬───────────────────────────────────┬──────────────────┐
-        // │ break_point ┆ category                          ┆ property_2_count │
-        // │ ---         ┆ ---                               ┆ ---              │
-        // │ f64         ┆ cat                               ┆ u32              │
-        // ╞═════════════╪═══════════════════════════════════╪══════════════════╡
+        let categories = categories_df.column(split_str)?.as_materialized_series().iter()
+            .take(HISTOGRAM_MAX_SERIES)
+            .map(|v| v.str_value().into_owned())
+            .collect::<Vec<_>>();
 
-        let struct_fields = groupby_df.struct_()?.fields_as_series();
-        let categories = struct_fields
-            .iter()
-            .find(|field| {
-                let name = field.name().as_str();
-                name == "category" || (!name.ends_with("_count") && name != "count" && name != "break_point")
-            })
-            .ok_or_else(|| WapukuError::DataFrame {
-                msg: format!(
-                    "unexpected histogram category fields: {:?}",
-                    struct_fields
-                        .iter()
-                        .map(|field| field.name().to_string())
-                        .collect::<Vec<_>>()
-                ),
-            })?;
-        let counts = struct_fields
-            .iter()
-            .find(|field| {
-                let name = field.name().as_str();
-                name == "count" || name.ends_with("_count")
-            })
-            .or_else(|| struct_fields.last())
-            .ok_or_else(|| WapukuError::DataFrame {
-                msg: format!(
-                    "unexpected histogram count fields: {:?}",
-                    struct_fields
-                        .iter()
-                        .map(|field| field.name().to_string())
-                        .collect::<Vec<_>>()
-                ),
-            })?;
+        let split_expr = categories.iter().rev().fold(lit(OTHER), |e, category| {
+            when(split_col.clone().eq(lit(category.clone()))).then(lit(category.clone())).otherwise(e)
+        });
 
-        Ok(Histogram::new(
-            frame_id,
-            column,
-            std::iter::zip(categories.iter(), counts.iter()).fold(Vec::new(), |mut vec, (category, count)| {
-                vec.push((
-                    FloatReformatter::exec(category.str_value().as_ref()).to_string(),
-                    any_value_to_u32(count).unwrap_or(0),
-                ));
-                vec
-            }),
+        Ok((
+            when(split_col.is_null()).then(lit(NA)).otherwise(split_expr),
+            categories.into_iter().chain([String::from(OTHER), String::from(NA)]).collect(),
         ))
     }
-}
 
-impl Data for PolarsData {
-    fn load(data: Box<Vec<u8>>, name: Box<String>) -> Result<Vec<Self>, WapukuError> {
-        if name.ends_with("csv") {
-            load_csv(data).map(|d| vec![PolarsData::new(d, *name)])
-        } else if name.ends_with("parquet") {
-            load_parquet(data).map(|d| vec![PolarsData::new(d, *name)])
-        } else if name.ends_with("zip") {
-            load_zip(data).map(|d_vec| {
-                d_vec
-                    .into_iter()
-                    .map(|(df, entry_name)| PolarsData::new(df, format!("{}/{}", name, entry_name)))
-                    .collect()
-            })
-        } else {
-            Err(WapukuError::General {
-                msg: String::from("I can load only csv or parquet files"),
-            })
-        }
-    }
+    //non-null, non-NaN values of the column as f64 per split category, or all under the column name, every k-th row past max_rows
+    fn split_values(&self, column: &str, split: Option<&String>, max_rows: Option<usize>) -> Result<Vec<(String, Vec<f64>)>, WapukuError> {
+        let value_str = "wapuku_value";
+        let split_str = "wapuku_split";
 
-    fn name(&self) -> String {
-        self.name.clone()
-    }
+        let (split_expr, names) = match split {
+            Some(split) => self.split_expr(split)?,
+            None => (lit(column), vec![String::from(column)]),
+        };
 
-    fn all_sets(&self) -> Vec<&dyn PropertiesSet> {
-        self.property_sets.iter().fold(vec![], |mut props, p| {
-            props.push(p);
+        let values_lf = self.df.clone()
+            .lazy()
+            .select([col(column).cast(DataType::Float64).alias(value_str), split_expr.alias(split_str)])
+            .filter(col(value_str).is_not_null().and(col(value_str).is_not_nan()));
 
-            props
-        })
+        let values_lf = match max_rows {
+            Some(max_rows) => stride_rows(values_lf, max_rows)?.0,
+            None => values_lf,
+        };
+
+        let values_df = values_lf.collect()?;
+
+        let mut groups = names.into_iter().map(|name| (name, vec![])).collect::<Vec<(String, Vec<f64>)>>();
+
+        for (value, name) in std::iter::zip(
+            values_df.column(value_str)?.as_materialized_series().iter(),
+            values_df.column(split_str)?.as_materialized_series().iter(),
+        ) {
+            if let (Some(value), Some((_, values))) = (any_value_to_f64(value), groups.iter_mut().find(|(n, _)| *n == name.str_value())) {
+                values.push(value);
+            }
+        }
+
+        groups.retain(|(_, values)| !values.is_empty());
+
+        Ok(groups)
     }
 
-    fn all_properties(&self) -> HashSet<&dyn Property> {
-        self.property_sets
-            .iter()
-            .flat_map(|property_set| property_set.properties().into_iter())
+    fn categoric_columns(&self) -> Vec<String> {
+        self.df.schema().iter()
+            .filter(|(_, dtype)| matches!(dtype, DataType::String | DataType::Categorical(..) | DataType::Enum(..) | DataType::Boolean))
+            .map(|(name, _)| name.to_string())
             .collect()
     }
+}
 
-    fn build_grid(
-        &self,
-        property_x: PropertyRange,
-        property_y: PropertyRange,
-        x_n: u8,
-        y_n: u8,
-        group_volume_property: &str,
-    ) -> GroupsGrid {
-        let property_x_name = property_x.property().name().as_str();
-        let property_y_name = property_y.property().name().as_str();
+//quartiles, whiskers at the last values within 1.5 IQR of the box and the outliers beyond, with a kde for the violin
+fn box_group(name: String, sorted: &[f64]) -> Option<BoxGroup> {
+    let (q1, median, q3) = (quantile_sorted(sorted, 0.25)?, quantile_sorted(sorted, 0.5)?, quantile_sorted(sorted, 0.75)?);
+    let (low_fence, high_fence) = (q1 - 1.5 * (q3 - q1), q3 + 1.5 * (q3 - q1));
 
-        let properties_df = self.df.select([property_x_name, property_y_name]).unwrap();
+    let whiskers = (
+        sorted.iter().find(|v| **v >= low_fence).copied().unwrap_or(q1),
+        sorted.iter().rev().find(|v| **v <= high_fence).copied().unwrap_or(q3),
+    );
+    let outliers = sorted.iter().filter(|v| **v < low_fence || **v > high_fence).copied().collect::<Vec<_>>();
+
+    //evenly spaced order statistics of a sorted column are a fair sample for the kde
+    let sample = stride_sample(sorted, KDE_MAX_SAMPLE);
+    let density = silverman_bandwidth(&sample)
+        .map(|h| gaussian_kde(&sample, h, sorted[0], sorted[sorted.len() - 1], KDE_POINTS))
+        .unwrap_or_default();
+
+    //the most extreme outliers on both ends are always shown, the first is in every stride sample
+    let mut shown = stride_sample(&outliers, BOX_MAX_OUTLIERS - 1);
+    if let Some(last) = outliers.last().filter(|_| outliers.len() > shown.len()) {
+        shown.push(*last);
+    }
 
-        let min_df = properties_df.min_horizontal().unwrap().unwrap();
-        let max_df = properties_df.max_horizontal().unwrap().unwrap();
-        // debug!("min_df={:?}, max_df={:?}", min_df, max_df);
+    Some(BoxGroup::new(name, sorted.len(), [q1, median, q3], whiskers, shown, outliers.len(), density))
+}
 
-        let min_x = property_x.min().unwrap_or(
-            min_df
-                .get(0)
-                .expect("0")
-                .try_extract::<f32>()
-                .expect("min_x") as i64,
-        ) as f32;
-        let max_x = property_x.max().unwrap_or(
-            max_df
-                .get(0)
-                .expect("0")
-                .try_extract::<f32>()
-                .expect("max_x") as i64,
-        ) as f32;
+//every k-th row, at most max_rows, picked in the query so that large frames are never loaded whole; with the row count before
+fn stride_rows(values_lf: LazyFrame, max_rows: usize) -> Result<(LazyFrame, usize), WapukuError> {
+    let rows_df = values_lf.clone().select([len().alias("rows")]).collect()?;
+    let rows = rows_df.column("rows")?.get(0).ok().and_then(any_value_to_u32).unwrap_or(0) as usize;
+    let step = rows.div_ceil(max_rows.max(1)).max(1);
 
-        let min_y = property_y.min().unwrap_or(
-            min_df
-                .get(0)
-                .expect("0")
-                .try_extract::<f32>()
-                .expect("min_y") as i64,
-        ) as f32;
-        let max_y = property_y.max().unwrap_or(
-            max_df
-                .get(0)
-                .expect("0")
-                .try_extract::<f32>()
-                .expect("max_y") as i64,
-        ) as f32;
+    Ok((values_lf.select([col("*").gather_every(step, 0)]), rows))
+}
 
-        let property_x_step = (((max_x - min_x) / x_n as f32).ceil()) as i64;
+fn range_filter(physical: Expr, range: Option<(f64, f64)>) -> Expr {
+    match range {
+        Some((min, max)) => physical.clone().is_null().or(physical.clone().gt_eq(lit(min)).and(physical.lt_eq(lit(max)))),
+        None => lit(true),
+    }
+}
+
+fn group_counts(values_lf: LazyFrame, key: Expr) -> Result<HashMap<i64, u32>, WapukuError> {
+    let counts_df = values_lf
+        .group_by([key.cast(DataType::Int64).alias("wapuku_key")])
+        .agg([len().alias("wapuku_count")])
+        .collect()?;
+
+    Ok(std::iter::zip(
+        counts_df.column("wapuku_key")?.as_materialized_series().iter(),
+        counts_df.column("wapuku_count")?.as_materialized_series().iter(),
+    )
+    .filter_map(|(key, count)| any_value_to_f64(key).map(|k| k as i64).zip(any_value_to_u32(count)))
+    .collect())
+}
+
+//unit for the span of a millisecond datetime column
+fn auto_calendar_unit(values_lf: LazyFrame, ts_str: &str) -> Result<CalendarUnit, WapukuError> {
+    let span_df = values_lf.select([
+        (col(ts_str).max() - col(ts_str).min()).cast(DataType::Int64).alias("span")
+    ]).collect()?;
+
+    Ok(CalendarUnit::auto(span_df.column("span")?.get(0).ok().and_then(any_value_to_f64).unwrap_or(0.0) as i64))
+}
+
+//t test, and Fisher z interval with the usual standard errors for the rank coefficients
+fn coefficient_value(measure: CorrMeasure, value: Option<f64>, n: usize) -> CorrValue {
+    let corr_value = CorrValue::new(measure, value.map(|r| r as f32), n);
+    let r = match value.filter(|r| r.is_finite()) {
+        Some(r) => r,
+        None => return corr_value,
+    };
+    let n = n as f64;
+
+    let (p_value, se) = match measure {
+        CorrMeasure::Kendall => (
+            (n >= 2.0).then(|| normal_p(3.0 * r * (n * (n - 1.0)).sqrt() / (2.0 * (2.0 * n + 5.0)).sqrt())),
+            (n > 4.0).then(|| (0.437 / (n - 4.0)).sqrt()),
+        ),
+        _ => (
+            (n > 2.0).then(|| if r.abs() >= 1.0 { 0.0 } else { student_t_p(r * ((n - 2.0) / (1.0 - r * r)).sqrt(), n - 2.0) }),
+            (n > 3.0).then(|| if measure == CorrMeasure::Spearman { (1.06 / (n - 3.0)).sqrt() } else { 1.0 / (n - 3.0).sqrt() }),
+        ),
+    };
+
+    corr_value
+        .with_p_value(p_value)
+        .with_ci(se.map(|se| fisher_ci(r, se)).map(|(lo, hi)| (lo as f32, hi as f32)))
+}
+
+fn cramers_v_of(pair_df: LazyFrame) -> Result<CorrValue, WapukuError> {
+    let cells_df = pair_df
+        .group_by([col("x"), col("y")])
+        .agg([len().alias("n")])
+        .collect()?;
+
+    let cells = std::iter::zip(
+        std::iter::zip(
+            cells_df.column("x")?.as_materialized_series().iter(),
+            cells_df.column("y")?.as_materialized_series().iter(),
+        ),
+        cells_df.column("n")?.as_materialized_series().iter(),
+    )
+    .filter_map(|((x, y), n)| any_value_to_f64(n).map(|n| (x.str_value().to_string(), y.str_value().to_string(), n)))
+    .collect::<Vec<_>>();
+
+    let n = cells.iter().map(|(_, _, n)| n).sum::<f64>();
+    let rows = cells.iter().map(|(x, _, _)| x).collect::<HashSet<_>>().len() as f64;
+    let columns = cells.iter().map(|(_, y, _)| y).collect::<HashSet<_>>().len() as f64;
+    let v = cramers_v(&cells);
+
+    //chi^2 = V^2 n (min(r, c) - 1)
+    let p_value = v.map(|v| chi2_p(v * v * n * (rows.min(columns) - 1.0), (rows - 1.0) * (columns - 1.0)));
+
+    Ok(CorrValue::new(CorrMeasure::CramersV, v.map(|v| v as f32), n as usize).with_p_value(p_value))
+}
+
+fn correlation_ratio_of(pair_df: LazyFrame, category: &str, value: &str) -> Result<CorrValue, WapukuError> {
+    let groups_df = pair_df
+        .filter(col(value).is_not_nan())
+        .group_by([col(category)])
+        .agg([
+            len().cast(DataType::Float64).alias("n"),
+            col(value).mean().alias("mean"),
+            col(value).var(0).alias("var"),
+        ])
+        .collect()?;
+
+    let groups = std::iter::zip(
+        std::iter::zip(
+            groups_df.column("n")?.as_materialized_series().iter(),
+            groups_df.column("mean")?.as_materialized_series().iter(),
+        ),
+        groups_df.column("var")?.as_materialized_series().iter(),
+    )
+    .filter_map(|((n, mean), var)| Some((any_value_to_f64(n)?, any_value_to_f64(mean)?, any_value_to_f64(var).unwrap_or(0.0))))
+    .collect::<Vec<_>>();
+
+    let n = groups.iter().map(|(n, _, _)| n).sum::<f64>();
+    let k = groups.len() as f64;
+    let eta = correlation_ratio(&groups);
+
+    //one-way ANOVA F = (eta^2 / (k - 1)) / ((1 - eta^2) / (n - k))
+    let p_value = eta.filter(|_| k > 1.0 && n > k).map(|eta| {
+        let eta2 = eta * eta;
+        if eta2 >= 1.0 { 0.0 } else { f_p((eta2 / (k - 1.0)) / ((1.0 - eta2) / (n - k)), k - 1.0, n - k) }
+    });
+
+    Ok(CorrValue::new(CorrMeasure::CorrelationRatio, eta.map(|eta| eta as f32), n as usize).with_p_value(p_value))
+}
+
+fn next_period(period: &NaiveDateTime, unit: CalendarUnit) -> Option<NaiveDateTime> {
+    match unit {
+        CalendarUnit::Hour => period.checked_add_signed(TimeDelta::hours(1)),
+        CalendarUnit::Day => period.checked_add_signed(TimeDelta::days(1)),
+        CalendarUnit::Week => period.checked_add_signed(TimeDelta::weeks(1)),
+        CalendarUnit::Month => period.checked_add_months(Months::new(1)),
+        CalendarUnit::Quarter => period.checked_add_months(Months::new(3)),
+        CalendarUnit::Year => period.checked_add_months(Months::new(12)),
+    }
+}
+
+fn format_period(period: &NaiveDateTime, unit: CalendarUnit) -> String {
+    match unit {
+        CalendarUnit::Hour => period.format("%Y-%m-%d %H:00").to_string(),
+        CalendarUnit::Day => period.format("%Y-%m-%d").to_string(),
+        CalendarUnit::Week => period.format("%G-W%V").to_string(),
+        CalendarUnit::Month => period.format("%Y-%m").to_string(),
+        CalendarUnit::Quarter => format!("{}-Q{}", period.year(), period.month0() / 3 + 1),
+        CalendarUnit::Year => period.format("%Y").to_string(),
+    }
+}
+
+fn format_duration_ms(millis: f64) -> String {
+    match millis.abs() {
+        m if m < 1_000.0 => format!("{:.0}ms", millis),
+        m if m < 60_000.0 => format!("{:.1}s", millis / 1_000.0),
+        m if m < 3_600_000.0 => format!("{:.1}min", millis / 60_000.0),
+        m if m < DAY_MS => format!("{:.1}h", millis / 3_600_000.0),
+        _ => format!("{:.1}d", millis / DAY_MS),
+    }
+}
+
+//smallest readable step of at least width_ms, 1-2-5 days past half a day
+fn duration_step_ms(width_ms: f64) -> f64 {
+    DURATION_STEPS_MS.iter().copied().find(|step| *step >= width_ms).unwrap_or_else(|| {
+        let days = width_ms / DAY_MS;
+        let magnitude = 10f64.powf(days.log10().floor());
+        [1.0, 2.0, 5.0, 10.0].iter().map(|m| m * magnitude).find(|d| *d >= days).unwrap_or(10.0 * magnitude) * DAY_MS
+    })
+}
+
+//about bins edges on whole duration steps, in the physical unit of the column
+fn duration_edges(min: f64, max: f64, bins: usize, time_unit: &TimeUnit) -> Vec<f64> {
+    let per_ms = match time_unit {
+        TimeUnit::Nanoseconds => 1_000_000.0,
+        TimeUnit::Microseconds => 1_000.0,
+        TimeUnit::Milliseconds => 1.0,
+    };
+
+    let step = duration_step_ms((max - min) / bins.max(1) as f64 / per_ms) * per_ms;
+    let first = (min / step).floor() * step;
+    let bins = ((max - first) / step).ceil().max(1.0) as usize;
+
+    (0..=bins.min(HISTOGRAM_MAX_BINS)).map(|i| first + i as f64 * step).collect()
+}
+
+//index of the bin for evenly spaced edges, the max value goes to the last bin,
+//a value on an edge may divide into the neighbour bin, so it is checked against the edges as the Range condition of the bin does
+fn uniform_bin(value: Expr, edges: &[f64]) -> Expr {
+    let bins = edges.len() - 1;
+    let (first, last) = (edges[0], edges[bins]);
+    let width = (last - first) / bins as f64;
+    let last_bin = lit(bins as i64 - 1);
+
+    //same arithmetic as uniform_edges
+    let edge = |i: Expr| when(i.clone().gt(last_bin.clone())).then(lit(last)).otherwise(lit(first) + i.cast(DataType::Float64) * lit(width));
+
+    let index = ((value.clone() - lit(first)) / lit(width)).cast(DataType::Int64);
+    let index = when(index.clone().gt(last_bin.clone())).then(last_bin.clone())
+        .when(index.clone().lt(lit(0i64))).then(lit(0i64))
+        .otherwise(index);
+
+    when(value.clone().lt(edge(index.clone())).and(index.clone().gt(lit(0i64)))).then(index.clone() - lit(1i64))
+        .when(value.gt_eq(edge(index.clone() + lit(1i64))).and(index.clone().lt(last_bin))).then(index.clone() + lit(1i64))
+        .otherwise(index)
+}
+
+fn bin_labels(edges: &[f64], dtype: &DataType) -> Vec<String> {
+    let bins = edges.len().saturating_sub(1);
+
+    (0..bins).map(|i| {
+        format!(
+            "[{}, {}{}",
+            format_physical(edges[i], dtype),
+            format_physical(edges[i + 1], dtype),
+            if i == bins - 1 { "]" } else { ")" }
+        )
+    }).collect()
+}
+
+fn ms_to_physical(ms: i64, dtype: &DataType) -> f64 {
+    match dtype {
+        DataType::Date => ms as f64 / 86_400_000.0,
+        DataType::Datetime(TimeUnit::Microseconds, _) => ms as f64 * 1_000.0,
+        DataType::Datetime(TimeUnit::Nanoseconds, _) => ms as f64 * 1_000_000.0,
+        _ => ms as f64,
+    }
+}
+
+//equal width bins of values as from, to and count
+fn sample_histogram(values: &[f64], bins: usize) -> Vec<(f64, f64, u32)> {
+    if values.is_empty() {
+        return vec![];
+    }
+
+    let (min, max) = values.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| (min.min(*v), max.max(*v)));
+    let edges = uniform_edges(min, max, bins);
+    if edges.len() < 2 {
+        return vec![(min, max, values.len() as u32)];
+    }
+
+    let width = edges[1] - edges[0];
+    let mut counts = vec![0u32; edges.len() - 1];
+    for v in values {
+        let bin = (((v - min) / width) as usize).min(counts.len() - 1);
+        counts[bin] += 1;
+    }
+
+    std::iter::zip(edges.windows(2), counts).map(|(edge, count)| (edge[0], edge[1], count)).collect()
+}
+
+fn uniform_edges(min: f64, max: f64, bins: usize) -> Vec<f64> {
+    let bins = bins.clamp(1, HISTOGRAM_MAX_BINS);
+
+    if max > min {
+        let width = (max - min) / bins as f64;
+        (0..=bins).map(|i| if i == bins { max } else { min + i as f64 * width }).collect()
+    } else {
+        vec![min]
+    }
+}
+
+fn format_physical(value: f64, dtype: &DataType) -> String {
+    match dtype {
+        DataType::Date => NaiveDate::from_num_days_from_ce_opt(UNIX_EPOCH_DAYS_FROM_CE + value.floor() as i32)
+            .map(|d| d.format("%Y-%m-%d").to_string())
+            .unwrap_or(format!("{}", value)),
+        DataType::Datetime(time_unit, _) => {
+            let millis = match time_unit {
+                TimeUnit::Nanoseconds => value / 1_000_000.0,
+                TimeUnit::Microseconds => value / 1_000.0,
+                TimeUnit::Milliseconds => value,
+            };
+            chrono::DateTime::from_timestamp_millis(millis as i64)
+                .map(|d| d.naive_utc().format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or(format!("{}", value))
+        }
+        DataType::Duration(time_unit) => format_duration_ms(match time_unit {
+            TimeUnit::Nanoseconds => value / 1_000_000.0,
+            TimeUnit::Microseconds => value / 1_000.0,
+            TimeUnit::Milliseconds => value,
+        }),
+        DataType::Time => {
+            let nanos = value as i64;
+            NaiveTime::from_num_seconds_from_midnight_opt((nanos / 1_000_000_000) as u32, (nanos % 1_000_000_000) as u32)
+                .map(|t| t.format("%H:%M:%S").to_string())
+                .unwrap_or(format!("{}", value))
+        }
+        _ => format!("{:.2}", value),
+    }
+}
+
+impl Data for PolarsData {
+    fn load(data: Box<Vec<u8>>, name: Box<String>) -> Result<Vec<Self>, WapukuError> {
+        if name.ends_with("csv") {
+            load_csv(data).map(|d| vec![PolarsData::new(d, *name)])
+        } else if name.ends_with("parquet") {
+            load_parquet(data).map(|d| vec![PolarsData::new(d, *name)])
+        } else if name.ends_with("zip") {
+            load_zip(data).map(|d_vec| {
+                d_vec
+                    .into_iter()
+                    .map(|(df, entry_name)| PolarsData::new(df, format!("{}/{}", name, entry_name)))
+                    .collect()
+            })
+        } else {
+            Err(WapukuError::General {
+                msg: String::from("I can load only csv or parquet files"),
+            })
+        }
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn all_sets(&self) -> Vec<&dyn PropertiesSet> {
+        self.property_sets.iter().fold(vec![], |mut props, p| {
+            props.push(p);
+
+            props
+        })
+    }
+
+    fn all_properties(&self) -> HashSet<&dyn Property> {
+        self.property_sets
+            .iter()
+            .flat_map(|property_set| property_set.properties().into_iter())
+            .collect()
+    }
+
+    fn build_grid(
+        &self,
+        property_x: PropertyRange,
+        property_y: PropertyRange,
+        x_n: u8,
+        y_n: u8,
+        group_volume_property: &str,
+    ) -> GroupsGrid {
+        let property_x_name = property_x.property().name().as_str();
+        let property_y_name = property_y.property().name().as_str();
+
+        let properties_df = self.df.select([property_x_name, property_y_name]).unwrap();
+
+        let min_df = properties_df.min_horizontal().unwrap().unwrap();
+        let max_df = properties_df.max_horizontal().unwrap().unwrap();
+        // debug!("min_df={:?}, max_df={:?}", min_df, max_df);
+
+        let min_x = property_x.min().unwrap_or(
+            min_df
+                .get(0)
+                .expect("0")
+                .try_extract::<f32>()
+                .expect("min_x") as i64,
+        ) as f32;
+        let max_x = property_x.max().unwrap_or(
+            max_df
+                .get(0)
+                .expect("0")
+                .try_extract::<f32>()
+                .expect("max_x") as i64,
+        ) as f32;
+
+        let min_y = property_y.min().unwrap_or(
+            min_df
+                .get(0)
+                .expect("0")
+                .try_extract::<f32>()
+                .expect("min_y") as i64,
+        ) as f32;
+        let max_y = property_y.max().unwrap_or(
+            max_df
+                .get(0)
+                .expect("0")
+                .try_extract::<f32>()
+                .expect("max_y") as i64,
+        ) as f32;
+
+        let property_x_step = (((max_x - min_x) / x_n as f32).ceil()) as i64;
 
         let property_y_step = (((max_y - min_y) / y_n as f32).ceil()) as i64;
 
@@ -821,12 +1437,18 @@ impl Data for PolarsData {
         &self,
         frame_id: u128,
         column: String,
-        bins: Option<usize>,
+        params: HistogramParams,
     ) -> Result<Histogram, WapukuError> {
         debug!("wapuku: build_histogram={:?}", column);
 
         match self.df.column(column.as_str())?.dtype() {
-            // DataType::Boolean => {}
+            DataType::Date | DataType::Datetime(_, _) if params.binning().is_calendar() => {
+                Ok(self.group_by_calendar(frame_id, column, params)?)
+            }
+            DataType::Time => {
+                Ok(self.group_by_calendar(frame_id, column, params.with_binning(Binning::HourOfDay))?)
+            }
+            DataType::Boolean => Ok(self.group_by_boolean(frame_id, column, params)?),
             DataType::UInt8
             | DataType::UInt16
             | DataType::UInt32
@@ -838,17 +1460,17 @@ impl Data for PolarsData {
             | DataType::Float32
             | DataType::Float64
             | DataType::Date
-            | DataType::Time => Ok(self.group_by_numeric(frame_id, column, bins)?),
+            | DataType::Datetime(_, _)
+            | DataType::Duration(_) => Ok(self.group_by_bins(frame_id, column, params)?),
             // DataType::Decimal(_, _) => {}
-            DataType::String => Ok(self.group_by_categoric(frame_id, column)?),
+            DataType::String | DataType::Categorical(..) | DataType::Enum(..) => {
+                Ok(self.group_by_categoric(frame_id, column, params)?)
+            }
             // DataType::Binary => {}
-            DataType::Datetime(_, _) => Ok(self.group_by_datetime(frame_id, column)?),
-            // DataType::Duration(_) => {}
             // DataType::Array(_, _) => {}
             // DataType::List(_) => {}
             // DataType::Object(_) => {}
             // DataType::Null => {}
-            // DataType::Categorical(_) => {}
             // DataType::Struct(_) => {}
             // DataType::Unknown => {}
             dtype => Err(WapukuError::DataLoad {
@@ -952,569 +1574,1566 @@ impl Data for PolarsData {
             .ok_or(WapukuError::DataLoad { msg: "ups".into() })
     }
 
-    fn clc_corrs(&self, frame_id: u128, columns: Vec<String>) -> Result<Corrs, WapukuError> {
-        let mut corr_hash: HashMap<(String, String), f32> = HashMap::new();
+    fn clc_corrs(&self, frame_id: u128, columns: Vec<String>, method: CorrMethod) -> Result<Corrs, WapukuError> {
+        let mut corr_hash: HashMap<(String, String), CorrValue> = HashMap::new();
 
-        let mut corr_df = self.df.clone();
+        let kinds = columns
+            .iter()
+            .map(|column_name| {
+                Ok(match self.df.column(column_name)?.dtype() {
+                    DataType::Boolean => WapukuDataType::Boolean,
+                    DataType::String | DataType::Categorical(..) | DataType::Enum(..) => WapukuDataType::String,
+                    _ => WapukuDataType::Numeric,
+                })
+            })
+            .collect::<Result<Vec<_>, WapukuError>>()?;
 
-        for column_name in columns.clone() {
-            match self.df.column(&column_name)?.dtype() {
-                DataType::String => {
-                    debug!("clc_corrs: replace={:?}", column_name);
-
-                    let values = self.df.column(&column_name)?.as_materialized_series();
-                    let unique_series = values.unique()?.sort(SortOptions::default())?;
-                    let unique_vals_map = unique_series
-                        .iter()
-                        .enumerate()
-                        .fold(HashMap::new(), |mut m, (i, v)| {
-                            m.insert(v.to_string(), i);
-                            m
-                        });
-                    let encoded = Series::new(
-                        column_name.clone().into(),
-                        values
-                            .iter()
-                            .map(|val| *unique_vals_map.get(val.to_string().as_str()).unwrap_or(&0) as f64)
-                            .collect::<Vec<f64>>(),
-                    );
-                    corr_df.replace(&column_name, encoded.into())?;
-                }
-                _ => {},
+        let pairs = (0..columns.len())
+            .flat_map(|i| (i + 1..columns.len()).map(move |j| (i, j)))
+            .map(|(i, j)| {
+                let measure = match (&kinds[i], &kinds[j]) {
+                    (WapukuDataType::Numeric, WapukuDataType::Numeric) => CorrMeasure::from(method),
+                    (WapukuDataType::Boolean, WapukuDataType::Numeric) | (WapukuDataType::Numeric, WapukuDataType::Boolean) => CorrMeasure::PointBiserial,
+                    (WapukuDataType::String, WapukuDataType::Numeric) | (WapukuDataType::Numeric, WapukuDataType::String) => CorrMeasure::CorrelationRatio,
+                    _ => CorrMeasure::CramersV,
+                };
+                (i, j, measure)
+            })
+            .collect::<Vec<_>>();
+
+        let l_df = self.df.clone().lazy();
+        let as_f64 = |i: usize| col(columns[i].as_str()).cast(DataType::Float64);
+
+        //all coefficients of the numeric block in one query, nulls are dropped per pair
+        let mut exprs = vec![];
+        for (k, (i, j, measure)) in pairs.iter().enumerate() {
+            let mask = col(columns[*i].as_str()).is_not_null().and(col(columns[*j].as_str()).is_not_null());
+            let (x, y) = (as_f64(*i).filter(mask.clone()), as_f64(*j).filter(mask.clone()));
+            let rank = |e: Expr| e.rank(RankOptions { method: RankMethod::Average, descending: false }, None);
+
+            let value = match measure {
+                CorrMeasure::Pearson | CorrMeasure::PointBiserial => pearson_corr(x.clone(), y.clone()),
+                CorrMeasure::Spearman => pearson_corr(rank(x.clone()), rank(y.clone())),
+                CorrMeasure::Covariance => cov(x.clone(), y.clone(), 1),
+                _ => continue,
+            };
+            exprs.push(value.alias(format!("value_{}", k)));
+            exprs.push(mask.sum().alias(format!("n_{}", k)));
+            if *measure == CorrMeasure::Covariance {
+                exprs.push(pearson_corr(x, y).alias(format!("r_{}", k)));
+            }
+        }
+        if method == CorrMethod::Covariance {
+            for i in (0..columns.len()).filter(|i| kinds[*i] == WapukuDataType::Numeric) {
+                exprs.push(as_f64(i).var(1).alias(format!("var_{}", i)));
+                exprs.push(col(columns[i].as_str()).is_not_null().sum().alias(format!("var_n_{}", i)));
             }
         }
 
-        let l_df = corr_df.lazy();
-        debug!("l_df={:?}", l_df.clone().collect());
+        let block_df = if exprs.is_empty() { None } else { Some(l_df.clone().select(exprs).collect()?) };
+        let block_value = |name: String| -> Result<Option<f64>, WapukuError> {
+            Ok(match block_df.as_ref() {
+                Some(block_df) => block_df.column(&name)?.as_materialized_series().iter().next().and_then(any_value_to_f64),
+                None => None,
+            })
+        };
 
-        for (i, column_0) in columns.iter().enumerate() {
-            //TODO itertools
-            for column_1 in columns.iter().skip(i + 1) {
-                debug!(
-                    "clc_corrs: column_0={:?}, column_1={:?}",
-                    column_0, column_1
-                );
-                let expr = pearson_corr(
-                    Expr::Column(column_0.as_str().into()),
-                    Expr::Column(column_1.as_str().into()),
-                )
-                .alias("corr");
-                let res = l_df.clone().select(&[expr]).collect();
-                let corr_series = res.as_ref()?.column("corr")?.as_materialized_series();
-                let corr_value = corr_series
-                    .iter()
-                    .next()
-                    .and_then(any_value_to_f32)
-                    .ok_or_else(|| WapukuError::DataFrame {
-                        msg: format!(
-                            "invalid corr dtype/value: dtype={:?}, value={:?}",
-                            corr_series.dtype(),
-                            corr_series.iter().next()
-                        ),
-                    })?;
+        //kendall needs the values, collected once for all pairs
+        let kendall_columns = pairs.iter()
+            .filter(|(_, _, measure)| *measure == CorrMeasure::Kendall)
+            .flat_map(|(i, j, _)| [*i, *j])
+            .collect::<BTreeSet<_>>();
+        let kendall_values = if kendall_columns.is_empty() {
+            HashMap::new()
+        } else {
+            let kendall_df = l_df.clone()
+                .select(kendall_columns.iter().map(|i| as_f64(*i)).collect::<Vec<_>>())
+                .collect()?;
+            kendall_columns.iter()
+                .map(|i| Ok((*i, kendall_df.column(columns[*i].as_str())?.as_materialized_series().iter().map(any_value_to_f64).collect::<Vec<_>>())))
+                .collect::<Result<HashMap<_, _>, WapukuError>>()?
+        };
+
+        for (k, (i, j, measure)) in pairs.iter().enumerate() {
+            let (column_0, column_1) = (&columns[*i], &columns[*j]);
+            debug!(
+                "clc_corrs: column_0={:?}, column_1={:?}, measure={:?}",
+                column_0, column_1, measure
+            );
+            //pairwise deletion of nulls
+            let select_pair = |dtype_0: DataType, dtype_1: DataType| {
+                l_df.clone()
+                    .select([
+                        col(column_0.as_str()).cast(dtype_0).alias("x"),
+                        col(column_1.as_str()).cast(dtype_1).alias("y"),
+                    ])
+                    .filter(col("x").is_not_null().and(col("y").is_not_null()))
+            };
+
+            let corr_value = match measure {
+                CorrMeasure::Pearson | CorrMeasure::PointBiserial | CorrMeasure::Spearman => {
+                    let n = block_value(format!("n_{}", k))?.unwrap_or(0.0) as usize;
+                    coefficient_value(*measure, block_value(format!("value_{}", k))?, n)
+                }
+                CorrMeasure::Covariance => {
+                    let n = block_value(format!("n_{}", k))?.unwrap_or(0.0) as usize;
+                    //significance of the covariance is the one of r
+                    let r = coefficient_value(CorrMeasure::Pearson, block_value(format!("r_{}", k))?, n);
+                    CorrValue::new(CorrMeasure::Covariance, block_value(format!("value_{}", k))?.map(|v| v as f32), n)
+                        .with_p_value(r.p_value())
+                        .with_r(r.value())
+                }
+                CorrMeasure::Kendall => {
+                    let (x, y): (Vec<f64>, Vec<f64>) = std::iter::zip(&kendall_values[i], &kendall_values[j])
+                        .filter_map(|(x, y)| Some(((*x)?, (*y)?)))
+                        .unzip();
+                    coefficient_value(CorrMeasure::Kendall, kendall_tau(&x, &y), x.len())
+                }
+                CorrMeasure::CorrelationRatio if kinds[*i] == WapukuDataType::String => {
+                    correlation_ratio_of(select_pair(DataType::String, DataType::Float64), "x", "y")?
+                }
+                CorrMeasure::CorrelationRatio => {
+                    correlation_ratio_of(select_pair(DataType::Float64, DataType::String), "y", "x")?
+                }
+                CorrMeasure::CramersV => {
+                    cramers_v_of(select_pair(DataType::String, DataType::String))?
+                }
+            };
+
+            corr_hash.insert(
+                (column_0.clone(), column_1.clone()),
+                corr_value,
+            );
+        }
 
+        if method == CorrMethod::Covariance {
+            for (i, column) in columns.iter().enumerate().filter(|(i, _)| kinds[*i] == WapukuDataType::Numeric) {
+                let n = block_value(format!("var_n_{}", i))?.unwrap_or(0.0) as usize;
                 corr_hash.insert(
-                    (column_0.clone(), column_1.clone()),
-                    corr_value,
+                    (column.clone(), column.clone()),
+                    CorrValue::new(CorrMeasure::Covariance, block_value(format!("var_{}", i))?.map(|v| v as f32), n),
                 );
             }
         }
 
-        debug!("corr_hash={:?}", corr_hash);
+        debug!("clc_corrs: pairs={}", corr_hash.len());
 
-        Ok(Corrs::new(frame_id, columns, corr_hash))
+        Ok(Corrs::new(frame_id, columns, method, corr_hash))
     }
 
-    fn plot_columns(&self, frame_id: u128, columns: Vec<String>) -> Result<ColumnsPlot, WapukuError> {
+    fn plot_columns(&self, frame_id: u128, columns: Vec<String>, max_points: usize) -> Result<ColumnsPlot, WapukuError> {
+        debug!("plot_columns columns={:?} max_points={}", columns, max_points);
+
+        let max_points = max_points.max(PLOT_MIN_POINTS);
+
         let series = columns
             .iter()
             .map(|column_name| {
                 let column = self.df.column(column_name)?;
+                let points = series_points_for_plot(column.as_materialized_series());
+                let downsampled_from = Some(points.len()).filter(|n| *n > max_points);
+
                 Ok(ColumnPlotSeries::new(
                     column_name.clone(),
-                    series_points_for_plot(column.as_materialized_series()),
-                ))
+                    min_max_downsample(&points, max_points),
+                ).with_downsampled_from(downsampled_from))
             })
             .collect::<Result<Vec<_>, WapukuError>>()?;
 
         Ok(ColumnsPlot::new(frame_id, columns, series))
     }
-}
 
-impl From<Filter> for Expr {
-    fn from(filter: Filter) -> Self {
-        if let Some(conditions) = filter.conditions() {
-            conditions_to_expr(conditions)
-        } else {
-            lit(true)
+    fn kde(&self, frame_id: u128, column: String, bandwidth: Bandwidth, split: Option<String>) -> Result<Kde, WapukuError> {
+        debug!("kde column={:?} bandwidth={:?} split={:?}", column, bandwidth, split);
+
+        let dtype = self.df.column(column.as_str())?.dtype();
+        if !dtype.is_numeric() {
+            return Err(WapukuError::DataLoad {
+                msg: format!("can't estimate density for {} of type {}", column, dtype),
+            });
         }
+
+        let rows = self.df.column(column.as_str())?.len() - self.df.column(column.as_str())?.null_count();
+        let sample_size = if rows > KDE_MAX_SAMPLE { Some(KDE_MAX_SAMPLE) } else { None };
+
+        let groups = self.split_values(column.as_str(), split.as_ref(), sample_size)?;
+
+        let (min, max) = groups.iter()
+            .flat_map(|(_, values)| values.iter())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| (min.min(*v), max.max(*v)));
+
+        let series = groups.into_iter().filter_map(|(name, values)| {
+            let h = match bandwidth {
+                Bandwidth::Silverman => silverman_bandwidth(&values),
+                Bandwidth::Scott => scott_bandwidth(&values),
+                Bandwidth::Manual { h } => Some(h).filter(|h| *h > 0.0),
+            }?;
+
+            Some(ColumnPlotSeries::new(
+                format!("{} (h={:.3})", name, h),
+                gaussian_kde(&values, h, min - 3.0 * h, max + 3.0 * h, KDE_POINTS),
+            ))
+        }).collect();
+
+        Ok(Kde::new(frame_id, column, bandwidth, split, sample_size, series))
     }
-}
 
-fn conditions_to_expr(condition: &ConditionType) -> Expr {
-    match condition {
-        ConditionType::Single {
-            column_name,
-            condition,
-        } => match condition {
-            Condition::Numeric { min, max } => col(column_name)
-                .gt_eq(*min)
-                .and(col(column_name).lt_eq(*max)),
-            Condition::String { pattern } => col(column_name)
-                .str()
-                .contains_literal(lit(pattern.to_owned())),
-            Condition::Boolean { val } => col(column_name).eq(*val),
-        },
-        ConditionType::Compoiste { conditions, ctype } => {
-            if conditions.len() == 0 {
-                warn!("empty composit condition");
-                lit(true)
-            } else if conditions.len() == 1 {
-                conditions_to_expr(conditions.get(0).unwrap())
-            } else {
-                conditions.into_iter().skip(1).fold(
-                    conditions_to_expr(conditions.get(0).expect("no first condition")),
-                    |a, c| match ctype {
-                        CompositeType::AND => a.and(conditions_to_expr(c)),
-                        CompositeType::OR => a.or(conditions_to_expr(c)),
-                    },
-                )
+    fn build_histogram_2d(&self, frame_id: u128, column_x: String, column_y: String, bins_x: usize, bins_y: usize) -> Result<Histogram2D, WapukuError> {
+        debug!("build_histogram_2d column_x={:?} column_y={:?} bins={:?}", column_x, column_y, (bins_x, bins_y));
+
+        let x_str = "wapuku_x";
+        let y_str = "wapuku_y";
+        let count_str = "wapuku_count";
+
+        let dtype_x = self.df.column(column_x.as_str())?.dtype().clone();
+        let dtype_y = self.df.column(column_y.as_str())?.dtype().clone();
+
+        for (column, dtype) in [(&column_x, &dtype_x), (&column_y, &dtype_y)] {
+            if !(dtype.is_numeric() || dtype.is_temporal()) {
+                return Err(WapukuError::DataLoad {
+                    msg: format!("can't build 2D histogram for {} of type {}", column, dtype),
+                });
             }
         }
-    }
-}
 
-#[cfg(test)]
-mod filter_test {
-    use crate::model::{Condition, ConditionType, Filter};
-    use crate::polars_df::tests::dummy_filter;
-    use log::debug;
-    use polars::prelude::Expr;
+        let values_lf = self.df.clone()
+            .lazy()
+            .select([
+                col(column_x.as_str()).to_physical().cast(DataType::Float64).alias(x_str),
+                col(column_y.as_str()).to_physical().cast(DataType::Float64).alias(y_str),
+            ])
+            .filter(
+                col(x_str).is_not_null().and(col(x_str).is_not_nan())
+                    .and(col(y_str).is_not_null()).and(col(y_str).is_not_nan())
+            );
+
+        let stats_df = values_lf.clone().select([
+            col(x_str).min().alias("min_x"),
+            col(x_str).max().alias("max_x"),
+            col(y_str).min().alias("min_y"),
+            col(y_str).max().alias("max_y"),
+        ]).collect()?;
+
+        let stat = |name: &str| stats_df.column(name).ok().and_then(|c| c.get(0).ok()).and_then(any_value_to_f64);
+
+        let edges = |min: f64, max: f64, bins: usize| if max > min {
+            uniform_edges(min, max, bins.clamp(1, HISTOGRAM_2D_MAX_BINS))
+        } else {
+            vec![min - 0.5, max + 0.5]
+        };
 
-    #[test]
-    pub fn test_string_pattern() {
-        let mut filter = dummy_filter();
-        filter.add_condition(
-            ConditionType::Single {
-                column_name: "property_2".into(),
-                condition: Condition::String {
-                    pattern: "aaaa".into(),
-                },
-            },
-            None,
-        );
+        let (edges_x, edges_y) = match (stat("min_x"), stat("max_x"), stat("min_y"), stat("max_y")) {
+            (Some(min_x), Some(max_x), Some(min_y), Some(max_y)) => (edges(min_x, max_x, bins_x), edges(min_y, max_y, bins_y)),
+            _ => return Ok(Histogram2D::new(frame_id, column_x, column_y, vec![], vec![], vec![], vec![], vec![])),
+        };
 
-        let expr: Expr = filter.into();
+        let counts_df = values_lf
+            .group_by([uniform_bin(col(x_str), &edges_x).alias(x_str), uniform_bin(col(y_str), &edges_y).alias(y_str)])
+            .agg([len().alias(count_str)])
+            .collect()?;
 
-        println!("expr={:?}", expr);
+        let mut counts = vec![vec![0u32; edges_x.len() - 1]; edges_y.len() - 1];
+        for ((x, y), count) in std::iter::zip(
+            std::iter::zip(
+                counts_df.column(x_str)?.as_materialized_series().iter(),
+                counts_df.column(y_str)?.as_materialized_series().iter(),
+            ),
+            counts_df.column(count_str)?.as_materialized_series().iter(),
+        ) {
+            if let (Some(x), Some(y), Some(count)) = (any_value_to_f64(x), any_value_to_f64(y), any_value_to_u32(count)) {
+                if let Some(c) = counts.get_mut(y as usize).and_then(|row| row.get_mut(x as usize)) {
+                    *c += count;
+                }
+            }
+        }
 
-        assert_eq!(
-            "col(\"property_2\").str.contains([Utf8(aaaa)])",
-            format!("{:?}", expr)
-        );
+        let labels_x = bin_labels(&edges_x, &dtype_x);
+        let labels_y = bin_labels(&edges_y, &dtype_y);
+
+        Ok(Histogram2D::new(frame_id, column_x, column_y, edges_x, edges_y, labels_x, labels_y, counts))
     }
 
-    #[test]
-    pub fn test_num_pattern() {
-        let mut filter = dummy_filter();
-        filter.add_condition(
-            ConditionType::Single {
-                column_name: "property_1".into(),
-                condition: Condition::Numeric {
-                    min: -10.0,
-                    max: 10.0,
-                },
-            },
-            None,
-        );
+    fn build_scatter(&self, frame_id: u128, params: ScatterParams) -> Result<Scatter, WapukuError> {
+        debug!("build_scatter params={:?}", params);
 
-        let expr: Expr = filter.into();
+        let x_str = "wapuku_x";
+        let y_str = "wapuku_y";
+        let size_str = "wapuku_size";
+        let split_str = "wapuku_split";
 
-        println!("expr={:?}", expr);
+        let plottable = |dtype: &DataType| dtype.is_numeric() || *dtype == DataType::Boolean;
 
-        assert_eq!(
-            "[([(col(\"property_1\")) >= (-10.0)]) & ([(col(\"property_1\")) <= (10.0)])]",
-            format!("{:?}", expr)
-        );
-    }
+        for column in [Some(params.column_x()), Some(params.column_y()), params.size_by()].into_iter().flatten() {
+            let dtype = self.df.column(column.as_str())?.dtype();
+            if !plottable(dtype) {
+                return Err(WapukuError::DataLoad {
+                    msg: format!("can't scatter {} of type {}", column, dtype),
+                });
+            }
+        }
 
-    #[test]
-    pub fn test_bool_pattern() {
-        let mut filter = dummy_filter();
-        filter.add_condition(
-            ConditionType::Single {
-                column_name: "property_3".into(),
-                condition: Condition::Boolean { val: true },
-            },
-            None,
+        let (split_expr, names) = match params.color_by() {
+            Some(color_by) => self.split_expr(color_by)?,
+            None => (lit(params.column_y().clone()), vec![params.column_y().clone()]),
+        };
+
+        let size_expr = match params.size_by() {
+            Some(size_by) => col(size_by.as_str()).cast(DataType::Float64),
+            None => lit(1.0),
+        };
+
+        let values_lf = self.df.clone()
+            .lazy()
+            .select([
+                col(params.column_x().as_str()).cast(DataType::Float64).alias(x_str),
+                col(params.column_y().as_str()).cast(DataType::Float64).alias(y_str),
+                size_expr.alias(size_str),
+                split_expr.alias(split_str),
+            ])
+            .filter(
+                col(x_str).is_not_null().and(col(x_str).is_not_nan())
+                    .and(col(y_str).is_not_null()).and(col(y_str).is_not_nan())
+                    .and(col(size_str).is_not_null()).and(col(size_str).is_not_nan())
+            );
+
+        //every k-th row keeps the overall shape of large frames
+        let (values_lf, rows) = stride_rows(values_lf, SCATTER_MAX_POINTS)?;
+        let values_df = values_lf.collect()?;
+
+        let mut groups = names.into_iter().map(|name| (name, vec![], vec![])).collect::<Vec<(String, Vec<[f64; 2]>, Vec<f64>)>>();
+
+        let points = std::iter::zip(
+            std::iter::zip(
+                values_df.column(x_str)?.as_materialized_series().iter(),
+                values_df.column(y_str)?.as_materialized_series().iter(),
+            ),
+            std::iter::zip(
+                values_df.column(size_str)?.as_materialized_series().iter(),
+                values_df.column(split_str)?.as_materialized_series().iter(),
+            ),
         );
 
-        let expr: Expr = filter.into();
+        for ((x, y), (size, name)) in points {
+            if let (Some(x), Some(y), Some(size), Some((_, points, sizes))) = (
+                any_value_to_f64(x),
+                any_value_to_f64(y),
+                any_value_to_f64(size),
+                groups.iter_mut().find(|(n, _, _)| *n == name.str_value()),
+            ) {
+                points.push([x, y]);
+                sizes.push(size);
+            }
+        }
 
-        println!("expr={:?}", expr);
+        let series = groups.into_iter()
+            .filter(|(_, points, _)| !points.is_empty())
+            .map(|(name, points, sizes)| ScatterSeries::new(name, points, if params.size_by().is_some() { sizes } else { vec![] }))
+            .collect();
 
-        assert_eq!("[(col(\"property_3\")) == (true)]", format!("{:?}", expr));
+        let numeric_columns = self.df.schema().iter()
+            .filter(|(_, dtype)| plottable(*dtype))
+            .map(|(name, _)| name.to_string())
+            .collect();
+
+        Ok(Scatter::new(frame_id, params, series, rows, numeric_columns, self.categoric_columns()))
     }
 
-    #[test]
-    pub fn test_and() {
-        let mut filter = dummy_filter();
-        filter.add_condition(
-            ConditionType::Single {
-                column_name: "property_2".into(),
-                condition: Condition::String {
-                    pattern: "aaaa".into(),
-                },
-            },
-            None,
-        );
-        filter.add_condition(
-            ConditionType::Single {
-                column_name: "property_2".into(),
-                condition: Condition::String {
-                    pattern: "bbb".into(),
-                },
-            },
-            None,
-        );
+    fn build_box_plot(&self, frame_id: u128, column: String, split_by: Option<String>) -> Result<BoxPlot, WapukuError> {
+        debug!("build_box_plot column={:?} split_by={:?}", column, split_by);
 
-        let expr: Expr = filter.into();
+        let dtype = self.df.column(column.as_str())?.dtype();
+        if !dtype.is_numeric() {
+            return Err(WapukuError::DataLoad {
+                msg: format!("can't build a box plot for {} of type {}", column, dtype),
+            });
+        }
 
-        assert_eq!("[(col(\"property_2\").str.contains([Utf8(aaaa)])) & (col(\"property_2\").str.contains([Utf8(bbb)]))]", format!("{:?}", expr));
+        let groups = self.split_values(column.as_str(), split_by.as_ref(), None)?
+            .into_iter()
+            .filter_map(|(name, mut values)| {
+                values.sort_by(|a, b| a.total_cmp(b));
+                box_group(name, &values)
+            })
+            .collect();
+
+        Ok(BoxPlot::new(frame_id, column, split_by, groups, self.categoric_columns()))
     }
 
-    #[test]
-    pub fn test_edit_top() {
-        let mut filter = dummy_filter();
-        let condition_type_0 = ConditionType::Single {
-            column_name: "property_2".into(),
-            condition: Condition::String {
-                pattern: "aaaa".into(),
-            },
+    fn build_time_series(&self, frame_id: u128, params: TimeSeriesParams) -> Result<TimeSeries, WapukuError> {
+        debug!("build_time_series params={:?}", params);
+
+        let ts_str = "wapuku_ts";
+
+        let dtype = self.df.column(params.column_x().as_str())?.dtype();
+        let time_zone = match dtype {
+            DataType::Date => None,
+            DataType::Datetime(_, time_zone) => time_zone.clone(),
+            _ => {
+                return Err(WapukuError::DataLoad {
+                    msg: format!("can't use {} of type {} as a time axis", params.column_x(), dtype),
+                });
+            }
         };
 
-        filter.add_condition(condition_type_0, None);
+        let plottable = |dtype: &DataType| dtype.is_numeric() || *dtype == DataType::Boolean;
 
-        let condition_type_0_ptr = filter.top_condition_ptr().expect("top_condition_ptr");
+        for column in params.columns_y() {
+            let dtype = self.df.column(column.as_str())?.dtype();
+            if !plottable(dtype) {
+                return Err(WapukuError::DataLoad {
+                    msg: format!("can't resample {} of type {}", column, dtype),
+                });
+            }
+        }
 
-        let Some(ConditionType::Single {
-            column_name,
-            condition: Condition::String { pattern },
-        }) = filter.conditions()
-        else {
-            panic!("no conditions")
-        };
+        let values_lf = self.df.clone()
+            .lazy()
+            .select(
+                std::iter::once(col(params.column_x().as_str()).cast(DataType::Datetime(TimeUnit::Milliseconds, time_zone.clone())).alias(ts_str))
+                    .chain(params.columns_y().iter().map(|column| col(column.as_str()).cast(DataType::Float64)))
+                    .collect::<Vec<_>>()
+            )
+            .filter(col(ts_str).is_not_null())
+            .sort([ts_str], SortMultipleOptions::default());
 
-        assert_eq!(column_name, "property_2");
-        assert_eq!(pattern, "aaaa");
+        let unit = match params.unit() {
+            Some(unit) => unit,
+            None => auto_calendar_unit(values_lf.clone(), ts_str)?,
+        };
 
-        let condition_type_1 = ConditionType::Single {
-            column_name: "property_2".into(),
-            condition: Condition::String {
-                pattern: "bbb".into(),
-            },
+        //without y columns the row count per period is plotted
+        let (names, aggs): (Vec<String>, Vec<Expr>) = if params.columns_y().is_empty() {
+            (vec![String::from(ROWS)], vec![len().cast(DataType::Float64).alias(ROWS)])
+        } else {
+            params.columns_y().iter().map(|column| {
+                let name = format!("{}({})", params.agg().label(), column);
+                let value = col(column.as_str());
+
+                let agg = match params.agg() {
+                    ResampleAgg::Sum => value.sum(),
+                    ResampleAgg::Mean => value.mean(),
+                    ResampleAgg::Count => value.count(),
+                    ResampleAgg::Min => value.min(),
+                    ResampleAgg::Max => value.max(),
+                };
+
+                (name.clone(), agg.cast(DataType::Float64).alias(name))
+            }).unzip()
         };
 
-        filter.add_condition(condition_type_1, Some(condition_type_0_ptr));
+        let every = Duration::parse(unit.every());
+
+        let resampled_lf = values_lf
+            .group_by_dynamic(
+                col(ts_str),
+                [],
+                DynamicGroupOptions {
+                    index_column: ts_str.into(),
+                    every,
+                    period: every,
+                    offset: Duration::new(0),
+                    include_boundaries: false,
+                    closed_window: ClosedWindow::Left,
+                    start_by: WindowBound,
+                    label: Label::Left,
+                },
+            )
+            .agg(aggs);
 
-        let Some(ConditionType::Single {
-            column_name,
-            condition: Condition::String { pattern },
-        }) = filter.conditions()
-        else {
-            panic!("no conditions")
+        //periods in the wall clock time of the column, the axis has no timezone
+        let resampled_lf = match time_zone {
+            Some(_) => resampled_lf.with_column(col(ts_str).dt().replace_time_zone(None, lit("raise"), NonExistent::Raise)),
+            None => resampled_lf,
         };
 
-        assert_eq!(column_name, "property_2");
-        assert_eq!(pattern, "bbb");
-    }
+        let periods_df = resampled_lf
+            .with_column(col(ts_str).cast(DataType::Int64))
+            .collect()?;
 
-    #[test]
-    pub fn test_edit_sub_1() {
-        let mut filter = dummy_filter();
-        let condition_type_0 = ConditionType::Single {
-            column_name: "property_1".into(),
-            condition: Condition::String {
-                pattern: "aaaa".into(),
-            },
-        };
-        let condition_type_1 = ConditionType::Single {
-            column_name: "property_2".into(),
-            condition: Condition::String {
-                pattern: "bbbb".into(),
-            },
+        //empty periods are not in group_by_dynamic output, they are filled in before the rolling mean
+        let rows = periods_df.column(ts_str)?.as_materialized_series().iter()
+            .enumerate()
+            .filter_map(|(row, ts)| any_value_to_f64(ts).map(|ts| (ts as i64, row)))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut periods = vec![];
+        let first = rows.keys().next().and_then(|ts| chrono::DateTime::from_timestamp_millis(*ts));
+        let last = rows.keys().last().and_then(|ts| chrono::DateTime::from_timestamp_millis(*ts));
+
+        if let (Some(first), Some(last)) = (first, last) {
+            let mut period = Some(first.naive_utc());
+            while let Some(p) = period.filter(|p| *p <= last.naive_utc()) {
+                periods.push(p.and_utc().timestamp_millis());
+                period = next_period(&p, unit);
+            }
+        }
+
+        let empty_value = match params.agg() {
+            _ if params.columns_y().is_empty() => Some(0.0),
+            ResampleAgg::Sum | ResampleAgg::Count => Some(0.0),
+            ResampleAgg::Mean | ResampleAgg::Min | ResampleAgg::Max => None,
         };
 
-        filter.add_condition(condition_type_0, None);
-        filter.add_condition(condition_type_1, None);
+        let mut columns = vec![Series::new(ts_str.into(), &periods).into()];
+        for name in names.iter() {
+            let values = periods_df.column(name.as_str())?.as_materialized_series().iter().map(any_value_to_f64).collect::<Vec<_>>();
+            let filled = periods.iter()
+                .map(|ts| rows.get(ts).map(|row| values[*row]).unwrap_or(empty_value))
+                .collect::<Vec<_>>();
 
-        let Some(ConditionType::Compoiste { conditions, ctype }) = filter.conditions() else {
-            panic!("no conditions")
-        };
-        let condition_type_0_f = conditions.get(0).expect("condition_type_1");
-        let condition_type_0_f_ptr = condition_type_0_f as *const _;
-        let condition_type_1_f = conditions.get(1).expect("condition_type_1");
-        let condition_type_1_f_ptr = condition_type_1_f as *const _;
+            columns.push(Series::new(name.as_str().into(), filled).into());
+        }
 
-        let condition_type_1 = ConditionType::Single {
-            column_name: "property_2".into(),
-            condition: Condition::String {
-                pattern: "cccc".into(),
-            },
+        let mut resampled_lf = DataFrame::new(periods.len(), columns)?.lazy();
+
+        let rolling_names = match params.rolling() {
+            Some(window) => names.iter().map(|name| format!("{} rolling mean {}", name, window)).collect(),
+            None => vec![],
         };
 
-        filter.add_condition(condition_type_1, Some(condition_type_1_f_ptr));
+        if let Some(window) = params.rolling() {
+            resampled_lf = resampled_lf.with_columns(
+                std::iter::zip(names.iter(), rolling_names.iter())
+                    .map(|(name, rolling_name)| col(name.as_str())
+                        .rolling_mean(RollingOptionsFixedWindow { window_size: window.max(1), min_periods: 1, ..Default::default() })
+                        .alias(rolling_name.as_str()))
+                    .collect::<Vec<_>>()
+            );
+        }
 
-        let Some(ConditionType::Compoiste { conditions, ctype }) = filter.conditions() else {
-            panic!("no conditions")
-        };
-        let ConditionType::Single {
-            column_name,
-            condition: Condition::String { pattern },
-        } = conditions.get(1).expect("condition_type_1").clone()
-        else {
-            panic!("no condition_type_1")
-        };
+        let resampled_df = resampled_lf.collect()?;
 
-        assert_eq!(column_name, "property_2");
-        assert_eq!(pattern, "cccc");
-    }
-}
+        debug!("build_time_series: resampled_df shape={:?}", resampled_df.shape());
 
-// impl Drop for PolarsData {
-//     fn drop(&mut self) {
-//         debug!("wapuku: Drop for PolarsData")
-//     }
-// }
+        let xs = resampled_df.column(ts_str)?.as_materialized_series().iter().map(any_value_to_f64).collect::<Vec<_>>();
 
-fn map_to_wapuku(d_type: &DataType) -> WapukuDataType {
-    //TODO
-    match d_type {
-        DataType::String => WapukuDataType::String,
+        let to_series = |names: Vec<String>| names.into_iter().map(|name| {
+            let points = std::iter::zip(xs.iter(), resampled_df.column(name.as_str())?.as_materialized_series().iter())
+                .filter_map(|(x, y)| x.zip(any_value_to_f64(y)))
+                .collect();
 
-        DataType::Float64
-        | DataType::Float32
-        | DataType::Int8
-        | DataType::Int16
-        | DataType::Int32
-        | DataType::Int64
-        | DataType::UInt8
-        | DataType::UInt16
-        | DataType::UInt32
-        | DataType::UInt64 => WapukuDataType::Numeric,
+            Ok(ColumnPlotSeries::new(name, points))
+        }).collect::<Result<Vec<_>, WapukuError>>();
 
-        DataType::Boolean => WapukuDataType::Boolean,
+        let schema = self.df.schema();
+        let datetime_columns = schema.iter()
+            .filter(|(_, dtype)| matches!(dtype, DataType::Date | DataType::Datetime(..)))
+            .map(|(name, _)| name.to_string())
+            .collect();
+        let numeric_columns = schema.iter()
+            .filter(|(_, dtype)| plottable(*dtype))
+            .map(|(name, _)| name.to_string())
+            .collect();
 
-        DataType::Datetime(..) | DataType::Date => WapukuDataType::Datetime,
-        _ => WapukuDataType::String,
+        Ok(TimeSeries::new(frame_id, params, unit, to_series(names)?, to_series(rolling_names)?, datetime_columns, numeric_columns))
     }
-}
 
-pub fn load_zip(data: Box<Vec<u8>>) -> Result<Vec<(DataFrame, String)>, WapukuError> {
-    let mut archive = ZipArchive::new(Cursor::new(data.as_slice()))?;
-    archive
-        .file_names()
-        .map(|s| String::from(s))
-        .collect::<Vec<String>>()
-        .into_iter()
-        .map(|file| {
-            let mut bytes = Vec::new();
-            archive.by_name(file.as_str())?.read_to_end(&mut bytes)?;
+    fn build_pair_plot(&self, frame_id: u128, columns: Vec<String>) -> Result<PairPlot, WapukuError> {
+        debug!("build_pair_plot columns={:?}", columns);
 
-            if file.ends_with("csv") {
-                load_csv(Box::new(bytes)).map(|df| (df, file))
-            } else if file.ends_with("parquet") {
-                load_parquet(Box::new(bytes)).map(|df| (df, file))
-            } else {
-                Err(WapukuError::DataLoad {
-                    msg: format!("Unexepcted file ending {}", file),
-                })
-            }
-        })
-        .collect::<Result<Vec<(DataFrame, String)>, WapukuError>>()
-}
+        //non-numeric columns of the selection are left out
+        let columns = columns.into_iter()
+            .filter(|column| self.df.column(column.as_str()).map(|c| c.dtype().is_numeric() || *c.dtype() == DataType::Boolean).unwrap_or(false))
+            .collect::<Vec<_>>();
 
-pub fn load_csv(csv_bytes: Box<Vec<u8>>) -> Result<DataFrame, WapukuError> {
-    CsvReader::new(Cursor::new(csv_bytes.as_slice()))
-        .finish()
-        .map_err(|e| e.into())
-}
+        if columns.len() < 2 {
+            return Err(WapukuError::DataLoad {
+                msg: String::from("pair plot needs at least two numeric columns"),
+            });
+        }
 
-#[cfg(feature = "parquet")]
-pub fn load_parquet(parquet_bytes: Box<Vec<u8>>) -> Result<DataFrame, WapukuError> {
-    ParquetReader::new(Cursor::new(parquet_bytes.as_slice()))
-        .finish()
-        .map_err(|e| e.into())
-}
+        if columns.len() > PAIR_PLOT_MAX_COLUMNS {
+            return Err(WapukuError::DataLoad {
+                msg: format!("pair plot of {} columns, max {}", columns.len(), PAIR_PLOT_MAX_COLUMNS),
+            });
+        }
 
-#[cfg(not(feature = "parquet"))]
-pub fn load_parquet(_parquet_bytes: Box<Vec<u8>>) -> Result<DataFrame, WapukuError> {
-    Err(WapukuError::DataLoad {
-        msg: "Parquet loading is not enabled in this build".into(),
-    })
-}
+        //rows complete in every column, so that all cells show the same sample
+        let complete = columns.iter().fold(lit(true), |e, column| {
+            e.and(col(column.as_str()).is_not_null()).and(col(column.as_str()).is_not_nan())
+        });
 
-pub(crate) fn group_by_1<E: AsRef<[Expr]>>(
-    df: &DataFrame,
-    group_by_field: &str,
-    step: i64,
-    aggregations: E,
-    offset: i64,
-) -> WapukuResult<DataFrame> {
-    let df = df.sort(
-        [group_by_field],
-        SortMultipleOptions::default().with_order_descending(false),
-    )?;
+        let values_lf = self.df.clone()
+            .lazy()
+            .select(columns.iter().map(|column| col(column.as_str()).cast(DataType::Float64)).collect::<Vec<_>>())
+            .filter(complete);
 
-    let df = df
-        .clone()
-        .lazy()
-        .sort([group_by_field], SortMultipleOptions::default())
-        .group_by_dynamic(
-            col(group_by_field),
-            [],
-            DynamicGroupOptions {
-                index_column: group_by_field.into(),
-                every: Duration::new(step),
-                period: Duration::new(step),
-                offset: Duration::new(offset),
-                include_boundaries: true,
-                closed_window: ClosedWindow::Left,
-                start_by: WindowBound,
-                label: Label::DataPoint,
-            },
-        )
-        .agg(aggregations)
-        // .agg([
-        //     col("property_3").count().alias("volume")
-        // ])
-        .collect()?;
+        let (values_lf, rows) = stride_rows(values_lf, PAIR_PLOT_MAX_POINTS)?;
+        let values_df = values_lf.collect()?;
 
-    let mut df = df.sort(
-        [group_by_field],
-        SortMultipleOptions::default().with_order_descending(false),
-    )?;
+        let values = columns.iter()
+            .map(|column| Ok(values_df.column(column.as_str())?.as_materialized_series().iter().filter_map(any_value_to_f64).collect::<Vec<_>>()))
+            .collect::<Result<Vec<_>, WapukuError>>()?;
 
-    df.rename(group_by_field, "group_by_field".into())
-        .expect("rename group_by_field");
-    // let df = df.clone().lazy().with_column(lit(1).alias("primary_field_group")).collect()?;
-    // df.rename(group_by_field, "secondary_group_field");
+        let histograms = values.iter().map(|values| sample_histogram(values, PAIR_PLOT_BINS)).collect();
 
-    debug!("wapuku: df grouped={:?}", df);
+        Ok(PairPlot::new(frame_id, columns, values, histograms, rows))
+    }
 
-    Ok(df)
-}
+    fn check_quality(&self, frame_id: u128) -> Result<DataQuality, WapukuError> {
+        let rows = self.df.height();
 
-pub(crate) fn group_by_2<E: AsRef<[Expr]>>(
-    df: &DataFrame,
-    primary_group_by_field: &str,
-    primary_step: i64,
-    secondary_group_by_field: &str,
-    secondary_step: i64,
-    aggregations: E,
-    primary_offset: i64,
-    secondary_offset: i64,
-) -> WapukuResult<DataFrame> {
-    let primary_field_value = "primary_field_value";
-    let primary_field_group = "primary_field_group"; //expanded value of the field in the second column group, to be joined back to main frame
+        let exprs = self.df.get_columns().iter().flat_map(|column| {
+            let name = column.name().as_str();
 
-    let primary_field_grouped_and_expanded = df.clone()
-        .lazy()
-        .select(&[
-            col(primary_group_by_field).alias(primary_field_group)
-        ])
-        .sort([primary_field_group], SortMultipleOptions::default())
-        .group_by_dynamic(
-            col(primary_field_group),
-            [],
-            DynamicGroupOptions {
-                index_column: primary_field_group.into(),
-                every: Duration::new(primary_step),
-                period: Duration::new(primary_step),
-                offset: Duration::new(primary_offset),
-                include_boundaries: true,
-                closed_window: ClosedWindow::Left,
-                start_by: WindowBound,
-                label: Label::DataPoint,
-            },
-        ).agg([
-        col(primary_field_group).alias(primary_field_value)
-    ])
-        .explode(
-            cols([primary_field_value]),
-            ExplodeOptions {
-                empty_as_null: false,
-                keep_nulls: false,
-            },
-        )
-        /*.with_row_count("primary_index", None)*/
-        .collect()?; //primary_field_in_group not used, for debug
+            let mut exprs = vec![
+                col(name).null_count().alias(format!("{} null_count", name).as_str()),
+                col(name).drop_nulls().n_unique().alias(format!("{} n_unique", name).as_str()),
+            ];
 
-    debug!(
-        "wapuku: primary_field_grouped_and_expanded={:?}",
-        primary_field_grouped_and_expanded
-    );
-    let mut df = df.sort(
-        [primary_group_by_field],
-        SortMultipleOptions::default().with_order_descending(false),
-    )?;
+            match column.dtype() {
+                DataType::String => {
+                    exprs.push(col(name).str().contains(lit(NUMERIC_STR_PATTERN), true).sum().alias(format!("{} numeric", name).as_str()));
+                    exprs.push(col(name).neq(col(name).str().strip_chars(lit(NULL))).sum().alias(format!("{} whitespace", name).as_str()));
+                    exprs.push(col(name).str().contains(lit(SENTINEL_STR_PATTERN), true).sum().alias(format!("{} sentinel", name).as_str()));
+                }
+                DataType::Date | DataType::Datetime(_, _) => {
+                    exprs.push(
+                        col(name).dt().year().eq(lit(9999))
+                            .or(col(name).dt().year().lt_eq(lit(1900)))
+                            .sum()
+                            .alias(format!("{} sentinel", name).as_str())
+                    );
+                }
+                dtype if dtype.is_numeric() => {
+                    exprs.push(
+                        SENTINEL_NUMBERS.iter()
+                            .fold(lit(false), |e, v| e.or(col(name).cast(DataType::Float64).eq(lit(*v))))
+                            .sum()
+                            .alias(format!("{} sentinel", name).as_str())
+                    );
+                }
+                _ => {}
+            }
 
-    let df = df
-        .with_column(primary_field_grouped_and_expanded.column(primary_field_group).unwrap().clone())?
-        // .with_column(primary_field_grouped_and_expanded.column("primary_index").unwrap().clone())?
-        // .with_column({
-        //     let mut series = primary_field_grouped_and_expanded.column("_lower_boundary").unwrap().clone();
-        //     series.rename("primary_lower_boundary");
-        //     series
-        // })?
-        ;
-    let df = df.sort(
-        [secondary_group_by_field],
-        SortMultipleOptions::default().with_order_descending(false),
-    )?;
+            exprs
+        }).collect::<Vec<Expr>>();
 
-    // let mut df = df.left_join(&primary_field_grouped_and_expanded, [primary_group_by_field], ["primary_field_value"] )?;
+        if exprs.is_empty() {
+            return Ok(DataQuality::new(frame_id, rows, vec![]));
+        }
 
-    debug!("2. wapuku: df={:?}", df);
+        let stats_df = self.df.clone().lazy().select(exprs).collect()?;
+        debug!("check_quality: stats_df shape={:?}", stats_df.shape());
 
-    let mut df = df
-        .clone()
-        .lazy()
-        .sort([secondary_group_by_field], SortMultipleOptions::default())
-        .group_by_dynamic(
-            col(secondary_group_by_field),
-            [col(primary_field_group)],
-            DynamicGroupOptions {
-                index_column: secondary_group_by_field.into(),
-                every: Duration::new(secondary_step),
-                period: Duration::new(secondary_step),
-                offset: Duration::new(secondary_offset),
-                include_boundaries: true,
-                closed_window: ClosedWindow::Left,
-                start_by: WindowBound,
-                label: Label::DataPoint,
-            },
+        let stat = |name: &str, stat: &str| -> Option<usize> {
+            stats_df
+                .column(format!("{} {}", name, stat).as_str()).ok()
+                .and_then(|c| c.get(0).ok())
+                .and_then(any_value_to_u32)
+                .map(|v| v as usize)
+        };
+
+        let mut issues = vec![];
+
+        let distinct_rows = self.df.clone()
+            .lazy()
+            .group_by(self.df.get_column_names().iter().map(|n| col(n.as_str())).collect::<Vec<_>>())
+            .agg([len().alias("wapuku_rows")])
+            .collect()?
+            .height();
+
+        if distinct_rows < rows {
+            issues.push(DataQualityIssue::new(
+                None,
+                DataQualityIssueType::DuplicateRows,
+                rows - distinct_rows,
+                format!("{} distinct of {} rows", distinct_rows, rows),
+            ));
+        }
+
+        for column in self.df.get_columns() {
+            let name = column.name().to_string();
+            let nulls = stat(&name, "null_count").unwrap_or(0);
+            let n_unique = stat(&name, "n_unique").unwrap_or(0);
+            let not_null = rows - nulls;
+
+            if rows > 0 && nulls as f32 / rows as f32 > NULL_SHARE_THRESHOLD {
+                issues.push(DataQualityIssue::new(
+                    Some(name.clone()),
+                    DataQualityIssueType::HighNullShare,
+                    nulls,
+                    format!("{:.1}% nulls", nulls as f32 / rows as f32 * 100.0),
+                ));
+            }
+
+            if rows > 1 && n_unique <= 1 {
+                issues.push(DataQualityIssue::new(
+                    Some(name.clone()),
+                    DataQualityIssueType::Constant,
+                    rows,
+                    if n_unique == 0 { String::from("all values are null") } else { String::from("single value") },
+                ));
+            }
+
+            if let Some(numeric) = stat(&name, "numeric") {
+                if numeric > 0 && numeric < not_null && numeric as f32 / not_null as f32 >= MIXED_TYPES_THRESHOLD {
+                    issues.push(DataQualityIssue::new(
+                        Some(name.clone()),
+                        DataQualityIssueType::MixedTypes,
+                        not_null - numeric,
+                        format!("{} of {} values are not numeric", not_null - numeric, not_null),
+                    ));
+                }
+            }
+
+            if let Some(whitespace) = stat(&name, "whitespace").filter(|v| *v > 0) {
+                issues.push(DataQualityIssue::new(
+                    Some(name.clone()),
+                    DataQualityIssueType::Whitespace,
+                    whitespace,
+                    "leading or trailing whitespace",
+                ));
+            }
+
+            if let Some(sentinel) = stat(&name, "sentinel").filter(|v| *v > 0) {
+                issues.push(DataQualityIssue::new(
+                    Some(name.clone()),
+                    DataQualityIssueType::Sentinel,
+                    sentinel,
+                    match column.dtype() {
+                        DataType::String => String::from("values like -999, 9999-12-31, N/A, null"),
+                        DataType::Date | DataType::Datetime(_, _) => String::from("dates in year 9999 or before 1900"),
+                        _ => format!("values in {:?}", SENTINEL_NUMBERS),
+                    },
+                ));
+            }
+
+            if looks_like_id(&name) && n_unique < not_null {
+                issues.push(DataQualityIssue::new(
+                    Some(name.clone()),
+                    DataQualityIssueType::NonUniqueId,
+                    not_null - n_unique,
+                    format!("{} unique of {} values", n_unique, not_null),
+                ));
+            }
+        }
+
+        Ok(DataQuality::new(frame_id, rows, issues))
+    }
+
+    fn find_outliers(&self, frame_id: u128, method: OutlierMethod) -> Result<Outliers, WapukuError> {
+        let columns = self.df.get_columns().iter()
+            .filter(|c| c.dtype().is_numeric())
+            .map(|c| c.name().to_string())
+            .collect::<Vec<_>>();
+
+        if columns.is_empty() {
+            return Ok(Outliers::new(frame_id, method, vec![]));
+        }
+
+        let stats_df = self.df.clone().lazy().select(
+            columns.iter().flat_map(|name| {
+                let x = col(name.as_str()).cast(DataType::Float64);
+
+                let mut exprs = vec![
+                    x.clone().min().alias(format!("{} min", name).as_str()),
+                    x.clone().max().alias(format!("{} max", name).as_str()),
+                ];
+
+                match method {
+                    OutlierMethod::Iqr => {
+                        exprs.push(x.clone().quantile(lit(0.25), QuantileMethod::Linear).alias(format!("{} center", name).as_str()));
+                        exprs.push(x.clone().quantile(lit(0.75), QuantileMethod::Linear).alias(format!("{} spread", name).as_str()));
+                    }
+                    OutlierMethod::ZScore => {
+                        exprs.push(x.clone().mean().alias(format!("{} center", name).as_str()));
+                        exprs.push(x.clone().std(1).alias(format!("{} spread", name).as_str()));
+                    }
+                    OutlierMethod::ModifiedZScore => {
+                        let median = x.clone().median();
+                        exprs.push(median.clone().alias(format!("{} center", name).as_str()));
+                        exprs.push(
+                            when(x.clone().lt(median.clone()))
+                                .then(median.clone() - x.clone())
+                                .otherwise(x.clone() - median)
+                                .median()
+                                .alias(format!("{} spread", name).as_str())
+                        );
+                    }
+                }
+
+                exprs
+            }).collect::<Vec<_>>()
+        ).collect()?;
+
+        debug!("find_outliers: stats_df shape={:?}", stats_df.shape());
+
+        let stat = |name: &str, stat: &str| -> Option<f64> {
+            stats_df
+                .column(format!("{} {}", name, stat).as_str()).ok()
+                .and_then(|c| c.get(0).ok())
+                .and_then(any_value_to_f64)
+        };
+
+        let threshold = method.threshold();
+        let value_str = "wapuku_value";
+        let row_str = "wapuku_row";
+
+        columns.iter().filter_map(|name| {
+            let (center, spread) = (stat(name, "center")?, stat(name, "spread")?);
+
+            let (lower, upper) = match method {
+                OutlierMethod::Iqr => {
+                    let iqr = spread - center;
+                    (center - threshold * iqr, spread + threshold * iqr)
+                }
+                OutlierMethod::ZScore => {
+                    (center - threshold * spread, center + threshold * spread)
+                }
+                OutlierMethod::ModifiedZScore => {
+                    (center - threshold * spread / MAD_SCALE, center + threshold * spread / MAD_SCALE)
+                }
+            };
+
+            Some((name, lower, upper, stat(name, "min")?, stat(name, "max")?))
+        }).map(|(name, lower, upper, min, max)| -> Result<ColumnOutliers, WapukuError> {
+            let outliers_df = self.df.clone()
+                .lazy()
+                .with_row_index(row_str, None)
+                .select([col(row_str), col(name.as_str()).cast(DataType::Float64).alias(value_str)])
+                .filter(outside_fences(col(value_str), lower, upper))
+                .sort_by_exprs(
+                    [when(col(value_str).lt(lit(lower))).then(lit(lower) - col(value_str)).otherwise(col(value_str) - lit(upper))],
+                    SortMultipleOptions::default().with_order_descending(true),
+                )
+                .collect()?;
+
+            let top_rows = outliers_df.head(Some(OUTLIERS_TOP_ROWS));
+            let top_rows = std::iter::zip(
+                top_rows.column(row_str)?.as_materialized_series().iter(),
+                top_rows.column(value_str)?.as_materialized_series().iter(),
+            )
+            .filter_map(|(row, value)| Some((any_value_to_u32(row)? as usize, any_value_to_f64(value)?)))
+            .collect();
+
+            Ok(ColumnOutliers::new(name.clone(), outliers_df.height(), lower, upper, min, max, top_rows))
+        }).collect::<Result<Vec<_>, WapukuError>>()
+            .map(|columns| Outliers::new(frame_id, method, columns))
+    }
+
+    fn value_counts(&self, frame_id: u128, column: String, sort: ValueCountsSort, offset: usize, limit: usize) -> Result<ValueCounts, WapukuError> {
+        debug!("value_counts column={:?} sort={:?}", column, sort);
+
+        let count_str = "wapuku_count";
+        let value_str = "wapuku_value";
+
+        let (by, descending) = match sort {
+            ValueCountsSort::CountDesc => ([col(count_str), col(column.as_str())], [true, false]),
+            ValueCountsSort::CountAsc => ([col(count_str), col(column.as_str())], [false, false]),
+            ValueCountsSort::ValueAsc => ([col(column.as_str()), col(count_str)], [false, true]),
+            ValueCountsSort::ValueDesc => ([col(column.as_str()), col(count_str)], [true, true]),
+        };
+
+        let counts_df = self.df.clone()
+            .lazy()
+            .group_by([col(column.as_str())])
+            .agg([len().alias(count_str)])
+            .sort_by_exprs(by, SortMultipleOptions::default().with_order_descending_multi(descending).with_nulls_last(true))
+            .select([col(column.as_str()).cast(DataType::String).alias(value_str), col(count_str)])
+            .collect()?;
+
+        debug!("value_counts: counts_df shape={:?}", counts_df.shape());
+
+        let rows = self.df.height().max(1) as f32;
+        let mut cumulative = 0usize;
+
+        let values = std::iter::zip(
+            counts_df.column(value_str)?.as_materialized_series().iter(),
+            counts_df.column(count_str)?.as_materialized_series().iter(),
         )
-        .agg(aggregations)
-        // .agg([
-        //     col("property_3").count().alias("volume")
-        // ])
-        .collect()?;
+        .enumerate()
+        .map_while(|(i, (value, count))| {
+            let count = any_value_to_u32(count).unwrap_or(0) as usize;
+            cumulative += count;
 
-    df.rename(secondary_group_by_field, "secondary_group_field".into())
-        .expect("rename secondary_group_field");
+            if i < offset + limit {
+                Some((i, value, count, cumulative))
+            } else {
+                None
+            }
+        })
+        .skip(offset)
+        .map(|(_, value, count, cumulative)| {
+            ValueCount::new(
+                match value {
+                    AnyValue::Null => None,
+                    value => Some(value.str_value().into_owned()),
+                },
+                count,
+                count as f32 / rows * 100.0,
+                cumulative as f32 / rows * 100.0,
+            )
+        })
+        .collect();
 
-    debug!("wapuku: df grouped={:?}", df);
+        Ok(ValueCounts::new(frame_id, column, sort, offset, counts_df.height(), values))
+    }
 
-    Ok(df)
-}
+    fn pivot(&self, frame_id: u128, row_column: String, column_column: String, value_column: String, agg: PivotAgg) -> Result<Pivot, WapukuError> {
+        debug!("pivot row_column={:?} column_column={:?} value_column={:?} agg={:?}", row_column, column_column, value_column, agg);
+
+        let row_str = "wapuku_row";
+        let column_str = "wapuku_column";
+        let value_str = "wapuku_value";
+        let row_sort_str = "wapuku_row_sort";
+        let column_sort_str = "wapuku_column_sort";
+
+        let agg_of = |value: Expr| match agg {
+            PivotAgg::Count => value.count(),
+            PivotAgg::Sum => value.sum(),
+            PivotAgg::Mean => value.mean(),
+            PivotAgg::Min => value.min(),
+            PivotAgg::Max => value.max(),
+            PivotAgg::NUnique => value.drop_nulls().n_unique(),
+        }
+        .cast(DataType::Float64);
 
-pub fn describe(lf: &LazyFrame) -> PolarsResult<DataFrame> {
-    describe_with_params(
-        lf,
-        true,
-        vec![
-            (
+        //nulls get a key of their own, a real "n/a" value stays apart from them
+        let key_expr = |column: &str| col(column).cast(DataType::String).fill_null(lit(NULL_KEY));
+        let value_expr = match agg {
+            PivotAgg::Count | PivotAgg::NUnique => col(value_column.as_str()),
+            _ => col(value_column.as_str()).cast(DataType::Float64),
+        };
+
+        let keyed_lf = self.df.clone()
+            .lazy()
+            .select([
+                key_expr(row_column.as_str()).alias(row_str),
+                key_expr(column_column.as_str()).alias(column_str),
+                value_expr.alias(value_str),
+                col(row_column.as_str()).alias(row_sort_str),
+                col(column_column.as_str()).alias(column_sort_str),
+            ]);
+
+        //keys in the order of the column values, not of their strings
+        let totals = |key: &str, sort: &str| -> Result<DataFrame, WapukuError> {
+            Ok(keyed_lf.clone()
+                .group_by([col(key)])
+                .agg([agg_of(col(value_str)).alias(value_str), col(sort).first()])
+                .sort_by_exprs([col(sort)], SortMultipleOptions::default().with_nulls_last(true))
+                .collect()?)
+        };
+
+        let keys_and_values = |df: &DataFrame, key: &str| -> Result<Vec<(String, Option<f64>)>, WapukuError> {
+            Ok(std::iter::zip(
+                df.column(key)?.as_materialized_series().iter(),
+                df.column(value_str)?.as_materialized_series().iter(),
+            )
+            .map(|(k, v)| (k.str_value().into_owned(), any_value_to_f64(v)))
+            .collect())
+        };
+
+        let rows_df = totals(row_str, row_sort_str)?;
+        let columns_df = totals(column_str, column_sort_str)?;
+
+        if rows_df.height() > PIVOT_MAX_KEYS || columns_df.height() > PIVOT_MAX_KEYS {
+            return Err(WapukuError::General {
+                msg: format!("too many distinct values for pivot: {} x {}, max {}", rows_df.height(), columns_df.height(), PIVOT_MAX_KEYS),
+            });
+        }
+
+        let row_totals = keys_and_values(&rows_df, row_str)?;
+        let column_totals = keys_and_values(&columns_df, column_str)?;
+        let label = |key: &String| if key == NULL_KEY { String::from(NA) } else { key.clone() };
+
+        let mut pivot = Pivot::new(
+            frame_id,
+            &row_column,
+            &column_column,
+            &value_column,
+            agg,
+            row_totals.iter().map(|(k, _)| label(k)).collect(),
+            column_totals.iter().map(|(k, _)| label(k)).collect(),
+        );
+
+        for (i, (_, total)) in row_totals.iter().enumerate() {
+            if let Some(total) = total {
+                pivot.set_row_total(i, *total);
+            }
+        }
+
+        for (i, (_, total)) in column_totals.iter().enumerate() {
+            if let Some(total) = total {
+                pivot.set_column_total(i, *total);
+            }
+        }
+
+        //combinations without rows stay empty rather than a count of 0
+        let cell_agg = when(element().len().gt(lit(0))).then(agg_of(element())).otherwise(lit(NULL).cast(DataType::Float64));
+
+        let cells_df = keyed_lf.clone()
+            .pivot(
+                cols([column_str]),
+                Arc::new(columns_df.select([column_str])?),
+                cols([row_str]),
+                cols([value_str]),
+                cell_agg,
+                false,
+                PlSmallStr::from_static("_"),
+            )
+            .collect()?;
+
+        let row_index = row_totals.iter().enumerate().map(|(i, (k, _))| (k.clone(), i)).collect::<HashMap<_, _>>();
+        let row_keys = cells_df.column(row_str)?.as_materialized_series().iter().map(|k| row_index.get(k.str_value().as_ref()).copied()).collect::<Vec<_>>();
+
+        for (column, (key, _)) in column_totals.iter().enumerate() {
+            for (row, value) in std::iter::zip(row_keys.iter(), cells_df.column(key.as_str())?.as_materialized_series().iter()) {
+                if let (Some(row), Some(value)) = (row, any_value_to_f64(value)) {
+                    pivot.set_cell(*row, column, value);
+                }
+            }
+        }
+
+        if let Some(total) = keyed_lf
+            .select([agg_of(col(value_str)).alias(value_str)])
+            .collect()?
+            .column(value_str)?
+            .get(0)
+            .ok()
+            .and_then(any_value_to_f64) {
+            pivot.set_total(total);
+        }
+
+        Ok(pivot)
+    }
+
+    fn group_by(&self, _frame_id: u128, keys: Vec<String>, aggs: Vec<(String, GroupByAgg)>) -> Result<FilteredFame, WapukuError> {
+        debug!("group_by keys={:?} aggs={:?}", keys, aggs);
+
+        if keys.is_empty() {
+            return Err(WapukuError::General { msg: String::from("no columns to group by") });
+        }
+
+        let aggs = aggs.iter().map(|(column, agg)| {
+            let c = col(column.as_str());
+            match agg {
+                GroupByAgg::Count => c.count(),
+                GroupByAgg::Sum => c.sum(),
+                GroupByAgg::Mean => c.mean(),
+                GroupByAgg::Median => c.median(),
+                GroupByAgg::Std => c.std(1),
+                GroupByAgg::Quantile { q } => c.quantile(lit(*q), QuantileMethod::Linear),
+                GroupByAgg::First => c.first(),
+                GroupByAgg::Last => c.last(),
+                GroupByAgg::NUnique => c.n_unique(),
+            }
+            .alias(agg.alias(column).as_str())
+        }).collect::<Vec<_>>();
+
+        let key_exprs = keys.iter().map(|k| col(k.as_str())).collect::<Vec<_>>();
+
+        let df = self.df.clone()
+            .lazy()
+            .group_by(key_exprs.clone())
+            .agg(aggs)
+            .sort_by_exprs(key_exprs, SortMultipleOptions::default().with_nulls_last(true))
+            .collect()?;
+
+        Ok(FilteredFame::new(Box::new(PolarsData::new(df, format!("{} by {}", self.name, keys.join(", "))))))
+    }
+}
+
+impl From<Filter> for Expr {
+    fn from(filter: Filter) -> Self {
+        if let Some(conditions) = filter.conditions() {
+            conditions_to_expr(conditions)
+        } else {
+            lit(true)
+        }
+    }
+}
+
+//strictly beyond the fences, the outlier count and its filter condition share it
+fn outside_fences(value: Expr, lower: f64, upper: f64) -> Expr {
+    value.clone().lt(lit(lower)).or(value.gt(lit(upper)))
+}
+
+fn conditions_to_expr(condition: &ConditionType) -> Expr {
+    match condition {
+        ConditionType::Single {
+            column_name,
+            condition,
+        } => match condition {
+            Condition::Numeric { min, max } => col(column_name)
+                .gt_eq(*min)
+                .and(col(column_name).lt_eq(*max)),
+            Condition::String { pattern } => col(column_name)
+                .str()
+                .contains_literal(lit(pattern.to_owned())),
+            Condition::Boolean { val } => col(column_name).eq(*val),
+            Condition::Equals { value } => match value {
+                Some(value) => col(column_name)
+                    .cast(DataType::String)
+                    .eq(lit(value.to_owned())),
+                None => col(column_name).is_null(),
+            },
+            Condition::Range { min, max, closed, .. } => {
+                let physical = col(column_name).to_physical().cast(DataType::Float64);
+                let upper = if *closed {
+                    physical.clone().lt_eq(lit(*max))
+                } else {
+                    physical.clone().lt(lit(*max))
+                };
+
+                physical.gt_eq(lit(*min)).and(upper)
+            }
+            Condition::Fences { lower, upper, outside } => {
+                let outliers = outside_fences(col(column_name).cast(DataType::Float64), *lower, *upper);
+
+                if *outside { outliers } else { outliers.not() }
+            }
+        },
+        ConditionType::Compoiste { conditions, ctype } => {
+            if conditions.len() == 0 {
+                warn!("empty composit condition");
+                lit(true)
+            } else if conditions.len() == 1 {
+                conditions_to_expr(conditions.get(0).unwrap())
+            } else {
+                conditions.into_iter().skip(1).fold(
+                    conditions_to_expr(conditions.get(0).expect("no first condition")),
+                    |a, c| match ctype {
+                        CompositeType::AND => a.and(conditions_to_expr(c)),
+                        CompositeType::OR => a.or(conditions_to_expr(c)),
+                    },
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod filter_test {
+    use crate::model::{Condition, ConditionType, Filter};
+    use crate::polars_df::tests::dummy_filter;
+    use log::debug;
+    use polars::prelude::Expr;
+
+    #[test]
+    pub fn test_string_pattern() {
+        let mut filter = dummy_filter();
+        filter.add_condition(
+            ConditionType::Single {
+                column_name: "property_2".into(),
+                condition: Condition::String {
+                    pattern: "aaaa".into(),
+                },
+            },
+            None,
+        );
+
+        let expr: Expr = filter.into();
+
+        println!("expr={:?}", expr);
+
+        assert_eq!(
+            "col(\"property_2\").str.contains([Utf8(aaaa)])",
+            format!("{:?}", expr)
+        );
+    }
+
+    #[test]
+    pub fn test_num_pattern() {
+        let mut filter = dummy_filter();
+        filter.add_condition(
+            ConditionType::Single {
+                column_name: "property_1".into(),
+                condition: Condition::Numeric {
+                    min: -10.0,
+                    max: 10.0,
+                },
+            },
+            None,
+        );
+
+        let expr: Expr = filter.into();
+
+        println!("expr={:?}", expr);
+
+        assert_eq!(
+            "[([(col(\"property_1\")) >= (-10.0)]) & ([(col(\"property_1\")) <= (10.0)])]",
+            format!("{:?}", expr)
+        );
+    }
+
+    #[test]
+    pub fn test_bool_pattern() {
+        let mut filter = dummy_filter();
+        filter.add_condition(
+            ConditionType::Single {
+                column_name: "property_3".into(),
+                condition: Condition::Boolean { val: true },
+            },
+            None,
+        );
+
+        let expr: Expr = filter.into();
+
+        println!("expr={:?}", expr);
+
+        assert_eq!("[(col(\"property_3\")) == (true)]", format!("{:?}", expr));
+    }
+
+    #[test]
+    pub fn test_and() {
+        let mut filter = dummy_filter();
+        filter.add_condition(
+            ConditionType::Single {
+                column_name: "property_2".into(),
+                condition: Condition::String {
+                    pattern: "aaaa".into(),
+                },
+            },
+            None,
+        );
+        filter.add_condition(
+            ConditionType::Single {
+                column_name: "property_2".into(),
+                condition: Condition::String {
+                    pattern: "bbb".into(),
+                },
+            },
+            None,
+        );
+
+        let expr: Expr = filter.into();
+
+        assert_eq!("[(col(\"property_2\").str.contains([Utf8(aaaa)])) & (col(\"property_2\").str.contains([Utf8(bbb)]))]", format!("{:?}", expr));
+    }
+
+    #[test]
+    pub fn test_edit_top() {
+        let mut filter = dummy_filter();
+        let condition_type_0 = ConditionType::Single {
+            column_name: "property_2".into(),
+            condition: Condition::String {
+                pattern: "aaaa".into(),
+            },
+        };
+
+        filter.add_condition(condition_type_0, None);
+
+        let condition_type_0_ptr = filter.top_condition_ptr().expect("top_condition_ptr");
+
+        let Some(ConditionType::Single {
+            column_name,
+            condition: Condition::String { pattern },
+        }) = filter.conditions()
+        else {
+            panic!("no conditions")
+        };
+
+        assert_eq!(column_name, "property_2");
+        assert_eq!(pattern, "aaaa");
+
+        let condition_type_1 = ConditionType::Single {
+            column_name: "property_2".into(),
+            condition: Condition::String {
+                pattern: "bbb".into(),
+            },
+        };
+
+        filter.add_condition(condition_type_1, Some(condition_type_0_ptr));
+
+        let Some(ConditionType::Single {
+            column_name,
+            condition: Condition::String { pattern },
+        }) = filter.conditions()
+        else {
+            panic!("no conditions")
+        };
+
+        assert_eq!(column_name, "property_2");
+        assert_eq!(pattern, "bbb");
+    }
+
+    #[test]
+    pub fn test_edit_sub_1() {
+        let mut filter = dummy_filter();
+        let condition_type_0 = ConditionType::Single {
+            column_name: "property_1".into(),
+            condition: Condition::String {
+                pattern: "aaaa".into(),
+            },
+        };
+        let condition_type_1 = ConditionType::Single {
+            column_name: "property_2".into(),
+            condition: Condition::String {
+                pattern: "bbbb".into(),
+            },
+        };
+
+        filter.add_condition(condition_type_0, None);
+        filter.add_condition(condition_type_1, None);
+
+        let Some(ConditionType::Compoiste { conditions, ctype }) = filter.conditions() else {
+            panic!("no conditions")
+        };
+        let condition_type_0_f = conditions.get(0).expect("condition_type_1");
+        let condition_type_0_f_ptr = condition_type_0_f as *const _;
+        let condition_type_1_f = conditions.get(1).expect("condition_type_1");
+        let condition_type_1_f_ptr = condition_type_1_f as *const _;
+
+        let condition_type_1 = ConditionType::Single {
+            column_name: "property_2".into(),
+            condition: Condition::String {
+                pattern: "cccc".into(),
+            },
+        };
+
+        filter.add_condition(condition_type_1, Some(condition_type_1_f_ptr));
+
+        let Some(ConditionType::Compoiste { conditions, ctype }) = filter.conditions() else {
+            panic!("no conditions")
+        };
+        let ConditionType::Single {
+            column_name,
+            condition: Condition::String { pattern },
+        } = conditions.get(1).expect("condition_type_1").clone()
+        else {
+            panic!("no condition_type_1")
+        };
+
+        assert_eq!(column_name, "property_2");
+        assert_eq!(pattern, "cccc");
+    }
+}
+
+// impl Drop for PolarsData {
+//     fn drop(&mut self) {
+//         debug!("wapuku: Drop for PolarsData")
+//     }
+// }
+
+fn map_to_wapuku(d_type: &DataType) -> WapukuDataType {
+    //TODO
+    match d_type {
+        DataType::String => WapukuDataType::String,
+
+        DataType::Float64
+        | DataType::Float32
+        | DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64 => WapukuDataType::Numeric,
+
+        DataType::Boolean => WapukuDataType::Boolean,
+
+        DataType::Datetime(..) | DataType::Date => WapukuDataType::Datetime,
+        _ => WapukuDataType::String,
+    }
+}
+
+pub fn load_zip(data: Box<Vec<u8>>) -> Result<Vec<(DataFrame, String)>, WapukuError> {
+    let mut archive = ZipArchive::new(Cursor::new(data.as_slice()))?;
+    archive
+        .file_names()
+        .map(|s| String::from(s))
+        .collect::<Vec<String>>()
+        .into_iter()
+        .map(|file| {
+            let mut bytes = Vec::new();
+            archive.by_name(file.as_str())?.read_to_end(&mut bytes)?;
+
+            if file.ends_with("csv") {
+                load_csv(Box::new(bytes)).map(|df| (df, file))
+            } else if file.ends_with("parquet") {
+                load_parquet(Box::new(bytes)).map(|df| (df, file))
+            } else {
+                Err(WapukuError::DataLoad {
+                    msg: format!("Unexepcted file ending {}", file),
+                })
+            }
+        })
+        .collect::<Result<Vec<(DataFrame, String)>, WapukuError>>()
+}
+
+pub fn load_csv(csv_bytes: Box<Vec<u8>>) -> Result<DataFrame, WapukuError> {
+    CsvReader::new(Cursor::new(csv_bytes.as_slice()))
+        .finish()
+        .map_err(|e| e.into())
+}
+
+#[cfg(feature = "parquet")]
+pub fn load_parquet(parquet_bytes: Box<Vec<u8>>) -> Result<DataFrame, WapukuError> {
+    ParquetReader::new(Cursor::new(parquet_bytes.as_slice()))
+        .finish()
+        .map_err(|e| e.into())
+}
+
+#[cfg(not(feature = "parquet"))]
+pub fn load_parquet(_parquet_bytes: Box<Vec<u8>>) -> Result<DataFrame, WapukuError> {
+    Err(WapukuError::DataLoad {
+        msg: "Parquet loading is not enabled in this build".into(),
+    })
+}
+
+pub(crate) fn group_by_1<E: AsRef<[Expr]>>(
+    df: &DataFrame,
+    group_by_field: &str,
+    step: i64,
+    aggregations: E,
+    offset: i64,
+) -> WapukuResult<DataFrame> {
+    let df = df.sort(
+        [group_by_field],
+        SortMultipleOptions::default().with_order_descending(false),
+    )?;
+
+    let df = df
+        .clone()
+        .lazy()
+        .sort([group_by_field], SortMultipleOptions::default())
+        .group_by_dynamic(
+            col(group_by_field),
+            [],
+            DynamicGroupOptions {
+                index_column: group_by_field.into(),
+                every: Duration::new(step),
+                period: Duration::new(step),
+                offset: Duration::new(offset),
+                include_boundaries: true,
+                closed_window: ClosedWindow::Left,
+                start_by: WindowBound,
+                label: Label::DataPoint,
+            },
+        )
+        .agg(aggregations)
+        // .agg([
+        //     col("property_3").count().alias("volume")
+        // ])
+        .collect()?;
+
+    let mut df = df.sort(
+        [group_by_field],
+        SortMultipleOptions::default().with_order_descending(false),
+    )?;
+
+    df.rename(group_by_field, "group_by_field".into())
+        .expect("rename group_by_field");
+    // let df = df.clone().lazy().with_column(lit(1).alias("primary_field_group")).collect()?;
+    // df.rename(group_by_field, "secondary_group_field");
+
+    debug!("wapuku: df grouped={:?}", df);
+
+    Ok(df)
+}
+
+pub(crate) fn group_by_2<E: AsRef<[Expr]>>(
+    df: &DataFrame,
+    primary_group_by_field: &str,
+    primary_step: i64,
+    secondary_group_by_field: &str,
+    secondary_step: i64,
+    aggregations: E,
+    primary_offset: i64,
+    secondary_offset: i64,
+) -> WapukuResult<DataFrame> {
+    let primary_field_value = "primary_field_value";
+    let primary_field_group = "primary_field_group"; //expanded value of the field in the second column group, to be joined back to main frame
+
+    let primary_field_grouped_and_expanded = df.clone()
+        .lazy()
+        .select(&[
+            col(primary_group_by_field).alias(primary_field_group)
+        ])
+        .sort([primary_field_group], SortMultipleOptions::default())
+        .group_by_dynamic(
+            col(primary_field_group),
+            [],
+            DynamicGroupOptions {
+                index_column: primary_field_group.into(),
+                every: Duration::new(primary_step),
+                period: Duration::new(primary_step),
+                offset: Duration::new(primary_offset),
+                include_boundaries: true,
+                closed_window: ClosedWindow::Left,
+                start_by: WindowBound,
+                label: Label::DataPoint,
+            },
+        ).agg([
+        col(primary_field_group).alias(primary_field_value)
+    ])
+        .explode(
+            cols([primary_field_value]),
+            ExplodeOptions {
+                empty_as_null: false,
+                keep_nulls: false,
+            },
+        )
+        /*.with_row_count("primary_index", None)*/
+        .collect()?; //primary_field_in_group not used, for debug
+
+    debug!(
+        "wapuku: primary_field_grouped_and_expanded={:?}",
+        primary_field_grouped_and_expanded
+    );
+    let mut df = df.sort(
+        [primary_group_by_field],
+        SortMultipleOptions::default().with_order_descending(false),
+    )?;
+
+    let df = df
+        .with_column(primary_field_grouped_and_expanded.column(primary_field_group).unwrap().clone())?
+        // .with_column(primary_field_grouped_and_expanded.column("primary_index").unwrap().clone())?
+        // .with_column({
+        //     let mut series = primary_field_grouped_and_expanded.column("_lower_boundary").unwrap().clone();
+        //     series.rename("primary_lower_boundary");
+        //     series
+        // })?
+        ;
+    let df = df.sort(
+        [secondary_group_by_field],
+        SortMultipleOptions::default().with_order_descending(false),
+    )?;
+
+    // let mut df = df.left_join(&primary_field_grouped_and_expanded, [primary_group_by_field], ["primary_field_value"] )?;
+
+    debug!("2. wapuku: df={:?}", df);
+
+    let mut df = df
+        .clone()
+        .lazy()
+        .sort([secondary_group_by_field], SortMultipleOptions::default())
+        .group_by_dynamic(
+            col(secondary_group_by_field),
+            [col(primary_field_group)],
+            DynamicGroupOptions {
+                index_column: secondary_group_by_field.into(),
+                every: Duration::new(secondary_step),
+                period: Duration::new(secondary_step),
+                offset: Duration::new(secondary_offset),
+                include_boundaries: true,
+                closed_window: ClosedWindow::Left,
+                start_by: WindowBound,
+                label: Label::DataPoint,
+            },
+        )
+        .agg(aggregations)
+        // .agg([
+        //     col("property_3").count().alias("volume")
+        // ])
+        .collect()?;
+
+    df.rename(secondary_group_by_field, "secondary_group_field".into())
+        .expect("rename secondary_group_field");
+
+    debug!("wapuku: df grouped={:?}", df);
+
+    Ok(df)
+}
+
+pub fn describe(lf: &LazyFrame) -> PolarsResult<DataFrame> {
+    describe_with_params(
+        lf,
+        true,
+        vec![
+            (
                 "count".to_owned(),
                 Box::new(|dt: &DataType| dt.is_ord()) as Box<dyn Fn(&DataType) -> bool>,
                 Box::new(|name: &String| {
@@ -1586,287 +3205,956 @@ pub fn describe(lf: &LazyFrame) -> PolarsResult<DataFrame> {
     )
 }
 
-type DescribeAggParam = (
-    String,                         //name
-    Box<dyn Fn(&DataType) -> bool>, //when applicable
-    Box<dyn Fn(&String) -> Expr>,   //aggregation expression
-);
+type DescribeAggParam = (
+    String,                         //name
+    Box<dyn Fn(&DataType) -> bool>, //when applicable
+    Box<dyn Fn(&String) -> Expr>,   //aggregation expression
+);
+
+pub fn describe_with_params(
+    lf: &LazyFrame,
+    fields_to_header: bool,
+    aggs: Vec<DescribeAggParam>,
+    only_columns: Option<Vec<&str>>,
+) -> PolarsResult<DataFrame> {
+    let schema = lf.clone().collect_schema()?;
+    let selected_columns = schema
+        .iter()
+        .map(|(name, dt)| (name.to_string(), dt.clone()))
+        .filter(|(name, _)| {
+            only_columns
+                .as_ref()
+                .map_or(true, |v| v.contains(&name.as_str()))
+        })
+        .collect::<Vec<_>>();
+    let exprs = schema
+        .iter()
+        .map(|(name, dt)| (name.to_string(), dt))
+        .filter(|(name, _)| {
+            only_columns
+                .as_ref()
+                .map_or(true, |v| v.contains(&name.as_str()))
+        })
+        .enumerate()
+        .flat_map(|(i, (name, dt))| {
+            once(lit(name.to_string()).alias(name.as_str()))
+                .chain(aggs.iter().enumerate().map(|(ai, a)| {
+                    if a.1(dt) {
+                        a.2(&name)
+                    } else {
+                        lit(NULL)
+                            .cast(dt.clone())
+                            .alias(format!("{} {}", i, ai).as_str()) //i and ai to keep names unique
+                    }
+                }))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let columns_len = exprs.len();
+
+    let mut summary_df = lf.clone().select(exprs).collect()?;
+
+    let aggs_len = aggs.len() + 1;
+    let summary_df = summary_df.transpose(None, None)?;
+    let values = summary_df.column("column_0")?;
+
+    let mut names = Vec::with_capacity(columns_len / aggs_len);
+    let mut agg_values = aggs
+        .iter()
+        .map(|_| Vec::<Option<String>>::with_capacity(columns_len / aggs_len))
+        .collect::<Vec<_>>();
+
+    for column_idx in 0..(columns_len / aggs_len) {
+        let base_idx = column_idx * aggs_len;
+        names.push(values.get(base_idx)?.str_value().into_owned());
+
+        for (agg_idx, agg_col) in agg_values.iter_mut().enumerate() {
+            let value = values.get(base_idx + agg_idx + 1)?;
+            if value.is_null() {
+                agg_col.push(None);
+            } else {
+                agg_col.push(Some(value.to_string()));
+            }
+        }
+    }
+
+    let height = names.len();
+    let mut out_columns = vec![Series::new("name".into(), names).into()];
+    for (agg, values) in aggs.iter().zip(agg_values) {
+        out_columns.push(Series::new(agg.0.clone().into(), values).into());
+    }
+
+    let mut summary_df = DataFrame::new(height, out_columns)?;
+
+    if !fields_to_header {
+        return summary_df
+            .clone()
+            .lazy()
+            .select(
+                once(col("name"))
+                    .chain(aggs.iter().map(|agg| col(agg.0.as_str()).cast(DataType::Float64)))
+                    .collect::<Vec<Expr>>(),
+            )
+            .collect();
+    }
+
+    summary_df = summary_df.transpose(None, Some(Either::Left("name".to_owned())))?;
+
+    summary_df = summary_df
+        .clone()
+        .lazy()
+        .select(
+            selected_columns
+                .iter()
+                .map(|(name, dt)| {
+                    if dt.is_numeric() {
+                        col(name.as_str()).cast(DataType::Float64)
+                    } else {
+                        col(name.as_str())
+                    }
+                })
+                .collect::<Vec<Expr>>(),
+        )
+        .collect()?;
+
+    summary_df.insert_column(
+        0,
+        Series::new(
+            "describe".into(),
+            aggs.iter().map(|q| q.0.clone()).collect::<Vec<String>>(),
+        )
+        .into(),
+    )?;
+
+    Ok(summary_df)
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    #![feature(async_fn_in_trait)]
+
+    use std::collections::HashSet;
+    use std::fs::File;
+    use std::iter;
+
+    use log::debug;
+    use polars::datatypes::AnyValue::List;
+    use polars::df;
+    use polars::export::arrow::compute::filter::filter;
+    use polars::export::arrow::io::ipc::read::FileReader;
+    use polars::export::chrono;
+    use polars::export::chrono::{NaiveDate, NaiveDateTime};
+    use polars::prelude::LiteralValue::DateTime;
+    use polars::prelude::*;
+    // use polars::io::prelude::utils::
+    use polars::io::mmap::MmapBytesReader;
+
+    use crate::data_type::{WapukuDataType, WapukuDataValues};
+    use crate::model::{
+        ColumnPlotSeries, CompositeType, Condition, ConditionType, CorrMeasure, CorrMethod, Data, DataGroup, DataProperty, DataQualityIssueType, Filter, Binning, CalendarUnit, GroupByAgg, GroupsGrid, Histogram, HistogramParams, HistogramScale, Bandwidth, OutlierMethod, PivotAgg, PivotNormalization, ResampleAgg, ScatterParams, TimeSeriesParams, ValueCountsSort,
+        NumericColumnSummary, Property, PropertyRange, StringColumnSummary, Summary, SummaryColumn,
+        SummaryColumnType, WaFrame,
+    };
+    use crate::polars_df::{box_group, describe, describe_with_params, group_by_2, PolarsData};
+    use crate::tests::init_log;
+
+    #[ctor::ctor]
+    fn init() {
+        std::env::set_var("POLARS_FMT_MAX_ROWS", "100");
+        std::env::set_var("FMT_MAX_COLS", "1000");
+
+        init_log();
+    }
+
+    #[test]
+    fn test_build_summary_ints() {
+        let df = df!(
+            "property_1" => &[1,   2,   3,   1,   2,   3,   1,   2,   3,],
+            "property_2" => &[10,  10,  10,  20,  20,  20,  30,  30,  30,],
+            "property_3" => &[11,  12,  13,  21,  22,  23,  31,  32,  33,]
+        )
+        .unwrap();
+        let mut data = PolarsData::new(df, String::from("test"));
+
+        let summary = data.build_summary(0, None);
+
+        check_numeric_column(&summary, 0, "1.0", "2.0", "3.0");
+        check_numeric_column(&summary, 1, "10.0", "20.0", "30.0");
+    }
+
+    #[test]
+    fn test_build_summary_dates() {
+        let date_series = DatetimeChunked::from_naive_datetime(
+            "days",
+            vec![
+                chrono::NaiveDateTime::parse_from_str("2023-01-01 00:00:01", "%Y-%m-%d %H:%M:%S")
+                    .unwrap(),
+                chrono::NaiveDateTime::parse_from_str("2023-01-15 00:00:01", "%Y-%m-%d %H:%M:%S")
+                    .unwrap(),
+                chrono::NaiveDateTime::parse_from_str("2023-02-01 00:00:02", "%Y-%m-%d %H:%M:%S")
+                    .unwrap(),
+                chrono::NaiveDateTime::parse_from_str("2023-02-15 00:00:02", "%Y-%m-%d %H:%M:%S")
+                    .unwrap(),
+                chrono::NaiveDateTime::parse_from_str("2023-03-01 00:00:02", "%Y-%m-%d %H:%M:%S")
+                    .unwrap(),
+                chrono::NaiveDateTime::parse_from_str("2023-03-15 00:00:02", "%Y-%m-%d %H:%M:%S")
+                    .unwrap(),
+            ],
+            TimeUnit::Milliseconds,
+        )
+        .into_series();
+        // let int_series = Int64Chunked::from_vec("ints", vec![1, 2, 3, 4, 5, 6]).into_series();
+
+        debug!("date_series={:?}", date_series.dtype());
+
+        let df = DataFrame::new(vec![date_series]).unwrap();
+
+        // let df = ParquetReader::new(File::open("../wapuku-egui/www/data/userdata1.parquet").unwrap()).finish().unwrap();
+
+        let mut data = PolarsData::new(df, String::from("test"));
+
+        let summary = data.build_summary(0, None);
+
+        println!("summary={:?}", summary);
+
+        if let SummaryColumnType::Datetime { data } = summary.columns()[0].dtype() {
+        } else {
+            panic!("column is not date: {:?}", summary.columns()[0].dtype())
+        }
+
+        // check_numeric_column(&summary, 0, "1.0", "2.0", "3.0");
+        // check_numeric_column(&summary, 1, "10.0", "20.0", "30.0");
+    }
+
+    #[test]
+    fn test_build_histogram_str() {
+        let df = df!(
+            "property_1" => &[1i32,   2i32,    3i32,  1i32,   2i32,    3i32,    1i32,    2i32,   3i32],
+            "property_2" => &[1f32,   2f32,    3f32,  1f32,    2f32,   3f32,    1f32,    2f32,   3f32],
+            "property_3" => &["A",  "B",  "C",  "A",  "B",  "C",  "C",  "B", "C"]
+        ).unwrap();
+
+        let mut data = PolarsData::new(df, String::from("test"));
+
+        let histogram = data
+            .build_histogram(0u128, String::from("property_3"), HistogramParams::default())
+            .expect("build_histogram");
+
+        let y = histogram.values();
+        println!("y={:?}", y);
+        assert_eq!(y.get(0).unwrap(), &(String::from("C"), 4));
+        assert_eq!(y.get(1).unwrap(), &(String::from("B"), 3));
+        assert_eq!(y.get(2).unwrap(), &(String::from("A"), 2));
+    }
+
+    #[test]
+    fn test_build_histogram_str_top_n() {
+        let df = df!(
+            "property_1" => &[Some("A"), Some("B"), Some("C"), Some("D"), Some("A"), Some("B"), Some("A"), None, None]
+        ).unwrap();
+
+        let data = PolarsData::new(df, String::from("test"));
+
+        let histogram = data
+            .build_histogram(0u128, String::from("property_1"), HistogramParams::new(Binning::Count { bins: 10 }, None, true).with_top_n(2))
+            .expect("build_histogram");
+
+        assert_eq!(
+            histogram.values(),
+            &vec![
+                (String::from("A"), 3),
+                (String::from("B"), 2),
+                (String::from("Other (2 values)"), 2),
+                (String::from("n/a"), 2),
+            ]
+        );
+
+        let histogram = data
+            .build_histogram(0u128, String::from("property_1"), HistogramParams::default().with_top_n(2))
+            .expect("build_histogram");
+
+        assert_eq!(
+            histogram.values(),
+            &vec![
+                (String::from("A"), 3),
+                (String::from("B"), 2),
+                (String::from("Other (2 values)"), 2),
+            ]
+        );
+        assert!(histogram.is_selectable(1) && !histogram.is_selectable(2));
+    }
+
+    #[test]
+    fn test_build_histogram_top_n_split_nulls() {
+        let df = df!(
+            "kind" => &[Some("A"), Some("A"), Some("A"), Some("B"), Some("C"), None, None],
+            "country" => &["NO", "NO", "IT", "IT", "NO", "NO", "IT"]
+        ).unwrap();
+
+        let data = PolarsData::new(df, String::from("test"));
+
+        let histogram = data
+            .build_histogram(0u128, String::from("kind"), HistogramParams::new(Binning::Count { bins: 10 }, None, true).with_top_n(1).with_split(Some(String::from("country"))))
+            .expect("build_histogram");
+
+        assert_eq!(
+            histogram.values(),
+            &vec![(String::from("A"), 3), (String::from("Other (2 values)"), 2), (String::from("n/a"), 2)]
+        );
+        assert_eq!(
+            histogram.series(),
+            &vec![
+                (String::from("NO"), vec![2, 1, 1]),
+                (String::from("IT"), vec![1, 1, 1]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_date_histogram_f32() {
+        let df = DataFrame::new(vec![DatetimeChunked::from_naive_datetime(
+            "registration_dttm",
+            vec![
+                chrono::NaiveDateTime::parse_from_str("2023-01-01 00:00:01", "%Y-%m-%d %H:%M:%S")
+                    .unwrap(),
+                chrono::NaiveDateTime::parse_from_str("2023-01-15 00:00:01", "%Y-%m-%d %H:%M:%S")
+                    .unwrap(),
+                chrono::NaiveDateTime::parse_from_str("2023-02-01 00:00:02", "%Y-%m-%d %H:%M:%S")
+                    .unwrap(),
+                chrono::NaiveDateTime::parse_from_str("2023-02-15 00:00:02", "%Y-%m-%d %H:%M:%S")
+                    .unwrap(),
+                chrono::NaiveDateTime::parse_from_str("2023-03-01 00:00:02", "%Y-%m-%d %H:%M:%S")
+                    .unwrap(),
+                chrono::NaiveDateTime::parse_from_str("2023-03-15 00:00:02", "%Y-%m-%d %H:%M:%S")
+                    .unwrap(),
+            ],
+            TimeUnit::Milliseconds,
+        )
+        .into_series()])
+        .unwrap();
+
+        // let df = ParquetReader::new(File::open("../wapuku-egui/www/data/userdata1.parquet").unwrap()).finish().unwrap();
+
+        println!("{:?}", df);
+
+        let mut data = PolarsData::new(df, String::from("test"));
+
+        let summary = data.build_summary(0u128, None);
+        println!("summary={:?}", summary);
+
+        let histogram = data
+            .build_histogram(0u128, String::from("registration_dttm"), HistogramParams::default())
+            .expect("build_histogram");
+
+        println!("histogram={:?}", histogram);
+        assert_eq!(histogram.values()[0].1, 1);
+    }
+
+    #[test]
+    fn test_kde() {
+        let df = df!(
+            "amount" => &[1.0f64, 2.0, 3.0, 4.0, 5.0, 10.0, 11.0, 12.0, 13.0],
+            "country" => &["NO", "NO", "NO", "NO", "NO", "IT", "IT", "IT", "IT"]
+        ).unwrap();
+
+        let data = PolarsData::new(df, String::from("test"));
+
+        let kde = data.kde(0u128, String::from("amount"), Bandwidth::Manual { h: 1.0 }, None).expect("kde");
+        assert_eq!(kde.series().len(), 1);
+        assert_eq!(kde.series()[0].name(), "amount (h=1.000)");
+        assert_eq!(kde.series()[0].points().first().unwrap().0, -2.0);
+        assert_eq!(kde.series()[0].points().last().unwrap().0, 16.0);
+        assert_eq!(kde.sample_size(), None);
+
+        let kde = data.kde(0u128, String::from("amount"), Bandwidth::Silverman, Some(String::from("country"))).expect("kde");
+        assert_eq!(kde.series().len(), 2);
+        assert!(kde.series()[0].name().starts_with("NO (h="));
+        assert!(kde.series()[1].name().starts_with("IT (h="));
+
+        let peak = |i: usize| kde.series()[i].points().iter().copied().max_by(|a, b| a.1.total_cmp(&b.1)).unwrap().0;
+        assert!(peak(0) < 6.0);
+        assert!(peak(1) > 9.0);
+
+        assert!(data.kde(0u128, String::from("country"), Bandwidth::Scott, None).is_err());
+    }
+
+    #[test]
+    fn test_build_scatter() {
+        let df = df!(
+            "property_1" => &[Some(1.0), Some(2.0), Some(3.0), Some(4.0), None,      Some(6.0)],
+            "property_2" => &[Some(10),  Some(20),  Some(30),  Some(40),  Some(50),  None],
+            "property_3" => &["a",       "b",       "a",       "b",       "a",       "a"],
+            "property_4" => &[1.0,       2.0,       3.0,       4.0,       5.0,       6.0]
+        ).unwrap();
+
+        let polars_data = PolarsData::new(df, String::from("test"));
+
+        let params = ScatterParams::new("property_1".into(), "property_2".into())
+            .with_color_by(Some("property_3".into()))
+            .with_size_by(Some("property_4".into()));
+        let scatter = polars_data.build_scatter(0, params).expect("scatter");
+
+        assert_eq!(scatter.rows(), 4);
+        assert!(!scatter.is_sampled());
+        assert_eq!(scatter.size_range(), Some((1.0, 4.0)));
+
+        let a = scatter.series().iter().find(|s| s.name() == "a").expect("a");
+        assert_eq!(a.points(), &vec![[1.0, 10.0], [3.0, 30.0]]);
+        assert_eq!(a.sizes(), &vec![1.0, 3.0]);
+
+        assert!(scatter.categoric_columns().contains(&"property_3".to_string()));
+        assert!(!scatter.numeric_columns().contains(&"property_3".to_string()));
+
+        assert!(polars_data.build_scatter(0, ScatterParams::new("property_1".into(), "property_3".into())).is_err());
+    }
+
+    #[test]
+    fn test_build_scatter_sampling() {
+        let df = df!(
+            "property_1" => &(0..12_000).map(|i| i as f64).collect::<Vec<_>>(),
+            "property_2" => &(0..12_000).map(|i| (i % 7) as f64).collect::<Vec<_>>()
+        ).unwrap();
+
+        let polars_data = PolarsData::new(df, String::from("test"));
+        let scatter = polars_data.build_scatter(0, ScatterParams::new("property_1".into(), "property_2".into())).expect("scatter");
+
+        assert_eq!(scatter.rows(), 12_000);
+        assert!(scatter.is_sampled());
+        assert_eq!(scatter.shown(), 4_000);
+        assert_eq!(scatter.size_range(), None);
+        assert_eq!(scatter.series()[0].points()[1], [3.0, 3.0]);
+    }
 
-pub fn describe_with_params(
-    lf: &LazyFrame,
-    fields_to_header: bool,
-    aggs: Vec<DescribeAggParam>,
-    only_columns: Option<Vec<&str>>,
-) -> PolarsResult<DataFrame> {
-    let schema = lf.clone().collect_schema()?;
-    let selected_columns = schema
-        .iter()
-        .map(|(name, dt)| (name.to_string(), dt.clone()))
-        .filter(|(name, _)| {
-            only_columns
-                .as_ref()
-                .map_or(true, |v| v.contains(&name.as_str()))
-        })
-        .collect::<Vec<_>>();
-    let exprs = schema
-        .iter()
-        .map(|(name, dt)| (name.to_string(), dt))
-        .filter(|(name, _)| {
-            only_columns
-                .as_ref()
-                .map_or(true, |v| v.contains(&name.as_str()))
-        })
-        .enumerate()
-        .flat_map(|(i, (name, dt))| {
-            once(lit(name.to_string()).alias(name.as_str()))
-                .chain(aggs.iter().enumerate().map(|(ai, a)| {
-                    if a.1(dt) {
-                        a.2(&name)
-                    } else {
-                        lit(NULL)
-                            .cast(dt.clone())
-                            .alias(format!("{} {}", i, ai).as_str()) //i and ai to keep names unique
-                    }
-                }))
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>();
+    #[test]
+    fn test_box_group_extremes() {
+        let sorted = iter::repeat(0.0).take(5_000).chain((1..=2_000).map(|v| v as f64)).collect::<Vec<_>>();
 
-    let columns_len = exprs.len();
+        let group = box_group(String::from("all"), &sorted).expect("box_group");
 
-    let mut summary_df = lf.clone().select(exprs).collect()?;
+        assert_eq!(group.outlier_count(), 2_000);
+        assert_eq!(group.outliers().len(), 1_000);
+        assert_eq!(group.outliers().first(), Some(&1.0));
+        assert_eq!(group.outliers().last(), Some(&2_000.0));
+    }
 
-    let aggs_len = aggs.len() + 1;
-    let summary_df = summary_df.transpose(None, None)?;
-    let values = summary_df.column("column_0")?;
+    #[test]
+    fn test_build_box_plot() {
+        let df = df!(
+            "property_1" => &[Some(1.0f64), Some(2.0), Some(3.0), Some(4.0), Some(5.0), Some(6.0), Some(7.0), Some(8.0), Some(9.0), Some(100.0), Some(10.0), Some(20.0), Some(30.0), None],
+            "property_2" => &["a", "a", "a", "a", "a", "a", "a", "a", "a", "a", "b", "b", "b", "b"]
+        ).unwrap();
 
-    let mut names = Vec::with_capacity(columns_len / aggs_len);
-    let mut agg_values = aggs
-        .iter()
-        .map(|_| Vec::<Option<String>>::with_capacity(columns_len / aggs_len))
-        .collect::<Vec<_>>();
+        let polars_data = PolarsData::new(df, String::from("test"));
 
-    for column_idx in 0..(columns_len / aggs_len) {
-        let base_idx = column_idx * aggs_len;
-        names.push(values.get(base_idx)?.str_value().into_owned());
+        let box_plot = polars_data.build_box_plot(0, "property_1".into(), None).expect("build_box_plot");
+        assert_eq!(box_plot.groups().len(), 1);
+        assert_eq!(box_plot.groups()[0].name(), "property_1");
+        assert_eq!(box_plot.groups()[0].n(), 13);
+        assert_eq!(box_plot.categoric_columns(), &vec![String::from("property_2")]);
 
-        for (agg_idx, agg_col) in agg_values.iter_mut().enumerate() {
-            let value = values.get(base_idx + agg_idx + 1)?;
-            if value.is_null() {
-                agg_col.push(None);
-            } else {
-                agg_col.push(Some(value.to_string()));
-            }
+        let box_plot = polars_data.build_box_plot(0, "property_1".into(), Some("property_2".into())).expect("build_box_plot");
+        let (a, b) = (&box_plot.groups()[0], &box_plot.groups()[1]);
+
+        assert_eq!((a.name(), a.n()), ("a", 10));
+        assert_eq!((a.q1(), a.median(), a.q3()), (3.25, 5.5, 7.75));
+        assert_eq!(a.whiskers(), (1.0, 9.0));
+        assert_eq!((a.outliers(), a.outlier_count()), (&vec![100.0], 1));
+        assert!(!a.density().is_empty());
+
+        assert_eq!((b.name(), b.n()), ("b", 3));
+        assert_eq!((b.q1(), b.median(), b.q3()), (15.0, 20.0, 25.0));
+        assert_eq!(b.whiskers(), (10.0, 30.0));
+        assert!(b.outliers().is_empty());
+
+        assert!(polars_data.build_box_plot(0, "property_2".into(), None).is_err());
+    }
+
+    #[test]
+    fn test_build_time_series() {
+        let ts = ["2023-01-01 00:00:00", "2023-01-01 12:00:00", "2023-01-02 00:00:00", "2023-01-03 00:00:00"]
+            .iter()
+            .map(|d| chrono::NaiveDateTime::parse_from_str(d, "%Y-%m-%d %H:%M:%S").unwrap())
+            .collect::<Vec<_>>();
+
+        let df = df!(
+            "ts" => ts,
+            "amount" => &[1.0f64, 2.0, 3.0, 4.0],
+            "city" => &["Oslo", "Rome", "Oslo", "Rome"]
+        ).unwrap();
+
+        let data = PolarsData::new(df, String::from("test"));
+
+        let params = TimeSeriesParams::new("ts".into(), vec!["amount".into()])
+            .with_unit(Some(CalendarUnit::Day))
+            .with_agg(ResampleAgg::Sum)
+            .with_rolling(Some(2));
+        let time_series = data.build_time_series(0, params).expect("build_time_series");
+
+        let day = 24.0 * 60.0 * 60.0 * 1000.0;
+        let start = 1_672_531_200_000.0; //2023-01-01
+
+        assert_eq!(time_series.unit(), CalendarUnit::Day);
+        assert_eq!(time_series.series()[0].name(), "sum(amount)");
+        assert_eq!(time_series.series()[0].points(), &vec![(start, 3.0), (start + day, 3.0), (start + 2.0 * day, 4.0)]);
+        assert_eq!(time_series.rolling()[0].name(), "sum(amount) rolling mean 2");
+        assert_eq!(time_series.rolling()[0].points(), &vec![(start, 3.0), (start + day, 3.0), (start + 2.0 * day, 3.5)]);
+        assert_eq!(time_series.datetime_columns(), &vec![String::from("ts")]);
+        assert_eq!(time_series.numeric_columns(), &vec![String::from("amount")]);
+
+        let rows = data.build_time_series(0, TimeSeriesParams::new("ts".into(), vec![])).expect("build_time_series");
+        assert_eq!(rows.unit(), CalendarUnit::Hour);
+        assert_eq!(rows.series()[0].name(), "rows");
+        assert_eq!(rows.series()[0].points().len(), 49);
+        assert_eq!(rows.series()[0].points().iter().map(|(_, y)| *y).sum::<f64>(), 4.0);
+        assert!(rows.rolling().is_empty());
+
+        assert!(data.build_time_series(0, TimeSeriesParams::new("amount".into(), vec![])).is_err());
+        assert!(data.build_time_series(0, TimeSeriesParams::new("ts".into(), vec!["city".into()])).is_err());
+    }
+
+    #[test]
+    fn test_build_time_series_gaps() {
+        let ts = ["2023-01-01 00:00:00", "2023-01-03 00:00:00"]
+            .iter()
+            .map(|d| chrono::NaiveDateTime::parse_from_str(d, "%Y-%m-%d %H:%M:%S").unwrap())
+            .collect::<Vec<_>>();
+
+        let df = df!(
+            "ts" => ts,
+            "amount" => &[2.0f64, 4.0]
+        ).unwrap();
+
+        let data = PolarsData::new(df, String::from("test"));
+
+        let day = 24.0 * 60.0 * 60.0 * 1000.0;
+        let start = 1_672_531_200_000.0; //2023-01-01
+
+        let params = |agg: ResampleAgg| TimeSeriesParams::new("ts".into(), vec!["amount".into()])
+            .with_unit(Some(CalendarUnit::Day))
+            .with_agg(agg)
+            .with_rolling(Some(2));
+
+        //the empty day counts as 0 for sums, and is skipped by the rolling mean of means
+        let sums = data.build_time_series(0, params(ResampleAgg::Sum)).expect("build_time_series");
+        assert_eq!(sums.series()[0].points(), &vec![(start, 2.0), (start + day, 0.0), (start + 2.0 * day, 4.0)]);
+        assert_eq!(sums.rolling()[0].points(), &vec![(start, 2.0), (start + day, 1.0), (start + 2.0 * day, 2.0)]);
+
+        let means = data.build_time_series(0, params(ResampleAgg::Mean)).expect("build_time_series");
+        assert_eq!(means.series()[0].points(), &vec![(start, 2.0), (start + 2.0 * day, 4.0)]);
+        assert_eq!(means.rolling()[0].points(), &vec![(start, 2.0), (start + day, 2.0), (start + 2.0 * day, 4.0)]);
+    }
+
+    #[test]
+    fn test_build_pair_plot() {
+        let df = df!(
+            "property_1" => &(1..=10).map(|i| i as f64).collect::<Vec<_>>(),
+            "property_2" => &(1..=10).map(|i| 2 * i).collect::<Vec<_>>(),
+            "property_3" => &["a", "b", "c", "d", "e", "f", "g", "h", "i", "j"],
+            "property_4" => &[Some(1.0f64), Some(1.0), Some(1.0), None, Some(1.0), Some(1.0), Some(1.0), Some(1.0), Some(1.0), Some(f64::NAN)]
+        ).unwrap();
+
+        let data = PolarsData::new(df, String::from("test"));
+        let pair_plot = data.build_pair_plot(0, vec!["property_1".into(), "property_2".into(), "property_3".into(), "property_4".into()]).expect("build_pair_plot");
+
+        assert_eq!(pair_plot.columns(), &vec![String::from("property_1"), String::from("property_2"), String::from("property_4")]);
+        assert_eq!(pair_plot.rows(), 8);
+        assert!(!pair_plot.is_sampled());
+        assert_eq!(pair_plot.points(0, 1)[3], [5.0, 10.0]);
+        assert_eq!(pair_plot.histograms()[0].len(), 20);
+        assert_eq!(pair_plot.histograms()[0].iter().map(|(_, _, count)| count).sum::<u32>(), 8);
+        assert_eq!(pair_plot.histograms()[2], vec![(1.0, 1.0, 8)]);
+
+        assert!(data.build_pair_plot(0, vec!["property_1".into(), "property_3".into()]).is_err());
+
+        let many = (0..9).map(|i| (format!("property_{}", i), (1..=10).map(|v| v as f64).collect::<Vec<_>>()))
+            .map(|(name, values)| Series::new(name.as_str().into(), values).into())
+            .collect::<Vec<Column>>();
+        let many_data = PolarsData::new(DataFrame::new(10, many).unwrap(), String::from("test"));
+        assert!(many_data.build_pair_plot(0, (0..9).map(|i| format!("property_{}", i)).collect()).is_err());
+
+        let df = df!(
+            "property_1" => &(0..5_000).map(|i| i as f64).collect::<Vec<_>>(),
+            "property_2" => &(0..5_000).map(|i| (i % 7) as f64).collect::<Vec<_>>()
+        ).unwrap();
+
+        let pair_plot = PolarsData::new(df, String::from("test")).build_pair_plot(0, vec!["property_1".into(), "property_2".into()]).expect("build_pair_plot");
+        assert!(pair_plot.is_sampled());
+        assert_eq!(pair_plot.shown(), 1_667);
+        assert_eq!(pair_plot.points(0, 1)[1], [3.0, 3.0]);
+    }
+
+    #[test]
+    fn test_plot_columns_downsampling() {
+        let df = df!(
+            "property_1" => &(0..100_000).map(|i| if i == 54_321 { 1e6 } else { (i % 10) as f64 }).collect::<Vec<_>>(),
+            "property_2" => &(0..100_000).map(|i| i as f64 * 0.5).collect::<Vec<_>>()
+        ).unwrap();
+
+        let polars_data = PolarsData::new(df.head(Some(200)), String::from("test"));
+        let plot = polars_data.plot_columns(0, vec!["property_1".into()], 1_000).expect("plot_columns");
+
+        assert_eq!(plot.series()[0].points().len(), 200);
+        assert_eq!(plot.series()[0].downsampled_from(), None);
+
+        let polars_data = PolarsData::new(df, String::from("test"));
+        let plot = polars_data.plot_columns(0, vec!["property_1".into(), "property_2".into()], 1_000).expect("plot_columns");
+
+        for series in plot.series() {
+            assert!(series.points().len() <= 1_000);
+            assert_eq!(series.downsampled_from(), Some(100_000));
         }
+        assert!(plot.series()[0].points().contains(&(54_321.0, 1e6)));
+        assert_eq!(plot.series()[0].points().last(), Some(&(99_999.0, 9.0)));
     }
 
-    let height = names.len();
-    let mut out_columns = vec![Series::new("name".into(), names).into()];
-    for (agg, values) in aggs.iter().zip(agg_values) {
-        out_columns.push(Series::new(agg.0.clone().into(), values).into());
+    #[test]
+    fn test_build_histogram_2d_edges() {
+        let on_edge = 3.0 * (7.0f64 / 5.0); //third edge of 5 bins over 0..7, divides to just under 3
+        let df = df!(
+            "amount" => &[0.0f64, on_edge, 7.0],
+            "qty" => &[1.0f64, 1.0, 2.0]
+        ).unwrap();
+
+        let data = PolarsData::new(df, String::from("test"));
+
+        let histogram = data.build_histogram_2d(0u128, String::from("amount"), String::from("qty"), 5, 1).expect("build_histogram_2d");
+        assert_eq!((histogram.count(2, 0), histogram.count(3, 0)), (0, 1));
+
+        //the cell and its filter agree on the row
+        let mut filter = dummy_filter();
+        filter.add_condition(histogram.to_condition(3, 0).expect("condition"), None);
+        let filtered_frame = data.apply_filter(0u128, filter).expect("apply_filter");
+        assert_eq!(filtered_frame.data().build_summary(0u128, None).shape(), "(1, 2)");
     }
 
-    let mut summary_df = DataFrame::new(height, out_columns)?;
+    #[test]
+    fn test_build_histogram_2d() {
+        let df = df!(
+            "amount" => &[1.0f64, 2.0, 3.0, 4.0, 4.0],
+            "qty" => &[10i64, 10, 20, 20, 15]
+        ).unwrap();
 
-    if !fields_to_header {
-        return summary_df
-            .clone()
-            .lazy()
-            .select(
-                once(col("name"))
-                    .chain(aggs.iter().map(|agg| col(agg.0.as_str()).cast(DataType::Float64)))
-                    .collect::<Vec<Expr>>(),
-            )
-            .collect();
+        let data = PolarsData::new(df, String::from("test"));
+
+        let histogram = data.build_histogram_2d(0u128, String::from("amount"), String::from("qty"), 2, 2).expect("build_histogram_2d");
+
+        assert_eq!(histogram.labels_x(), &vec![String::from("[1.00, 2.50)"), String::from("[2.50, 4.00]")]);
+        assert_eq!(histogram.labels_y(), &vec![String::from("[10.00, 15.00)"), String::from("[15.00, 20.00]")]);
+        assert_eq!((histogram.count(0, 0), histogram.count(1, 0), histogram.count(0, 1), histogram.count(1, 1)), (2, 0, 0, 3));
+        assert_eq!(histogram.max_count(), 3);
+
+        let mut filter = dummy_filter();
+        filter.add_condition(histogram.to_condition(1, 1).expect("condition"), None);
+        let filtered_frame = data.apply_filter(0u128, filter).expect("apply_filter");
+        assert_eq!(filtered_frame.data().build_summary(0u128, None).shape(), "(3, 2)");
+
+        let mut filter = dummy_filter();
+        filter.add_condition(histogram.to_condition(0, 0).expect("condition"), None);
+        let filtered_frame = data.apply_filter(0u128, filter).expect("apply_filter");
+        assert_eq!(filtered_frame.data().build_summary(0u128, None).shape(), "(2, 2)");
+
+        assert!(histogram.to_condition(2, 0).is_none());
+        assert!(data.build_histogram_2d(0u128, String::from("amount"), String::from("missing"), 2, 2).is_err());
     }
 
-    summary_df = summary_df.transpose(None, Some(Either::Left("name".to_owned())))?;
+    #[test]
+    fn test_histogram_selection() {
+        let df = df!(
+            "amount" => &[Some(1.0f64), Some(1.5), Some(2.0), Some(4.5), None],
+            "country" => &[Some("NO"), Some("IT"), Some("NO"), None, Some("SE")]
+        ).unwrap();
 
-    summary_df = summary_df
-        .clone()
-        .lazy()
-        .select(
-            selected_columns
-                .iter()
-                .map(|(name, dt)| {
-                    if dt.is_numeric() {
-                        col(name.as_str()).cast(DataType::Float64)
-                    } else {
-                        col(name.as_str())
-                    }
-                })
-                .collect::<Vec<Expr>>(),
-        )
-        .collect()?;
+        let data = PolarsData::new(df, String::from("test"));
 
-    summary_df.insert_column(
-        0,
-        Series::new(
-            "describe".into(),
-            aggs.iter().map(|q| q.0.clone()).collect::<Vec<String>>(),
-        )
-        .into(),
-    )?;
+        let filtered_rows = |histogram: &Histogram| {
+            let mut filter = dummy_filter();
+            filter.add_condition(histogram.selection_condition().expect("condition"), None);
+            data.apply_filter(0u128, filter).expect("apply_filter").data().build_summary(0u128, None).shape()
+        };
 
-    Ok(summary_df)
-}
+        let mut histogram = data
+            .build_histogram(0u128, String::from("amount"), HistogramParams::new(Binning::Edges { edges: vec![0.0, 2.0, 6.0] }, None, true))
+            .expect("build_histogram");
 
-#[cfg(test)]
-pub(super) mod tests {
-    #![feature(async_fn_in_trait)]
+        assert_eq!(histogram.toggle_selected(0, false), vec![0]);
+        histogram.set_selected(vec![0]);
+        assert_eq!(histogram.toggle_selected(0, false), Vec::<usize>::new());
+        assert_eq!(histogram.toggle_selected(2, true), vec![0, 2]);
+        assert_eq!(filtered_rows(&histogram), "(2, 2)");
 
-    use std::collections::HashSet;
-    use std::fs::File;
-    use std::iter;
+        histogram.set_selected(histogram.toggle_selected(2, true));
+        assert_eq!(filtered_rows(&histogram), "(3, 2)");
 
-    use log::debug;
-    use polars::datatypes::AnyValue::List;
-    use polars::df;
-    use polars::export::arrow::compute::filter::filter;
-    use polars::export::arrow::io::ipc::read::FileReader;
-    use polars::export::chrono;
-    use polars::export::chrono::{NaiveDate, NaiveDateTime};
-    use polars::prelude::LiteralValue::DateTime;
-    use polars::prelude::*;
-    // use polars::io::prelude::utils::
-    use polars::io::mmap::MmapBytesReader;
+        histogram.set_selected(vec![1, 2]);
+        assert_eq!(filtered_rows(&histogram), "(3, 2)");
 
-    use crate::data_type::{WapukuDataType, WapukuDataValues};
-    use crate::model::{
-        CompositeType, Condition, ConditionType, Data, DataGroup, DataProperty, Filter, GroupsGrid,
-        NumericColumnSummary, Property, PropertyRange, StringColumnSummary, Summary, SummaryColumn,
-        SummaryColumnType,
-    };
-    use crate::polars_df::{describe, describe_with_params, group_by_2, PolarsData};
-    use crate::tests::init_log;
+        let mut histogram = data
+            .build_histogram(0u128, String::from("country"), HistogramParams::new(Binning::Count { bins: 10 }, None, true).with_top_n(1))
+            .expect("build_histogram");
 
-    #[ctor::ctor]
-    fn init() {
-        std::env::set_var("POLARS_FMT_MAX_ROWS", "100");
-        std::env::set_var("FMT_MAX_COLS", "1000");
+        assert_eq!(histogram.values().iter().map(|(v, _)| v.clone()).collect::<Vec<_>>(), vec!["NO", "Other (2 values)", "n/a"]);
+        assert!(!histogram.is_selectable(1));
 
-        init_log();
+        histogram.set_selected(vec![0, 1, 2]);
+        assert_eq!(histogram.selected(), &vec![0, 2]);
+        assert_eq!(filtered_rows(&histogram), "(3, 2)");
     }
 
     #[test]
-    fn test_build_summary_ints() {
+    fn test_histogram_compare() {
         let df = df!(
-            "property_1" => &[1,   2,   3,   1,   2,   3,   1,   2,   3,],
-            "property_2" => &[10,  10,  10,  20,  20,  20,  30,  30,  30,],
-            "property_3" => &[11,  12,  13,  21,  22,  23,  31,  32,  33,]
-        )
-        .unwrap();
-        let mut data = PolarsData::new(df, String::from("test"));
+            "amount" => &[1.0f64, 2.0, 3.0, 4.0, 5.0, 6.0],
+            "country" => &["NO", "NO", "IT", "IT", "NO", "SE"]
+        ).unwrap();
 
-        let summary = data.build_summary(0, None);
+        let data = PolarsData::new(df, String::from("test"));
 
-        check_numeric_column(&summary, 0, "1.0", "2.0", "3.0");
-        check_numeric_column(&summary, 1, "10.0", "20.0", "30.0");
+        let mut filter = dummy_filter();
+        filter.add_condition(ConditionType::Single { column_name: "country".into(), condition: Condition::Equals { value: Some("NO".into()) } }, None);
+
+        let frame = WaFrame::new(1u128, String::from("test filtered"), data.build_summary(1u128, None)).with_parent(0u128, filter);
+        let (parent_id, compare) = frame.parent_condition().expect("parent");
+        assert_eq!(parent_id, 0u128);
+
+        let histogram = data
+            .build_histogram(1u128, String::from("amount"), HistogramParams::new(Binning::Edges { edges: vec![0.0, 3.0, 6.0] }, None, false).with_compare(Some(compare.clone())))
+            .expect("build_histogram");
+
+        assert_eq!(histogram.series(), &vec![(String::from("parent"), vec![2, 4]), (String::from("filtered"), vec![2, 1])]);
+
+        let histogram = data
+            .build_histogram(1u128, String::from("country"), HistogramParams::default().with_compare(Some(compare)))
+            .expect("build_histogram");
+
+        assert_eq!(histogram.series(), &vec![(String::from("parent"), vec![3, 2, 1]), (String::from("filtered"), vec![3, 0, 0])]);
     }
 
     #[test]
-    fn test_build_summary_dates() {
-        let date_series = DatetimeChunked::from_naive_datetime(
-            "days",
-            vec![
-                chrono::NaiveDateTime::parse_from_str("2023-01-01 00:00:01", "%Y-%m-%d %H:%M:%S")
-                    .unwrap(),
-                chrono::NaiveDateTime::parse_from_str("2023-01-15 00:00:01", "%Y-%m-%d %H:%M:%S")
-                    .unwrap(),
-                chrono::NaiveDateTime::parse_from_str("2023-02-01 00:00:02", "%Y-%m-%d %H:%M:%S")
-                    .unwrap(),
-                chrono::NaiveDateTime::parse_from_str("2023-02-15 00:00:02", "%Y-%m-%d %H:%M:%S")
-                    .unwrap(),
-                chrono::NaiveDateTime::parse_from_str("2023-03-01 00:00:02", "%Y-%m-%d %H:%M:%S")
-                    .unwrap(),
-                chrono::NaiveDateTime::parse_from_str("2023-03-15 00:00:02", "%Y-%m-%d %H:%M:%S")
-                    .unwrap(),
-            ],
-            TimeUnit::Milliseconds,
-        )
-        .into_series();
-        // let int_series = Int64Chunked::from_vec("ints", vec![1, 2, 3, 4, 5, 6]).into_series();
+    fn test_histogram_scales() {
+        let df = df!(
+            "amount" => &[1.0f64, 1.5, 2.0, 4.5]
+        ).unwrap();
+
+        let data = PolarsData::new(df, String::from("test"));
+
+        let mut histogram = data
+            .build_histogram(0u128, String::from("amount"), HistogramParams::new(Binning::Edges { edges: vec![0.0, 2.0, 6.0] }, None, false))
+            .expect("build_histogram");
 
-        debug!("date_series={:?}", date_series.dtype());
+        assert_eq!(histogram.scaled_values(), vec![2.0, 2.0]);
 
-        let df = DataFrame::new(vec![date_series]).unwrap();
+        histogram.set_view(histogram.view().with_scale(HistogramScale::Percent));
+        assert_eq!(histogram.scaled_values(), vec![50.0, 50.0]);
 
-        // let df = ParquetReader::new(File::open("../wapuku-egui/www/data/userdata1.parquet").unwrap()).finish().unwrap();
+        histogram.set_view(histogram.view().with_scale(HistogramScale::Density));
+        assert_eq!(histogram.scaled_values(), vec![0.25, 0.125]);
 
-        let mut data = PolarsData::new(df, String::from("test"));
+        histogram.set_view(histogram.view().with_scale(HistogramScale::CumulativePercent));
+        assert_eq!(histogram.scaled_values(), vec![50.0, 100.0]);
 
-        let summary = data.build_summary(0, None);
+        assert_eq!(histogram.markers(), &vec![(String::from("mean 2.25"), 2.25), (String::from("median 1.75"), 1.75)]);
+        assert_eq!(histogram.bin_position(1.0), Some(0.5));
+        assert_eq!(histogram.bin_position(4.0), Some(1.5));
 
-        println!("summary={:?}", summary);
+        let df = df!(
+            "amount" => &[Some(1.0f64), Some(1.5), Some(2.0), Some(4.5), None]
+        ).unwrap();
 
-        if let SummaryColumnType::Datetime { data } = summary.columns()[0].dtype() {
-        } else {
-            panic!("column is not date: {:?}", summary.columns()[0].dtype())
-        }
+        let data = PolarsData::new(df, String::from("test"));
 
-        // check_numeric_column(&summary, 0, "1.0", "2.0", "3.0");
-        // check_numeric_column(&summary, 1, "10.0", "20.0", "30.0");
+        let mut histogram = data
+            .build_histogram(0u128, String::from("amount"), HistogramParams::new(Binning::Edges { edges: vec![0.0, 2.0, 6.0] }, None, true))
+            .expect("build_histogram");
+
+        //the n/a bar is out of the totals and of the running sum
+        histogram.set_view(histogram.view().with_scale(HistogramScale::Percent));
+        assert_eq!(histogram.scaled_values(), vec![50.0, 50.0, 25.0]);
+
+        histogram.set_view(histogram.view().with_scale(HistogramScale::CumulativePercent));
+        assert_eq!(histogram.scaled_values(), vec![50.0, 100.0, 25.0]);
     }
 
     #[test]
-    fn test_build_histogram_str() {
+    fn test_histogram_kde_points() {
         let df = df!(
-            "property_1" => &[1i32,   2i32,    3i32,  1i32,   2i32,    3i32,    1i32,    2i32,   3i32],
-            "property_2" => &[1f32,   2f32,    3f32,  1f32,    2f32,   3f32,    1f32,    2f32,   3f32],
-            "property_3" => &["A",  "B",  "C",  "A",  "B",  "C",  "C",  "B", "C"]
+            "amount" => &[1.0f64, 1.5, 3.0, 4.5],
+            "country" => &["NO", "NO", "IT", "IT"]
         ).unwrap();
 
-        let mut data = PolarsData::new(df, String::from("test"));
+        let data = PolarsData::new(df, String::from("test"));
+
+        let mut histogram = data
+            .build_histogram(0u128, String::from("amount"), HistogramParams::new(Binning::Edges { edges: vec![0.0, 2.0, 6.0] }, None, false).with_split(Some(String::from("country"))))
+            .expect("build_histogram");
+
+        histogram.set_kde(vec![ColumnPlotSeries::new(String::from("NO (h=1.000)"), vec![(1.0, 0.5), (4.0, 0.5), (7.0, 0.5)])]);
+
+        //scaled by the group's own rows and the width of the bin under x, dropped outside of the bins
+        assert_eq!(histogram.kde_points(), vec![("NO (h=1.000)", vec![(1.0, 2.0), (4.0, 4.0)])]);
+
+        histogram.set_view(histogram.view().with_scale(HistogramScale::Percent));
+        assert_eq!(histogram.kde_points()[0].1, vec![(1.0, 50.0), (4.0, 100.0)]);
+
+        histogram.set_view(histogram.view().with_scale(HistogramScale::Density));
+        assert_eq!(histogram.kde_points()[0].1, vec![(1.0, 0.25), (4.0, 0.25)]);
+
+        histogram.set_view(histogram.view().with_scale(HistogramScale::Count).with_normalized(true));
+        assert_eq!(histogram.kde_points()[0].1, vec![(1.0, 100.0), (4.0, 200.0)]);
+
+        histogram.set_view(histogram.view().with_scale(HistogramScale::CumulativeCount));
+        assert!(histogram.kde_points().is_empty());
+    }
+
+    #[test]
+    fn test_build_histogram_split() {
+        let df = df!(
+            "amount" => &[1.0f64, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0],
+            "country" => &[Some("NO"), Some("NO"), Some("IT"), Some("NO"), Some("IT"), Some("IT"), None, Some("IT")],
+            "paid" => &[true, false, true, true, false, true, true, true]
+        ).unwrap();
+
+        let data = PolarsData::new(df, String::from("test"));
 
         let histogram = data
-            .build_histogram(0u128, String::from("property_3"), None)
+            .build_histogram(0u128, String::from("amount"), HistogramParams::new(Binning::Count { bins: 2 }, None, false).with_split(Some(String::from("country"))))
             .expect("build_histogram");
 
-        let y = histogram.values();
-        println!("y={:?}", y);
-        assert_eq!(y.get(0).unwrap().1, 2);
-        assert_eq!(y.get(1).unwrap().1, 3);
-        assert_eq!(y.get(2).unwrap().1, 4);
+        assert_eq!(histogram.values().iter().map(|(_, c)| *c).collect::<Vec<_>>(), vec![4, 4]);
+        assert_eq!(
+            histogram.series(),
+            &vec![
+                (String::from("IT"), vec![1, 3]),
+                (String::from("NO"), vec![3, 0]),
+                (String::from("n/a"), vec![0, 1]),
+            ]
+        );
+
+        let histogram = data
+            .build_histogram(0u128, String::from("paid"), HistogramParams::default().with_split(Some(String::from("country"))))
+            .expect("build_histogram");
+
+        assert_eq!(
+            histogram.series(),
+            &vec![
+                (String::from("IT"), vec![3, 1, 0]),
+                (String::from("NO"), vec![2, 1, 0]),
+                (String::from("n/a"), vec![1, 0, 0]),
+            ]
+        );
     }
 
     #[test]
-    fn test_build_date_histogram_f32() {
-        let df = DataFrame::new(vec![DatetimeChunked::from_naive_datetime(
-            "registration_dttm",
-            vec![
-                chrono::NaiveDateTime::parse_from_str("2023-01-01 00:00:01", "%Y-%m-%d %H:%M:%S")
-                    .unwrap(),
-                chrono::NaiveDateTime::parse_from_str("2023-01-15 00:00:01", "%Y-%m-%d %H:%M:%S")
-                    .unwrap(),
-                chrono::NaiveDateTime::parse_from_str("2023-02-01 00:00:02", "%Y-%m-%d %H:%M:%S")
-                    .unwrap(),
-                chrono::NaiveDateTime::parse_from_str("2023-02-15 00:00:02", "%Y-%m-%d %H:%M:%S")
-                    .unwrap(),
-                chrono::NaiveDateTime::parse_from_str("2023-03-01 00:00:02", "%Y-%m-%d %H:%M:%S")
-                    .unwrap(),
-                chrono::NaiveDateTime::parse_from_str("2023-03-15 00:00:02", "%Y-%m-%d %H:%M:%S")
-                    .unwrap(),
+    fn test_build_histogram_other_types() {
+        let df = df!(
+            "flag" => &[Some(true), Some(false), Some(true), None],
+            "elapsed" => &[500i64, 1_500, 90_000, 7_200_000],
+            "time" => &[
+                chrono::NaiveTime::from_hms_opt(8, 15, 0).unwrap(),
+                chrono::NaiveTime::from_hms_opt(8, 45, 0).unwrap(),
+                chrono::NaiveTime::from_hms_opt(13, 0, 0).unwrap(),
+                chrono::NaiveTime::from_hms_opt(23, 59, 59).unwrap(),
             ],
-            TimeUnit::Milliseconds,
-        )
-        .into_series()])
+            "city" => &["Oslo", "Rome", "Oslo", "Oslo"]
+        ).unwrap()
+        .lazy()
+        .with_columns([
+            col("elapsed").cast(DataType::Duration(TimeUnit::Milliseconds)),
+            col("city").cast(DataType::from_categories(Categories::global())),
+        ])
+        .collect()
         .unwrap();
 
-        // let df = ParquetReader::new(File::open("../wapuku-egui/www/data/userdata1.parquet").unwrap()).finish().unwrap();
+        let data = PolarsData::new(df, String::from("test"));
 
-        println!("{:?}", df);
+        let histogram = |column: &str, params: HistogramParams| {
+            data.build_histogram(0u128, String::from(column), params)
+                .expect("build_histogram")
+                .values()
+                .clone()
+        };
 
-        let mut data = PolarsData::new(df, String::from("test"));
+        assert_eq!(
+            histogram("flag", HistogramParams::default()),
+            vec![(String::from("true"), 2), (String::from("false"), 1)]
+        );
 
-        let summary = data.build_summary(0u128, None);
-        println!("summary={:?}", summary);
+        assert_eq!(
+            histogram("flag", HistogramParams::new(Binning::Count { bins: 10 }, None, true)),
+            vec![(String::from("true"), 2), (String::from("false"), 1), (String::from("n/a"), 1)]
+        );
 
-        let histogram = data
-            .build_histogram(0u128, String::from("registration_dttm"), None)
-            .expect("build_histogram");
+        let elapsed = histogram("elapsed", HistogramParams::new(Binning::Edges { edges: vec![0.0, 1_000.0, 60_000.0, 7_200_000.0] }, None, false));
+        assert_eq!(
+            elapsed,
+            vec![(String::from("[0ms, 1.0s)"), 1), (String::from("[1.0s, 1.0min)"), 1), (String::from("[1.0min, 2.0h]"), 2)]
+        );
 
-        println!("histogram={:?}", histogram);
-        assert_eq!(histogram.values()[0].1, 1);
+        //no nulls, no n/a bar
+        let with_nulls = histogram("elapsed", HistogramParams::new(Binning::Edges { edges: vec![0.0, 1_000.0, 60_000.0, 7_200_000.0] }, None, true));
+        assert_eq!(with_nulls, elapsed);
+
+        let elapsed = histogram("elapsed", HistogramParams::new(Binning::Count { bins: 4 }, None, false));
+        assert_eq!(elapsed.first(), Some(&(String::from("[0ms, 30.0min)"), 3)));
+        assert_eq!(elapsed.last(), Some(&(String::from("[1.5h, 2.0h]"), 1)));
+
+        let time = histogram("time", HistogramParams::default());
+        assert_eq!(time.len(), 24);
+        assert_eq!(time[8], (String::from("08:00"), 2));
+        assert_eq!(time[23], (String::from("23:00"), 1));
+
+        assert_eq!(
+            histogram("city", HistogramParams::default()),
+            vec![(String::from("Oslo"), 3), (String::from("Rome"), 1)]
+        );
+    }
+
+    #[test]
+    fn test_build_calendar_histogram() {
+        let registration_dttm = ["2023-01-01 10:00:01", "2023-01-15 10:30:01", "2023-02-01 12:00:02", "2023-02-15 23:00:02", "2023-04-01 00:00:02", "2023-04-15 00:00:02"]
+            .iter()
+            .map(|d| chrono::NaiveDateTime::parse_from_str(d, "%Y-%m-%d %H:%M:%S").unwrap())
+            .collect::<Vec<_>>();
+
+        let df = df!(
+            "registration_dttm" => registration_dttm
+        ).unwrap();
+
+        let data = PolarsData::new(df, String::from("test"));
+
+        let histogram = |binning: Binning| {
+            data.build_histogram(0u128, String::from("registration_dttm"), HistogramParams::new(binning, None, false))
+                .expect("build_histogram")
+                .values()
+                .clone()
+        };
+
+        assert_eq!(
+            histogram(Binning::Calendar { unit: Some(CalendarUnit::Month) }),
+            vec![
+                (String::from("2023-01"), 2),
+                (String::from("2023-02"), 2),
+                (String::from("2023-03"), 0),
+                (String::from("2023-04"), 2),
+            ]
+        );
+        assert_eq!(histogram(Binning::Calendar { unit: Some(CalendarUnit::Quarter) }), vec![(String::from("2023-Q1"), 4), (String::from("2023-Q2"), 2)]);
+
+        let by_week = histogram(Binning::Calendar { unit: None });
+        assert_eq!(by_week.len(), 16);
+        assert_eq!(by_week[0], (String::from("2022-W52"), 1));
+        assert_eq!(by_week[1], (String::from("2023-W01"), 0));
+
+        let by_weekday = histogram(Binning::DayOfWeek);
+        assert_eq!(by_weekday.len(), 7);
+        assert_eq!(by_weekday[2], (String::from("Wed"), 2));
+        assert_eq!(by_weekday[5], (String::from("Sat"), 2));
+        assert_eq!(by_weekday[6], (String::from("Sun"), 2));
+
+        let by_hour = histogram(Binning::HourOfDay);
+        assert_eq!(by_hour.len(), 24);
+        assert_eq!(by_hour[0], (String::from("00:00"), 2));
+        assert_eq!(by_hour[10], (String::from("10:00"), 2));
     }
 
     #[test]
@@ -1894,7 +4182,7 @@ pub(super) mod tests {
             .expect("convert_column");
         println!("ok={:?}", ok);
         println!("2. {:?}", data.build_summary(0, None).columns()[0].dtype());
-        println!("2. {:?}", data.build_histogram(0, "days".into(), None));
+        println!("2. {:?}", data.build_histogram(0, "days".into(), HistogramParams::default()));
 
         // assert!(ok);
     }
@@ -1931,23 +4219,248 @@ pub(super) mod tests {
         let mut data = PolarsData::new(df, String::from("test"));
 
         let histogram = data
-            .build_histogram(0u128, String::from("property_2"), Some(10))
+            .build_histogram(0u128, String::from("property_2"), HistogramParams::default())
             .expect("build_histogram");
 
         println!("histogram={:?}", histogram);
 
         let y = histogram.values();
         println!("y={:?}", y);
-        assert_eq!(y.get(0).unwrap().0, "(-inf, 0.00]");
-        assert_eq!(y.get(0).unwrap().1, 0);
-        assert_eq!(y.get(3).unwrap().0, "(0.80, 1.20]");
-        assert_eq!(y.get(3).unwrap().1, 3);
+        assert_eq!(y.len(), 10);
+        assert_eq!(y.get(0).unwrap().0, "[1.00, 1.20)");
+        assert_eq!(y.get(0).unwrap().1, 3);
+        assert_eq!(y.get(1).unwrap().1, 0);
+        assert_eq!(y.get(9).unwrap().0, "[2.80, 3.00]");
+        assert_eq!(y.get(9).unwrap().1, 3);
 
         // let histogram = data.build_histogram(0u128, String::from("property_3"), Some(10)).expect("build_histogram");
         //
         // println!("histogram={:?}", histogram);
     }
 
+    #[test]
+    fn test_build_histogram_binning() {
+        let df = df!(
+            "property_1" => &[Some(1.0f64), Some(2.0), Some(3.0), Some(4.0), Some(5.0), Some(6.0), Some(7.0), Some(8.0), None]
+        ).unwrap();
+
+        let data = PolarsData::new(df, String::from("test"));
+
+        let counts = |binning: Binning, range: Option<(f64, f64)>, include_nulls: bool| {
+            data.build_histogram(0u128, String::from("property_1"), HistogramParams::new(binning, range, include_nulls))
+                .expect("build_histogram")
+                .values()
+                .iter()
+                .map(|(_, count)| *count)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(counts(Binning::Count { bins: 2 }, None, false), vec![4, 4]);
+        assert_eq!(counts(Binning::Width { width: 3.0 }, None, false), vec![3, 3, 2]);
+        assert!(data.build_histogram(0u128, String::from("property_1"), HistogramParams::new(Binning::Width { width: 0.001 }, None, false)).is_err());
+        assert_eq!(counts(Binning::Sturges, None, false), vec![2, 2, 2, 2]);
+        assert_eq!(counts(Binning::Quantile { bins: 2 }, None, false), vec![4, 4]);
+        assert_eq!(counts(Binning::Edges { edges: vec![0.0, 2.0, 10.0] }, None, false), vec![1, 7]);
+        assert_eq!(counts(Binning::Count { bins: 2 }, Some((3.0, 6.0)), false), vec![2, 2]);
+        assert_eq!(counts(Binning::Count { bins: 2 }, None, true), vec![4, 4, 1]);
+
+        let histogram = data
+            .build_histogram(0u128, String::from("property_1"), HistogramParams::new(Binning::Edges { edges: vec![0.0, 2.0, 10.0] }, None, true))
+            .expect("build_histogram");
+        assert_eq!(histogram.values()[0].0, "[0.00, 2.00)");
+        assert_eq!(histogram.values()[2].0, "n/a");
+    }
+
+    #[test]
+    fn test_check_quality() {
+        let df = df!(
+            "id" =>       &[1i64,  2,        2,       4,       5,        6],
+            "constant" => &["a",   "a",      "a",     "a",     "a",      "a"],
+            "amount" =>   &["1",   "3",      "3",     "x",     "4",      "5"],
+            "name" =>     &[" a",  "b",      "b",     "c ",    "d",      "e"],
+            "score" =>    &[Some(1.0), None, None,    None,    Some(-999.0), None]
+        ).unwrap();
+
+        let data = PolarsData::new(df, String::from("test"));
+
+        let quality = data.check_quality(0u128).expect("check_quality");
+        debug!("quality={:?}", quality);
+
+        let of_type = |issue_type: DataQualityIssueType| {
+            quality.issues_of(issue_type).map(|i| (i.column().cloned(), i.affected())).collect::<Vec<_>>()
+        };
+
+        assert_eq!(of_type(DataQualityIssueType::DuplicateRows), vec![(None, 1)]);
+        assert_eq!(of_type(DataQualityIssueType::Constant), vec![(Some("constant".into()), 6)]);
+        assert_eq!(of_type(DataQualityIssueType::MixedTypes), vec![(Some("amount".into()), 1)]);
+        assert_eq!(of_type(DataQualityIssueType::Whitespace), vec![(Some("name".into()), 2)]);
+        assert_eq!(of_type(DataQualityIssueType::HighNullShare), vec![(Some("score".into()), 4)]);
+        assert_eq!(of_type(DataQualityIssueType::Sentinel), vec![(Some("score".into()), 1)]);
+        assert_eq!(of_type(DataQualityIssueType::NonUniqueId), vec![(Some("id".into()), 1)]);
+    }
+
+    #[test]
+    fn test_find_outliers() {
+        let df = df!(
+            "property_1" => &[10i64, 11, 12, 11, 10, 12, 11, 100],
+            "property_2" => &[1.0f64, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0],
+            "property_3" => &["a", "b", "c", "d", "e", "f", "g", "h"]
+        ).unwrap();
+
+        let data = PolarsData::new(df, String::from("test"));
+
+        let outliers = data.find_outliers(0u128, OutlierMethod::Iqr).expect("find_outliers");
+        debug!("outliers={:?}", outliers);
+
+        assert_eq!(outliers.columns().len(), 2);
+
+        let property_1 = outliers.column("property_1").expect("property_1");
+        assert_eq!(property_1.count(), 1);
+        assert_eq!(property_1.top_rows(), &vec![(7usize, 100.0)]);
+        assert!(property_1.upper() < 100.0);
+        assert_eq!(property_1.max(), 100.0);
+
+        assert_eq!(outliers.column("property_2").expect("property_2").count(), 0);
+
+        //the filter conditions split the rows the same way as the count
+        for (isolate, shape) in [(true, "(1, 3)"), (false, "(7, 3)")] {
+            let mut filter = dummy_filter();
+            filter.add_condition(property_1.to_condition(isolate), None);
+            let filtered_frame = data.apply_filter(0u128, filter).expect("apply_filter");
+            assert_eq!(filtered_frame.data().build_summary(0u128, None).shape(), shape);
+        }
+
+        let outliers = data.find_outliers(0u128, OutlierMethod::ModifiedZScore).expect("find_outliers");
+        assert_eq!(outliers.column("property_1").expect("property_1").count(), 1);
+    }
+
+    #[test]
+    fn test_value_counts() {
+        let df = df!(
+            "property_1" => &[Some("a"), Some("b"), Some("a"), None, Some("a"), Some("b"), Some("c"), Some("d")],
+            "property_2" => &[1.5f64, 2.0, 1.5, 3.0, 1.5, 2.0, 1.5, 1.5]
+        ).unwrap();
+
+        let data = PolarsData::new(df, String::from("test"));
+
+        let value_counts = data.value_counts(0u128, String::from("property_1"), ValueCountsSort::CountDesc, 0, 100).expect("value_counts");
+        debug!("value_counts={:?}", value_counts);
+
+        assert_eq!(value_counts.distinct(), 5);
+        assert_eq!(
+            value_counts.values().iter().map(|v| (v.value().cloned(), v.count())).collect::<Vec<_>>(),
+            vec![(Some("a".into()), 3), (Some("b".into()), 2), (Some("c".into()), 1), (Some("d".into()), 1), (None, 1)]
+        );
+        assert_eq!(value_counts.values()[0].percent(), 37.5);
+        assert_eq!(value_counts.values()[1].cumulative_percent(), 62.5);
+        assert_eq!(value_counts.values()[4].cumulative_percent(), 100.0);
+
+        let page = data.value_counts(0u128, String::from("property_1"), ValueCountsSort::CountDesc, 1, 2).expect("value_counts");
+        assert_eq!(page.offset(), 1);
+        assert_eq!(
+            page.values().iter().map(|v| (v.value().cloned(), v.cumulative_percent())).collect::<Vec<_>>(),
+            vec![(Some("b".into()), 62.5), (Some("c".into()), 75.0)]
+        );
+
+        let numeric = data.value_counts(0u128, String::from("property_2"), ValueCountsSort::ValueAsc, 0, 100).expect("value_counts");
+        assert_eq!(
+            numeric.values().iter().map(|v| (v.value().cloned(), v.count())).collect::<Vec<_>>(),
+            vec![(Some("1.5".into()), 5), (Some("2.0".into()), 2), (Some("3.0".into()), 1)]
+        );
+
+        let mut filter = dummy_filter();
+        filter.add_condition(numeric.values()[1].to_condition(String::from("property_2")), None);
+        let filtered_frame = data.apply_filter(0u128, filter).expect("apply_filter");
+        assert_eq!(filtered_frame.data().build_summary(0u128, None).shape(), "(2, 2)");
+    }
+
+    #[test]
+    fn test_pivot() {
+        let df = df!(
+            "region" => &["a", "a", "b", "b", "b"],
+            "kind" =>   &["x", "y", "x", "x", "y"],
+            "amount" => &[1i64, 2, 3, 4, 5]
+        ).unwrap();
+
+        let data = PolarsData::new(df, String::from("test"));
+
+        let mut pivot = data.pivot(0u128, "region".into(), "kind".into(), "amount".into(), PivotAgg::Sum).expect("pivot");
+        debug!("pivot={:?}", pivot);
+
+        assert_eq!(pivot.row_keys(), &vec![String::from("a"), String::from("b")]);
+        assert_eq!(pivot.column_keys(), &vec![String::from("x"), String::from("y")]);
+        assert_eq!(pivot.value(Some(1), Some(0)), Some(7.0));
+        assert_eq!(pivot.value(Some(0), None), Some(3.0));
+        assert_eq!(pivot.value(None, Some(1)), Some(7.0));
+        assert_eq!(pivot.value(None, None), Some(15.0));
+
+        pivot.set_normalization(PivotNormalization::Row);
+        assert_eq!(pivot.value(Some(0), Some(1)), Some(2.0 / 3.0 * 100.0));
+
+        pivot.set_normalization(PivotNormalization::Total);
+        assert_eq!(pivot.value(None, None), Some(100.0));
+
+        let counts = data.pivot(0u128, "region".into(), "kind".into(), "amount".into(), PivotAgg::Count).expect("pivot");
+        assert_eq!(counts.value(Some(1), Some(0)), Some(2.0));
+        assert_eq!(counts.value(Some(0), Some(0)), Some(1.0));
+
+        let mut means = data.pivot(0u128, "region".into(), "kind".into(), "amount".into(), PivotAgg::Mean).expect("pivot");
+        means.set_normalization(PivotNormalization::Row);
+        assert_eq!(means.value(Some(1), Some(0)), Some(3.5));
+    }
+
+    #[test]
+    fn test_pivot_nulls() {
+        let df = df!(
+            "region" => &[Some("a"), Some("n/a"), None, None, Some("a")],
+            "kind" =>   &[Some(2i64), Some(10), None, Some(2), Some(10)],
+            "amount" => &[1i64, 2, 3, 4, 5]
+        ).unwrap();
+
+        let data = PolarsData::new(df, String::from("test"));
+
+        let pivot = data.pivot(0u128, "region".into(), "kind".into(), "amount".into(), PivotAgg::Count).expect("pivot");
+
+        //a real "n/a" keeps apart from the nulls, numeric keys keep their order
+        assert_eq!(pivot.row_keys(), &vec![String::from("a"), String::from("n/a"), String::from("n/a")]);
+        assert_eq!(pivot.column_keys(), &vec![String::from("2"), String::from("10"), String::from("n/a")]);
+        assert_eq!(pivot.value(Some(1), None), Some(1.0));
+        assert_eq!(pivot.value(Some(2), None), Some(2.0));
+        assert_eq!(pivot.value(Some(2), Some(2)), Some(1.0));
+        assert_eq!(pivot.value(Some(1), Some(0)), None);
+    }
+
+    #[test]
+    fn test_group_by() {
+        let df = df!(
+            "region" => &["a", "a", "b", "b", "b"],
+            "kind" =>   &["x", "y", "x", "x", "y"],
+            "amount" => &[1i64, 2, 3, 4, 5]
+        ).unwrap();
+
+        let data = PolarsData::new(df, String::from("test"));
+
+        let grouped = data.group_by(
+            0u128,
+            vec![String::from("region")],
+            vec![
+                (String::from("amount"), GroupByAgg::Sum),
+                (String::from("amount"), GroupByAgg::Quantile { q: 0.5 }),
+                (String::from("kind"), GroupByAgg::NUnique),
+            ],
+        ).expect("group_by");
+
+        let summary = grouped.data().build_summary(0u128, None);
+        assert_eq!(summary.shape(), "(2, 4)");
+        assert_eq!(
+            summary.columns().iter().map(|c| c.name().clone()).collect::<Vec<_>>(),
+            vec!["region", "amount_sum", "amount_q0.5", "kind_n_unique"]
+        );
+        check_numeric_column(&summary, 1, "3.0", "7.5", "12.0");
+
+        assert!(data.group_by(0u128, vec![], vec![]).is_err());
+    }
+
     #[test]
     fn test_build_summary_str() {
         let df = df!(
@@ -2574,31 +5087,164 @@ pub(super) mod tests {
                 "property_3".into(),
                 "property_4".into(),
             ],
+            CorrMethod::Pearson,
         );
 
         let corrs = res.expect("corrs");
 
-        assert_eq!(
-            corrs
-                .values()
-                .get(&("property_1".into(), "property_2".into()))
-                .expect("property_1 property_2"),
-            &1.
-        );
-        assert_eq!(
-            corrs
-                .values()
-                .get(&("property_1".into(), "property_4".into()))
-                .expect("property_1 property_2"),
-            &-1.
-        );
-        assert_eq!(
-            corrs
-                .values()
-                .get(&("property_1".into(), "property_3".into()))
-                .expect("property_1 property_3"),
-            &0.9847319
-        );
+        assert_eq!(corrs.get_val(&"property_1".into(), &"property_2".into()), Some(1.));
+        assert_eq!(corrs.get_val(&"property_1".into(), &"property_4".into()), Some(-1.));
+        //eta^2 = between 8000 / total 8250
+        assert!((corrs.get_val(&"property_1".into(), &"property_3".into()).expect("property_1 property_3") - (8000f32 / 8250.).sqrt()).abs() < 1e-6);
+        assert_eq!(corrs.get_measure(&"property_1".into(), &"property_3".into()), Some(CorrMeasure::CorrelationRatio));
+        assert_eq!(corrs.get_measure(&"property_1".into(), &"property_2".into()), Some(CorrMeasure::Pearson));
+    }
+
+    #[test]
+    fn test_corr_measures() {
+        let df = df!(
+            "property_1" => &[1.0,   2.0,   3.0,   4.0,   5.0,   6.0],
+            "property_2" => &["a",   "a",   "b",   "b",   "c",   "c"],
+            "property_3" => &["x",   "x",   "y",   "y",   "z",   "z"],
+            "property_4" => &[false, false, false, true,  true,  true]
+        ).unwrap();
+
+        let polars_data = PolarsData::new(df, String::from("test"));
+        let corrs = polars_data.clc_corrs(
+            0,
+            vec!["property_1".into(), "property_2".into(), "property_3".into(), "property_4".into()],
+            CorrMethod::Spearman,
+        ).expect("corrs");
+
+        assert_eq!(corrs.get_measure(&"property_2".into(), &"property_3".into()), Some(CorrMeasure::CramersV));
+        assert!((corrs.get_val(&"property_2".into(), &"property_3".into()).unwrap() - 1.0).abs() < 1e-6);
+
+        assert_eq!(corrs.get_measure(&"property_2".into(), &"property_4".into()), Some(CorrMeasure::CramersV));
+
+        //same as pearson on 0/1
+        assert_eq!(corrs.get_measure(&"property_1".into(), &"property_4".into()), Some(CorrMeasure::PointBiserial));
+        assert!((corrs.get_val(&"property_1".into(), &"property_4".into()).unwrap() - (27f32 / 35.).sqrt()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_corr_methods() {
+        let df = df!(
+            "property_1" => &[1,    2,    3,    4,     5,      6],
+            "property_2" => &[1,    4,    9,    16,    25,     1000],
+            "property_3" => &[2,    1,    4,    3,     6,      5]
+        ).unwrap();
+
+        let polars_data = PolarsData::new(df, String::from("test"));
+        let columns: Vec<String> = vec!["property_1".into(), "property_2".into(), "property_3".into()];
+
+        let pearson = polars_data.clc_corrs(0, columns.clone(), CorrMethod::Pearson).expect("pearson");
+        assert_eq!(pearson.method(), CorrMethod::Pearson);
+        assert!(pearson.get_val(&"property_1".into(), &"property_2".into()).unwrap() < 0.9);
+
+        let spearman = polars_data.clc_corrs(0, columns.clone(), CorrMethod::Spearman).expect("spearman");
+        assert_eq!(spearman._title(), "Correlations (Spearman)");
+        assert!((spearman.get_val(&"property_1".into(), &"property_2".into()).unwrap() - 1.0).abs() < 1e-6);
+
+        let kendall = polars_data.clc_corrs(0, columns, CorrMethod::Kendall).expect("kendall");
+        assert!((kendall.get_val(&"property_1".into(), &"property_2".into()).unwrap() - 1.0).abs() < 1e-6);
+        //concordant 12, discordant 3 of 15 pairs
+        assert!((kendall.get_val(&"property_1".into(), &"property_3".into()).unwrap() - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_corr_significance() {
+        let df = df!(
+            "property_1" => &[Some(1.0), Some(2.0), Some(3.0), Some(4.0), Some(5.0), Some(6.0), Some(7.0), Some(8.0), None],
+            "property_2" => &[Some(1.2), Some(1.9), Some(3.3), Some(3.8), Some(5.1), Some(6.2), Some(6.8), None,      Some(9.0)],
+            "property_3" => &[Some(5.0), Some(1.0), Some(4.0), Some(2.0), Some(3.0), Some(1.0), Some(5.0), Some(2.0), Some(4.0)],
+            "property_4" => &[Some(1.0), Some(1.0), Some(1.0), Some(1.0), Some(1.0), Some(1.0), Some(1.0), Some(1.0), Some(1.0)]
+        ).unwrap();
+
+        let polars_data = PolarsData::new(df, String::from("test"));
+        let corrs = polars_data.clc_corrs(
+            0,
+            vec!["property_1".into(), "property_2".into(), "property_3".into(), "property_4".into()],
+            CorrMethod::Pearson,
+        ).expect("corrs");
+
+        //pairwise deletion: rows 8 and 9 have a null on one side
+        let strong = corrs.get(&"property_2".into(), &"property_1".into()).expect("property_1 property_2");
+        assert_eq!(strong.n(), 7);
+        assert!(strong.is_significant());
+        let (lo, hi) = strong.ci().expect("ci");
+        assert!(lo < strong.value().unwrap() && strong.value().unwrap() < hi && hi <= 1.0);
+
+        let weak = corrs.get(&"property_1".into(), &"property_3".into()).expect("property_1 property_3");
+        assert_eq!(weak.n(), 8);
+        assert!(!weak.is_significant());
+
+        //constant column
+        let undefined = corrs.get(&"property_1".into(), &"property_4".into()).expect("property_1 property_4");
+        assert_eq!(undefined.value(), None);
+        assert!(!undefined.is_significant());
+
+        assert!(corrs.get(&"property_1".into(), &"property_5".into()).is_none());
+    }
+
+    #[test]
+    fn test_covariance() {
+        let df = df!(
+            "property_1" => &[Some(1.0), Some(2.0), Some(3.0), Some(4.0), None],
+            "property_2" => &[Some(2.0), Some(4.0), Some(6.0), Some(8.0), Some(10.0)],
+            "property_3" => &["a",       "a",       "b",       "b",       "b"]
+        ).unwrap();
+
+        let polars_data = PolarsData::new(df, String::from("test"));
+        let corrs = polars_data.clc_corrs(
+            0,
+            vec!["property_1".into(), "property_2".into(), "property_3".into()],
+            CorrMethod::Covariance,
+        ).expect("corrs");
+
+        assert_eq!(corrs._title(), "Covariances");
+
+        let covariance = corrs.get(&"property_1".into(), &"property_2".into()).expect("property_1 property_2");
+        assert_eq!(covariance.measure(), CorrMeasure::Covariance);
+        assert_eq!(covariance.n(), 4);
+        assert!((covariance.value().unwrap() - 10. / 3.).abs() < 1e-5);
+
+        //variances use every non-null value of the column
+        assert!((corrs.get_val(&"property_1".into(), &"property_1".into()).unwrap() - 5. / 3.).abs() < 1e-5);
+        assert!((corrs.get_val(&"property_2".into(), &"property_2".into()).unwrap() - 10.).abs() < 1e-5);
+
+        //covariance cells on their own scale, η and V on theirs
+        assert!((corrs.scale(CorrMeasure::Covariance) - 10.).abs() < 1e-5);
+        assert_eq!(corrs.scale(CorrMeasure::CorrelationRatio), 1.0);
+        assert!((corrs.strength(&"property_1".into(), &"property_2".into()).unwrap() - 1.0).abs() < 1e-5);
+        assert_eq!(corrs.get_measure(&"property_1".into(), &"property_3".into()), Some(CorrMeasure::CorrelationRatio));
+    }
+
+    #[test]
+    fn test_corrs_cluster_order() {
+        let df = df!(
+            "property_1" => &[1.0,  2.0,  3.0,  4.0,  5.0,  6.0,  7.0,  8.0],
+            "property_2" => &[3.0,  1.0,  4.0,  1.0,  5.0,  9.0,  2.0,  6.0],
+            "property_3" => &[2.1,  3.9,  6.2,  8.1,  9.8,  12.2, 13.9, 16.1],
+            "property_4" => &[2.9,  1.2,  4.1,  0.8,  5.2,  8.7,  2.1,  6.3]
+        ).unwrap();
+
+        let polars_data = PolarsData::new(df, String::from("test"));
+        let mut corrs = polars_data.clc_corrs(
+            0,
+            vec!["property_1".into(), "property_2".into(), "property_3".into(), "property_4".into()],
+            CorrMethod::Pearson,
+        ).expect("corrs");
+
+        assert_eq!(corrs.ordered_columns(), vec!["property_1", "property_2", "property_3", "property_4"]);
+
+        corrs.set_clustered(true);
+        let order = corrs.ordered_columns();
+        let position = |c: &str| order.iter().position(|o| o == c).unwrap() as i32;
+        assert_eq!((position("property_1") - position("property_3")).abs(), 1);
+        assert_eq!((position("property_2") - position("property_4")).abs(), 1);
+
+        corrs.set_clustered(false);
+        assert_eq!(corrs.ordered_columns(), vec!["property_1", "property_2", "property_3", "property_4"]);
     }
 
     #[test]
@@ -0,0 +1,522 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+pub const Z_95: f64 = 1.959_963_984_540_054;
+
+pub fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+pub fn std_dev(values: &[f64]) -> Option<f64> {
+    let mean = mean(values)?;
+
+    if values.len() < 2 {
+        None
+    } else {
+        Some((values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64).sqrt())
+    }
+}
+
+//linear interpolation, values must be sorted
+pub fn quantile_sorted(sorted: &[f64], q: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+
+    let pos = q.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let (lo, hi) = (pos.floor() as usize, pos.ceil() as usize);
+
+    Some(sorted[lo] + (sorted[hi] - sorted[lo]) * (pos - lo as f64))
+}
+
+//every n/size-th value, keeps the original order
+pub fn stride_sample(values: &[f64], size: usize) -> Vec<f64> {
+    if values.len() <= size || size == 0 {
+        values.to_vec()
+    } else {
+        let step = values.len() as f64 / size as f64;
+        (0..size).map(|i| values[(i as f64 * step) as usize]).collect()
+    }
+}
+
+//first and last point plus the min and max of each bucket in x order, so peaks and the extremes survive
+pub fn min_max_downsample(points: &[(f64, f64)], max_points: usize) -> Vec<(f64, f64)> {
+    if points.len() <= max_points.max(4) {
+        return points.to_vec();
+    }
+
+    let inner = &points[1..points.len() - 1];
+    let buckets = (max_points.max(4) - 2) / 2;
+    let mut sampled = Vec::with_capacity(buckets * 2 + 2);
+
+    sampled.push(points[0]);
+    for bucket in inner.chunks(inner.len().div_ceil(buckets)) {
+        let (min, max) = bucket.iter().enumerate().skip(1).fold((0, 0), |(min, max), (i, (_, y))| {
+            (if *y < bucket[min].1 { i } else { min }, if *y > bucket[max].1 { i } else { max })
+        });
+
+        match min.cmp(&max) {
+            Ordering::Less => sampled.extend([bucket[min], bucket[max]]),
+            Ordering::Greater => sampled.extend([bucket[max], bucket[min]]),
+            Ordering::Equal => sampled.push(bucket[min]),
+        }
+    }
+    sampled.push(points[points.len() - 1]);
+
+    sampled
+}
+
+pub fn scott_bandwidth(values: &[f64]) -> Option<f64> {
+    let std = std_dev(values)?;
+    Some(1.06 * std * (values.len() as f64).powf(-0.2)).filter(|h| *h > 0.0)
+}
+
+pub fn silverman_bandwidth(values: &[f64]) -> Option<f64> {
+    let std = std_dev(values)?;
+
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let iqr = quantile_sorted(&sorted, 0.75)? - quantile_sorted(&sorted, 0.25)?;
+
+    let spread = if iqr > 0.0 { std.min(iqr / 1.34) } else { std };
+
+    Some(0.9 * spread * (values.len() as f64).powf(-0.2)).filter(|h| *h > 0.0)
+}
+
+//gaussian kernel density on an even grid of points over [from, to]
+pub fn gaussian_kde(values: &[f64], bandwidth: f64, from: f64, to: f64, points: usize) -> Vec<(f64, f64)> {
+    if values.is_empty() || bandwidth <= 0.0 || points < 2 {
+        return vec![];
+    }
+
+    let norm = 1.0 / (values.len() as f64 * bandwidth * (2.0 * PI).sqrt());
+    let step = (to - from) / (points - 1) as f64;
+
+    (0..points)
+        .map(|i| {
+            let x = from + i as f64 * step;
+            let density = values.iter().map(|v| (-0.5 * ((x - v) / bandwidth).powi(2)).exp()).sum::<f64>() * norm;
+            (x, density)
+        })
+        .collect()
+}
+
+//tau-b, Knight's O(n log n) algorithm, None if either side is constant
+pub fn kendall_tau(x: &[f64], y: &[f64]) -> Option<f64> {
+    let mut pairs: Vec<(f64, f64)> = x.iter().copied().zip(y.iter().copied()).collect();
+    let n = pairs.len();
+    if n < 2 {
+        return None;
+    }
+
+    pairs.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+
+    let n0 = (n * (n - 1) / 2) as f64;
+    let tied_x = tied_pairs(&pairs.iter().map(|p| p.0).collect::<Vec<_>>());
+    let tied_xy = tied_pairs(&pairs);
+
+    let mut ys: Vec<f64> = pairs.iter().map(|p| p.1).collect();
+    let swaps = merge_sort_swaps(&mut ys) as f64;
+    let tied_y = tied_pairs(&ys);
+
+    let denominator = ((n0 - tied_x) * (n0 - tied_y)).sqrt();
+    if denominator > 0.0 {
+        Some((n0 - tied_x - tied_y + tied_xy - 2.0 * swaps) / denominator)
+    } else {
+        None
+    }
+}
+
+//number of pairs within runs of equal values, values must be sorted
+fn tied_pairs<T: PartialEq>(sorted: &[T]) -> f64 {
+    let mut total = 0;
+    let mut run = 1;
+
+    for i in 1..sorted.len() {
+        if sorted[i] == sorted[i - 1] {
+            run += 1;
+        } else {
+            total += run * (run - 1) / 2;
+            run = 1;
+        }
+    }
+
+    (total + run * (run - 1) / 2) as f64
+}
+
+//sorts in place, returns the number of inversions
+fn merge_sort_swaps(values: &mut Vec<f64>) -> usize {
+    let n = values.len();
+    if n < 2 {
+        return 0;
+    }
+
+    let mut right = values.split_off(n / 2);
+    let mut swaps = merge_sort_swaps(values) + merge_sort_swaps(&mut right);
+
+    let left = std::mem::take(values);
+    let (mut i, mut j) = (0, 0);
+    values.reserve(n);
+
+    while i < left.len() && j < right.len() {
+        if right[j] < left[i] {
+            values.push(right[j]);
+            swaps += left.len() - i;
+            j += 1;
+        } else {
+            values.push(left[i]);
+            i += 1;
+        }
+    }
+    values.extend_from_slice(&left[i..]);
+    values.extend_from_slice(&right[j..]);
+
+    swaps
+}
+
+//contingency table cells as (row, column, count), None if either side has a single category
+pub fn cramers_v(cells: &[(String, String, f64)]) -> Option<f64> {
+    let mut rows: HashMap<&str, f64> = HashMap::new();
+    let mut columns: HashMap<&str, f64> = HashMap::new();
+
+    for (row, column, count) in cells {
+        *rows.entry(row.as_str()).or_insert(0.0) += count;
+        *columns.entry(column.as_str()).or_insert(0.0) += count;
+    }
+
+    let n = rows.values().sum::<f64>();
+    let k = rows.len().min(columns.len());
+    if n <= 0.0 || k < 2 {
+        return None;
+    }
+
+    //cells missing from the table are zeros and add nothing to the sum
+    let chi2 = n * (cells.iter().map(|(row, column, count)| count * count / (rows[row.as_str()] * columns[column.as_str()])).sum::<f64>() - 1.0);
+
+    Some((chi2.max(0.0) / (n * (k - 1) as f64)).sqrt())
+}
+
+//groups as (count, mean, population variance), None if all values are equal
+pub fn correlation_ratio(groups: &[(f64, f64, f64)]) -> Option<f64> {
+    let n = groups.iter().map(|(count, _, _)| count).sum::<f64>();
+    if n <= 0.0 {
+        return None;
+    }
+
+    let mean = groups.iter().map(|(count, mean, _)| count * mean).sum::<f64>() / n;
+    let between = groups.iter().map(|(count, m, _)| count * (m - mean).powi(2)).sum::<f64>();
+    let within = groups.iter().map(|(count, _, var)| count * var).sum::<f64>();
+
+    if between + within > 0.0 {
+        Some((between / (between + within)).sqrt())
+    } else {
+        None
+    }
+}
+
+//Lanczos approximation, g=7
+pub fn ln_gamma(x: f64) -> f64 {
+    const G: f64 = 7.0;
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    if x < 0.5 {
+        //reflection
+        (PI / (PI * x).sin()).ln() - ln_gamma(1.0 - x)
+    } else {
+        let x = x - 1.0;
+        let t = x + G + 0.5;
+        let sum = COEFFICIENTS.iter().enumerate().skip(1).fold(COEFFICIENTS[0], |sum, (i, c)| sum + c / (x + i as f64));
+
+        0.5 * (2.0 * PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+    }
+}
+
+//I_x(a, b), continued fraction (Numerical Recipes betacf)
+pub fn regularized_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    let front = (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_cf(a, b, x) / a
+    } else {
+        1.0 - front * beta_cf(b, a, 1.0 - x) / b
+    }
+}
+
+fn beta_cf(a: f64, b: f64, x: f64) -> f64 {
+    let tiny = 1e-300;
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    d = 1.0 / if d.abs() < tiny { tiny } else { d };
+    let mut h = d;
+
+    for m in 1..300 {
+        let m = m as f64;
+        for numerator in [
+            m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m)),
+            -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0)),
+        ] {
+            d = 1.0 + numerator * d;
+            d = 1.0 / if d.abs() < tiny { tiny } else { d };
+            c = 1.0 + numerator / c;
+            c = if c.abs() < tiny { tiny } else { c };
+            h *= d * c;
+        }
+
+        if (d * c - 1.0).abs() < 1e-14 {
+            break;
+        }
+    }
+
+    h
+}
+
+//Q(a, x) = 1 - P(a, x), series below a + 1, continued fraction above
+pub fn regularized_gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+
+    let ln_front = a * x.ln() - x - ln_gamma(a);
+
+    if x < a + 1.0 {
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        for n in 1..500 {
+            term *= x / (a + n as f64);
+            sum += term;
+            if term.abs() < sum.abs() * 1e-15 {
+                break;
+            }
+        }
+        1.0 - sum * ln_front.exp()
+    } else {
+        let tiny = 1e-300;
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..500 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            d = 1.0 / if d.abs() < tiny { tiny } else { d };
+            c = b + an / c;
+            c = if c.abs() < tiny { tiny } else { c };
+            h *= d * c;
+            if (d * c - 1.0).abs() < 1e-15 {
+                break;
+            }
+        }
+        ln_front.exp() * h
+    }
+}
+
+//two-sided p-values
+
+pub fn normal_p(z: f64) -> f64 {
+    regularized_gamma_q(0.5, z * z / 2.0)
+}
+
+pub fn student_t_p(t: f64, df: f64) -> f64 {
+    regularized_beta(df / 2.0, 0.5, df / (df + t * t))
+}
+
+//upper tail for the chi-squared and F tests
+
+pub fn chi2_p(chi2: f64, df: f64) -> f64 {
+    regularized_gamma_q(df / 2.0, chi2 / 2.0)
+}
+
+pub fn f_p(f: f64, df_1: f64, df_2: f64) -> f64 {
+    regularized_beta(df_2 / 2.0, df_1 / 2.0, df_2 / (df_2 + df_1 * f))
+}
+
+//95% interval through the Fisher z transform, se is the standard error of z
+pub fn fisher_ci(r: f64, se: f64) -> (f64, f64) {
+    let z = r.clamp(-1.0 + 1e-12, 1.0 - 1e-12).atanh();
+    ((z - Z_95 * se).tanh(), (z + Z_95 * se).tanh())
+}
+
+//agglomerative clustering with average linkage, returns the leaves in dendrogram order
+pub fn average_linkage_order(distances: &[Vec<f64>]) -> Vec<usize> {
+    let mut clusters: Vec<Vec<usize>> = (0..distances.len()).map(|i| vec![i]).collect();
+
+    while clusters.len() > 1 {
+        let mut closest = (0, 1, f64::INFINITY);
+
+        for (a, cluster_a) in clusters.iter().enumerate() {
+            for (b, cluster_b) in clusters.iter().enumerate().skip(a + 1) {
+                let d = cluster_a.iter().flat_map(|i| cluster_b.iter().map(move |j| distances[*i][*j])).sum::<f64>()
+                    / (cluster_a.len() * cluster_b.len()) as f64;
+                if d < closest.2 {
+                    closest = (a, b, d);
+                }
+            }
+        }
+
+        let merged = clusters.remove(closest.1);
+        clusters[closest.0].extend(merged);
+    }
+
+    clusters.pop().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod stats_tests {
+    use crate::stats::{average_linkage_order, chi2_p, correlation_ratio, cramers_v, f_p, fisher_ci, gaussian_kde, kendall_tau, ln_gamma, min_max_downsample, normal_p, student_t_p, quantile_sorted, scott_bandwidth, silverman_bandwidth, stride_sample};
+
+    #[test]
+    fn test_min_max_downsample() {
+        let points = (0..1000).map(|i| (i as f64, (i as f64 / 10.0).sin())).chain([(1000.0, 5.0), (1001.0, -5.0), (1002.0, 0.0)]).collect::<Vec<_>>();
+
+        let sampled = min_max_downsample(&points, 100);
+
+        assert!(sampled.len() <= 100);
+        assert_eq!(sampled.first(), points.first());
+        assert_eq!(sampled.last(), points.last());
+        assert!(sampled.contains(&(1000.0, 5.0)) && sampled.contains(&(1001.0, -5.0)));
+        assert!(sampled.windows(2).all(|w| w[0].0 < w[1].0));
+
+        assert_eq!(min_max_downsample(&points[..50], 100), points[..50].to_vec());
+    }
+
+    #[test]
+    fn test_bandwidth() {
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0];
+
+        assert_eq!(quantile_sorted(&values, 0.25), Some(2.0));
+        assert!((scott_bandwidth(&values).unwrap() - 1.06 * 2.5f64.sqrt() * 5f64.powf(-0.2)).abs() < 1e-9);
+        assert!((silverman_bandwidth(&values).unwrap() - 0.9 * (2.0 / 1.34) * 5f64.powf(-0.2)).abs() < 1e-9);
+        assert_eq!(scott_bandwidth(&[1.0, 1.0, 1.0]), None);
+    }
+
+    #[test]
+    fn test_gaussian_kde() {
+        let kde = gaussian_kde(&[0.0, 1.0, 2.0], 0.5, -5.0, 7.0, 1201);
+
+        let area = kde.windows(2).map(|w| (w[1].0 - w[0].0) * (w[0].1 + w[1].1) / 2.0).sum::<f64>();
+        assert!((area - 1.0).abs() < 1e-3);
+
+        let peak = kde.iter().max_by(|a, b| a.1.total_cmp(&b.1)).unwrap();
+        assert!((peak.0 - 1.0).abs() < 1e-9);
+
+        assert_eq!(stride_sample(&[1.0, 2.0, 3.0, 4.0], 2), vec![1.0, 3.0]);
+    }
+
+    #[test]
+    fn test_kendall_tau() {
+        assert_eq!(kendall_tau(&[1.0, 2.0, 3.0, 4.0], &[10.0, 20.0, 30.0, 40.0]), Some(1.0));
+        assert_eq!(kendall_tau(&[1.0, 2.0, 3.0, 4.0], &[4.0, 3.0, 2.0, 1.0]), Some(-1.0));
+        assert_eq!(kendall_tau(&[1.0, 1.0, 1.0], &[1.0, 2.0, 3.0]), None);
+
+        let x = [1.0, 2.0, 2.0, 3.0, 4.0, 5.0, 5.0, 6.0];
+        let y = [2.0, 1.0, 3.0, 3.0, 5.0, 4.0, 4.0, 1.0];
+
+        //brute force tau-b
+        let (mut concordant, mut discordant, mut only_x, mut only_y) = (0.0f64, 0.0, 0.0, 0.0);
+        for i in 0..x.len() {
+            for j in i + 1..x.len() {
+                let s = (x[i] - x[j]) * (y[i] - y[j]);
+                if s > 0.0 {
+                    concordant += 1.0;
+                } else if s < 0.0 {
+                    discordant += 1.0;
+                } else if x[i] != x[j] {
+                    only_x += 1.0;
+                } else if y[i] != y[j] {
+                    only_y += 1.0;
+                }
+            }
+        }
+        let expected = (concordant - discordant)
+            / ((concordant + discordant + only_x) * (concordant + discordant + only_y)).sqrt();
+
+        assert!((kendall_tau(&x, &y).unwrap() - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_cramers_v() {
+        let cells = |table: &[(&str, &str, f64)]| table.iter().map(|(r, c, n)| (r.to_string(), c.to_string(), *n)).collect::<Vec<_>>();
+
+        assert_eq!(cramers_v(&cells(&[("a", "x", 10.0), ("b", "y", 5.0), ("c", "z", 7.0)])), Some(1.0));
+        assert_eq!(cramers_v(&cells(&[("a", "x", 10.0), ("a", "y", 5.0)])), None);
+
+        //independent: every row has the same column shares
+        assert!(cramers_v(&cells(&[("a", "x", 2.0), ("a", "y", 4.0), ("b", "x", 3.0), ("b", "y", 6.0)])).unwrap() < 1e-9);
+
+        //2x2 reduces to |phi| = |ad - bc| / sqrt(r1 r2 c1 c2)
+        let v = cramers_v(&cells(&[("a", "x", 10.0), ("a", "y", 20.0), ("b", "x", 30.0), ("b", "y", 40.0)])).unwrap();
+        let phi = (10.0 * 40.0 - 20.0 * 30.0f64).abs() / (30.0 * 70.0 * 40.0 * 60.0f64).sqrt();
+        assert!((v - phi).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_correlation_ratio() {
+        assert_eq!(correlation_ratio(&[(3.0, 1.0, 0.0), (2.0, 5.0, 0.0)]), Some(1.0));
+        assert_eq!(correlation_ratio(&[(3.0, 2.0, 1.0), (2.0, 2.0, 4.0)]), Some(0.0));
+        assert_eq!(correlation_ratio(&[(3.0, 2.0, 0.0)]), None);
+
+        //groups [1, 3] and [5, 7]: between 16, within 4
+        assert!((correlation_ratio(&[(2.0, 2.0, 1.0), (2.0, 6.0, 1.0)]).unwrap() - 0.8f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_p_values() {
+        assert!((ln_gamma(5.0) - 24f64.ln()).abs() < 1e-12);
+        assert!((ln_gamma(0.5) - std::f64::consts::PI.sqrt().ln()).abs() < 1e-12);
+
+        assert!((normal_p(1.959964) - 0.05).abs() < 1e-6);
+        assert!((student_t_p(2.0, 10.0) - 0.073388).abs() < 1e-6);
+        //cauchy
+        assert!((student_t_p(1.0, 1.0) - 0.5).abs() < 1e-12);
+        assert!((chi2_p(3.841459, 1.0) - 0.05).abs() < 1e-6);
+        assert!((chi2_p(4.0, 2.0) - (-2f64).exp()).abs() < 1e-12);
+        assert!((f_p(4.0, 2.0, 10.0) - 1.8f64.powi(-5)).abs() < 1e-12);
+
+        let (lo, hi) = fisher_ci(0.5, 0.1);
+        assert!(lo < 0.5 && 0.5 < hi);
+        assert!((lo.atanh() + hi.atanh() - 2.0 * 0.5f64.atanh()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_average_linkage_order() {
+        //0 and 2 are close, 1 and 3 are close
+        let distances = vec![
+            vec![0.0, 0.9, 0.1, 0.8],
+            vec![0.9, 0.0, 0.7, 0.2],
+            vec![0.1, 0.7, 0.0, 0.9],
+            vec![0.8, 0.2, 0.9, 0.0],
+        ];
+
+        let order = average_linkage_order(&distances);
+        let position = |i: usize| order.iter().position(|o| *o == i).unwrap() as i32;
+
+        assert_eq!(order.len(), 4);
+        assert_eq!((position(0) - position(2)).abs(), 1);
+        assert_eq!((position(1) - position(3)).abs(), 1);
+
+        assert_eq!(average_linkage_order(&[]), Vec::<usize>::new());
+    }
+}
@@ -1,7 +1,7 @@
 use std::collections::HashSet;
 
 use crate::data_type::WapukuDataType;
-use crate::model::{ColumnsPlot, Corrs, Data, DataBounds, DataGroup, DataLump, DataProperty, Filter, FilteredFame, GroupsGrid, Histogram, PropertiesSet, Property, PropertyRange, SimpleDataGroup, SimplePropertiesSet, Summary, SummaryColumn, WapukuError};
+use crate::model::{Bandwidth, BoxPlot, ColumnsPlot, CorrMethod, Corrs, Data, DataBounds, DataGroup, DataLump, DataQuality, DataProperty, Filter, FilteredFame, GroupByAgg, GroupsGrid, Histogram, Histogram2D, HistogramParams, Kde, OutlierMethod, Outliers, PairPlot, Pivot, PivotAgg, ValueCounts, ValueCountsSort, PropertiesSet, Scatter, ScatterParams, Property, PropertyRange, SimpleDataGroup, SimplePropertiesSet, Summary, SummaryColumn, TimeSeries, TimeSeriesParams, WapukuError};
 
 
 #[derive(Debug)]
@@ -90,7 +90,7 @@ impl  Data for TestData {
         todo!()
     }
 
-    fn build_histogram(&self, frame_id: u128, column: String, bins: Option<usize>) -> Result<Histogram, WapukuError> {
+    fn build_histogram(&self, frame_id: u128, column: String, params: HistogramParams) -> Result<Histogram, WapukuError> {
         todo!()
     }
 
@@ -106,11 +106,55 @@ impl  Data for TestData {
         todo!()
     }
 
-    fn clc_corrs(&self, frame_id: u128, columns: Vec<String>) -> Result<Corrs, WapukuError> {
+    fn clc_corrs(&self, frame_id: u128, columns: Vec<String>, method: CorrMethod) -> Result<Corrs, WapukuError> {
         todo!()
     }
 
-    fn plot_columns(&self, frame_id: u128, columns: Vec<String>) -> Result<ColumnsPlot, WapukuError> {
+    fn plot_columns(&self, frame_id: u128, columns: Vec<String>, max_points: usize) -> Result<ColumnsPlot, WapukuError> {
+        todo!()
+    }
+
+    fn kde(&self, frame_id: u128, column: String, bandwidth: Bandwidth, split: Option<String>) -> Result<Kde, WapukuError> {
+        todo!()
+    }
+
+    fn build_histogram_2d(&self, frame_id: u128, column_x: String, column_y: String, bins_x: usize, bins_y: usize) -> Result<Histogram2D, WapukuError> {
+        todo!()
+    }
+
+    fn build_scatter(&self, frame_id: u128, params: ScatterParams) -> Result<Scatter, WapukuError> {
+        todo!()
+    }
+
+    fn build_box_plot(&self, frame_id: u128, column: String, split_by: Option<String>) -> Result<BoxPlot, WapukuError> {
+        todo!()
+    }
+
+    fn build_time_series(&self, frame_id: u128, params: TimeSeriesParams) -> Result<TimeSeries, WapukuError> {
+        todo!()
+    }
+
+    fn build_pair_plot(&self, frame_id: u128, columns: Vec<String>) -> Result<PairPlot, WapukuError> {
+        todo!()
+    }
+
+    fn check_quality(&self, frame_id: u128) -> Result<DataQuality, WapukuError> {
+        todo!()
+    }
+
+    fn find_outliers(&self, frame_id: u128, method: OutlierMethod) -> Result<Outliers, WapukuError> {
+        todo!()
+    }
+
+    fn value_counts(&self, frame_id: u128, column: String, sort: ValueCountsSort, offset: usize, limit: usize) -> Result<ValueCounts, WapukuError> {
+        todo!()
+    }
+
+    fn pivot(&self, frame_id: u128, row_column: String, column_column: String, value_column: String, agg: PivotAgg) -> Result<Pivot, WapukuError> {
+        todo!()
+    }
+
+    fn group_by(&self, frame_id: u128, keys: Vec<String>, aggs: Vec<(String, GroupByAgg)>) -> Result<FilteredFame, WapukuError> {
         todo!()
     }
 }
@@ -1,10 +1,12 @@
 use std::borrow::Cow;
+use chrono::DateTime;
 use once_cell::sync::Lazy;
 use regex::{Captures, Regex, Replacer};
 
 pub struct FloatReformatter;
 
 static RE:Lazy<Regex> =  Lazy::new(|| Regex::new(r"(?<num>\d+\.\d+)").expect("FloatReformatter regexp"));
+static ID_RE:Lazy<Regex> =  Lazy::new(|| Regex::new(r"(?i)(^id$|_id$|^id_|uuid|guid)").expect("id column regexp"));
 
 impl Replacer for FloatReformatter {
     fn replace_append(&mut self, caps: &Captures<'_>, dst: &mut String) {
@@ -19,6 +21,11 @@ impl FloatReformatter {
 }
 
 
+pub fn looks_like_id(column_name: &str) -> bool {
+    ID_RE.is_match(column_name)
+}
+
+
 pub fn val_or_na(v: &String) -> impl ToString + Sized + '_{
     if v.is_empty() {
         "n/a"
@@ -27,9 +34,25 @@ pub fn val_or_na(v: &String) -> impl ToString + Sized + '_{
     }
 }
 
+//epoch milliseconds as a date, as precise as the step between neighbouring labels needs
+pub fn format_timestamp_ms(ms: f64, step_ms: f64) -> String {
+    const DAY_MS: f64 = 24.0 * 60.0 * 60.0 * 1000.0;
+
+    let format = match step_ms {
+        s if s < DAY_MS => "%Y-%m-%d %H:%M",
+        s if s < 28.0 * DAY_MS => "%Y-%m-%d",
+        s if s < 365.0 * DAY_MS => "%Y-%m",
+        _ => "%Y",
+    };
+
+    DateTime::from_timestamp_millis(ms.round() as i64)
+        .map(|d| d.naive_utc().format(format).to_string())
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod util_tests {
-    use crate::utils::FloatReformatter;
+    use crate::utils::{format_timestamp_ms, looks_like_id, FloatReformatter};
 
     #[test]
     fn test_fix_numeric_label() {
@@ -38,4 +61,25 @@ mod util_tests {
         assert_eq!(FloatReformatter::exec("(1.2000000000000002, 1.6]"), "(1.20, 1.60]");
         assert_eq!(FloatReformatter::exec("(2.4000000000000004, 2.8000000000000003]"), "(2.40, 2.80]");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_format_timestamp_ms() {
+        let ms = 1_672_576_200_000.0; //2023-01-01 12:30 UTC
+        let day = 24.0 * 60.0 * 60.0 * 1000.0;
+
+        assert_eq!(format_timestamp_ms(ms, 0.0), "2023-01-01 12:30");
+        assert_eq!(format_timestamp_ms(ms, day), "2023-01-01");
+        assert_eq!(format_timestamp_ms(ms, 30.0 * day), "2023-01");
+        assert_eq!(format_timestamp_ms(ms, 365.0 * day), "2023");
+    }
+
+    #[test]
+    fn test_looks_like_id() {
+        assert!(looks_like_id("id"));
+        assert!(looks_like_id("user_id"));
+        assert!(looks_like_id("ID_CLIENT"));
+        assert!(looks_like_id("session_uuid"));
+        assert!(!looks_like_id("paid"));
+        assert!(!looks_like_id("identity"));
+    }
+}